@@ -2,10 +2,20 @@
 
 use super::*;
 
+use crate::commitment;
+use crate::rbac::Role;
+use crate::structs::{AllowedCall, Entity, GenesisPolicy, Permission2Role, PolicyOp, Role2User};
 #[allow(unused)]
 use crate::Pallet as RBAC;
 use frame_benchmarking::v1::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::{
+    traits::{Currency, GetCallMetadata},
+    BoundedVec,
+};
 use frame_system::{Pallet as System, RawOrigin};
+use sp_core::H256;
+use sp_runtime::traits::{Bounded, Convert};
+use sp_std::{boxed::Box, vec, vec::Vec};
 
 /// Assert that the last event equals the provided one.
 fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
@@ -24,6 +34,33 @@ const ADMIN_STR: &[u8] = b"ADMIN";
 const GROUP_STR: &[u8] = b"Users";
 const PERM_STR: &[u8] = b"CAN_DELETE";
 
+/// Key differing from the zero key at its `bit`th bit only.
+fn tree_key(bit: u32) -> H256 {
+    let mut key = H256::zero();
+    key.0[bit as usize / 8] = 0x80 >> (bit % 8);
+    key
+}
+
+/// Name as taken by the dispatchables.
+fn bounded_name<T: Config>(name: &[u8]) -> BoundedVec<u8, T::MaxNameLength> {
+    name.to_vec().try_into().expect("benchmark names are short")
+}
+
+/// Snapshot label as taken by the dispatchables.
+fn bounded_label<T: Config>(label: &[u8]) -> BoundedVec<u8, T::MaxSnapshotLabelLength> {
+    label
+        .to_vec()
+        .try_into()
+        .expect("benchmark labels are short")
+}
+
+/// Derives a distinct entity id from a base id and an index.
+fn indexed_id(base: [u8; 32], index: u32) -> [u8; 32] {
+    let mut id = base;
+    id[..4].copy_from_slice(&index.to_le_bytes());
+    id
+}
+
 benchmarks! {
     where_clause { where
         T: Config<EntityId = [u8; 32]>
@@ -31,7 +68,7 @@ benchmarks! {
 
     add_role {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-    }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone(), ADMIN_STR.to_vec())
+    }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))
     verify {
         assert_last_event::<T>(Event::<T>::RoleAdded(
             caller.clone(),
@@ -42,8 +79,8 @@ benchmarks! {
 
     update_role {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
-    }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone(), ADMIN_STR.to_vec())
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+    }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))
     verify {
         assert_last_event::<T>(Event::<T>::RoleUpdated(
             caller.clone(),
@@ -54,7 +91,7 @@ benchmarks! {
 
     disable_role {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone())
     verify {
         assert_last_event::<T>(Event::<T>::RoleRemoved(
@@ -65,18 +102,18 @@ benchmarks! {
 
     fetch_role {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone(), ROLE_ID.clone())
 
     fetch_roles {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID2.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID2.clone(), bounded_name::<T>(ADMIN_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone())
 
     assign_role_to_user {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone(), USER_ID.clone())
     verify {
         assert_last_event::<T>(Event::<T>::RoleAssignedToUser(
@@ -88,7 +125,7 @@ benchmarks! {
 
     unassign_role_to_user {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_role_to_user(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), USER_ID.clone())?;
     }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone(), USER_ID.clone())
     verify {
@@ -101,8 +138,8 @@ benchmarks! {
 
     assign_role_to_group {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
-        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
 
     }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone(), GROUP_ID.clone())
     verify {
@@ -115,8 +152,8 @@ benchmarks! {
 
     unassign_role_to_group {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
-        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_role_to_group(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), GROUP_ID.clone())?;
     }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone(), GROUP_ID.clone())
     verify {
@@ -129,13 +166,13 @@ benchmarks! {
 
     fetch_user_roles {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_role_to_user(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), USER_ID.clone())?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone(), USER_ID.clone())
 
     add_permission {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-    }: _(RawOrigin::Signed(caller.clone()), PERMISSION_ID.clone(), PERM_STR.to_vec())
+    }: _(RawOrigin::Signed(caller.clone()), PERMISSION_ID.clone(), bounded_name::<T>(PERM_STR))
     verify {
         assert_last_event::<T>(Event::<T>::PermissionAdded(
             caller.clone(),
@@ -147,8 +184,8 @@ benchmarks! {
     update_permission {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
         RBAC::<T>::add_permission(
-            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), PERM_STR.to_vec())?;
-    }: _(RawOrigin::Signed(caller.clone()), PERMISSION_ID.clone(), PERM_STR.to_vec())
+            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(PERM_STR))?;
+    }: _(RawOrigin::Signed(caller.clone()), PERMISSION_ID.clone(), bounded_name::<T>(PERM_STR))
     verify {
         assert_last_event::<T>(Event::<T>::PermissionUpdated(
             caller.clone(),
@@ -160,7 +197,7 @@ benchmarks! {
     disable_permission {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
         RBAC::<T>::add_permission(
-            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), PERM_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(PERM_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), PERMISSION_ID.clone())
     verify {
         assert_last_event::<T>(Event::<T>::PermissionDisabled(
@@ -172,22 +209,22 @@ benchmarks! {
     fetch_permission {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
         RBAC::<T>::add_permission(
-            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), PERM_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(PERM_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone(), PERMISSION_ID.clone())
 
     fetch_permissions {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
         RBAC::<T>::add_permission(
-            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), PERM_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(PERM_STR))?;
         RBAC::<T>::add_permission(
-            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID2.clone(), PERM_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID2.clone(), bounded_name::<T>(PERM_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone())
 
     assign_permission_to_role {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::add_permission(
-            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), ADMIN_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), PERMISSION_ID.clone(), ROLE_ID.clone())
     verify {
         assert_last_event::<T>(Event::<T>::PermissionAssigned(
@@ -199,9 +236,9 @@ benchmarks! {
 
     unassign_permission_to_role {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::add_permission(
-            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), ADMIN_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_permission_to_role(
             RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), ROLE_ID.clone())?;
     }: _(RawOrigin::Signed(caller.clone()), PERMISSION_ID.clone(), ROLE_ID.clone())
@@ -215,16 +252,16 @@ benchmarks! {
 
     fetch_role_permissions {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::add_permission(
-            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), ADMIN_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_permission_to_role(
             RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), ROLE_ID.clone())?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone(), ROLE_ID.clone())
 
     add_group {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-    }: _(RawOrigin::Signed(caller.clone()), GROUP_ID.clone(), GROUP_STR.to_vec())
+    }: _(RawOrigin::Signed(caller.clone()), GROUP_ID.clone(), bounded_name::<T>(GROUP_STR))
     verify {
         assert_last_event::<T>(Event::<T>::GroupAdded(
             caller.clone(),
@@ -236,8 +273,8 @@ benchmarks! {
     update_group {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
         RBAC::<T>::add_group(
-            RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), GROUP_STR.to_vec())?;
-    }: _(RawOrigin::Signed(caller.clone()), GROUP_ID.clone(), GROUP_STR.to_vec())
+            RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(GROUP_STR))?;
+    }: _(RawOrigin::Signed(caller.clone()), GROUP_ID.clone(), bounded_name::<T>(GROUP_STR))
     verify {
         assert_last_event::<T>(Event::<T>::GroupUpdated(
             caller.clone(),
@@ -249,7 +286,7 @@ benchmarks! {
     disable_group {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
         RBAC::<T>::add_group(
-            RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), GROUP_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(GROUP_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), GROUP_ID.clone())
     verify {
         assert_last_event::<T>(Event::<T>::GroupDisabled(
@@ -261,20 +298,20 @@ benchmarks! {
     fetch_group {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
         RBAC::<T>::add_group(
-            RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), GROUP_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(GROUP_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone(), GROUP_ID.clone())
 
     fetch_groups {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
         RBAC::<T>::add_group(
-            RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), GROUP_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(GROUP_STR))?;
         RBAC::<T>::add_group(
-            RawOrigin::Signed(caller.clone()).into(), GROUP_ID2.clone(), GROUP_STR.to_vec())?;
+            RawOrigin::Signed(caller.clone()).into(), GROUP_ID2.clone(), bounded_name::<T>(GROUP_STR))?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone())
 
     assign_user_to_group {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), GROUP_STR.to_vec())?;
+        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(GROUP_STR))?;
 
     }: _(RawOrigin::Signed(caller.clone()), USER_ID.clone(), GROUP_ID.clone())
     verify {
@@ -287,7 +324,7 @@ benchmarks! {
 
     unassign_user_to_group {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_user_to_group(RawOrigin::Signed(caller.clone()).into(), USER_ID.clone(), GROUP_ID.clone())?;
     }: _(RawOrigin::Signed(caller.clone()), USER_ID.clone(), GROUP_ID.clone())
     verify {
@@ -300,15 +337,15 @@ benchmarks! {
 
     fetch_user_groups {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_user_to_group(RawOrigin::Signed(caller.clone()).into(), USER_ID.clone(), GROUP_ID.clone())?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone(), USER_ID.clone())
 
     fetch_user_permissions {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
-        RBAC::<T>::add_permission(RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), ADMIN_STR.to_vec())?;
-        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        RBAC::<T>::add_permission(RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_user_to_group(RawOrigin::Signed(caller.clone()).into(), USER_ID.clone(), GROUP_ID.clone())?;
         RBAC::<T>::assign_role_to_group(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), GROUP_ID.clone())?;
         RBAC::<T>::assign_permission_to_role(
@@ -317,9 +354,9 @@ benchmarks! {
 
     fetch_group_permissions {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
-        RBAC::<T>::add_permission(RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), ADMIN_STR.to_vec())?;
-        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        RBAC::<T>::add_permission(RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_role_to_group(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), GROUP_ID.clone())?;
         RBAC::<T>::assign_permission_to_role(
             RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), ROLE_ID.clone())?;
@@ -327,10 +364,254 @@ benchmarks! {
 
     fetch_group_roles {
         let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
-        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), ADMIN_STR.to_vec())?;
-        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), ADMIN_STR.to_vec())?;
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
         RBAC::<T>::assign_role_to_group(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), GROUP_ID.clone())?;
     }: _(RawOrigin::Signed(caller.clone()), caller.clone(), GROUP_ID.clone())
+
+    clone_role {
+        let p in 1 .. T::MaxCloneAssignments::get();
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        let mut permissions = vec![];
+        for i in 0 .. p {
+            let permission_id = indexed_id(PERMISSION_ID, i);
+            RBAC::<T>::add_permission(RawOrigin::Signed(caller.clone()).into(), permission_id, bounded_name::<T>(PERM_STR))?;
+            RBAC::<T>::assign_permission_to_role(
+                RawOrigin::Signed(caller.clone()).into(), permission_id, ROLE_ID.clone())?;
+            permissions.push(permission_id);
+        }
+    }: _(RawOrigin::Signed(caller.clone()), ROLE_ID.clone(), ROLE_ID2.clone(), bounded_name::<T>(ADMIN_STR))
+    verify {
+        assert_last_event::<T>(Event::<T>::RoleCloned(
+            caller.clone(),
+            ROLE_ID.clone(),
+            ROLE_ID2.clone(),
+            ADMIN_STR.to_vec(),
+            permissions,
+        ).into());
+    }
+
+    clone_group {
+        let r in 1 .. T::MaxCloneAssignments::get();
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        RBAC::<T>::add_group(RawOrigin::Signed(caller.clone()).into(), GROUP_ID.clone(), bounded_name::<T>(GROUP_STR))?;
+        let mut roles = vec![];
+        for i in 0 .. r {
+            let role_id = indexed_id(ROLE_ID, i);
+            RBAC::<T>::add_role(RawOrigin::Signed(caller.clone()).into(), role_id, bounded_name::<T>(ADMIN_STR))?;
+            RBAC::<T>::assign_role_to_group(RawOrigin::Signed(caller.clone()).into(), role_id, GROUP_ID.clone())?;
+            roles.push(role_id);
+        }
+    }: _(RawOrigin::Signed(caller.clone()), GROUP_ID.clone(), GROUP_ID2.clone(), bounded_name::<T>(GROUP_STR))
+    verify {
+        assert_last_event::<T>(Event::<T>::GroupCloned(
+            caller.clone(),
+            GROUP_ID.clone(),
+            GROUP_ID2.clone(),
+            GROUP_STR.to_vec(),
+            roles,
+        ).into());
+    }
+
+    set_permission_calls {
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        RBAC::<T>::add_permission(RawOrigin::Signed(caller.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(PERM_STR))?;
+        let calls: Vec<AllowedCall> = (0 .. T::MaxAllowedCalls::get()).map(|i| AllowedCall {
+            pallet_name: b"System".to_vec(),
+            function_name: indexed_id(PERMISSION_ID, i).to_vec(),
+        }).collect();
+    }: _(RawOrigin::Signed(caller.clone()), PERMISSION_ID.clone(), calls.clone().try_into().unwrap())
+    verify {
+        assert_last_event::<T>(Event::<T>::PermissionCallsSet(
+            caller.clone(),
+            PERMISSION_ID.clone(),
+            calls,
+        ).into());
+    }
+
+    dispatch_with_permission {
+        let l in 1 .. T::MaxDispatchLookups::get();
+        let owner : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 1, 0);
+        let user_id = T::DispatchUserMapper::convert(caller.clone());
+        let call: <T as Config>::RuntimeCall = frame_system::Call::<T>::remark { remark: vec![] }.into();
+        let metadata = call.get_call_metadata();
+        let allowed = AllowedCall {
+            pallet_name: metadata.pallet_name.as_bytes().to_vec(),
+            function_name: metadata.function_name.as_bytes().to_vec(),
+        };
+        RBAC::<T>::add_role(RawOrigin::Signed(owner.clone()).into(), ROLE_ID.clone(), bounded_name::<T>(ADMIN_STR))?;
+        RBAC::<T>::add_permission(RawOrigin::Signed(owner.clone()).into(), PERMISSION_ID.clone(), bounded_name::<T>(PERM_STR))?;
+        RBAC::<T>::assign_permission_to_role(
+            RawOrigin::Signed(owner.clone()).into(), PERMISSION_ID.clone(), ROLE_ID.clone())?;
+        // Only the last of the user's roles grants the permission
+        for i in 1 .. l {
+            let role_id = indexed_id(ROLE_ID2, i);
+            RBAC::<T>::add_role(RawOrigin::Signed(owner.clone()).into(), role_id, bounded_name::<T>(ADMIN_STR))?;
+            RBAC::<T>::assign_role_to_user(RawOrigin::Signed(owner.clone()).into(), role_id, user_id)?;
+        }
+        RBAC::<T>::assign_role_to_user(RawOrigin::Signed(owner.clone()).into(), ROLE_ID.clone(), user_id)?;
+        RBAC::<T>::set_permission_calls(
+            RawOrigin::Signed(owner.clone()).into(), PERMISSION_ID.clone(), vec![allowed].try_into().unwrap())?;
+    }: _(RawOrigin::Signed(caller.clone()), owner.clone(), PERMISSION_ID.clone(), Box::new(call))
+    verify {
+        assert_last_event::<T>(Event::<T>::DispatchedWithPermission(
+            caller.clone(),
+            owner.clone(),
+            PERMISSION_ID.clone(),
+            Ok(()),
+        ).into());
+    }
+
+    publish_policy_root {
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        let root = H256::repeat_byte(1);
+    }: _(RawOrigin::Signed(caller.clone()), root)
+    verify {
+        assert_last_event::<T>(Event::<T>::PolicyRootPublished(caller.clone(), root).into());
+    }
+
+    verify_access_proof {
+        let owner : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 1, 0);
+        let entity = |id| Entity { id, name: PERM_STR.to_vec(), enabled: true };
+        // Unrelated permissions deepen the tree
+        let mut permissions: Vec<Entity<[u8; 32]>> = (0 .. 1024)
+            .map(|i| entity(indexed_id(PERMISSION_ID2, i)))
+            .collect();
+        permissions.push(entity(PERMISSION_ID));
+        let policy = GenesisPolicy {
+            roles: vec![entity(ROLE_ID)],
+            permissions,
+            role2users: vec![Role2User { role: ROLE_ID, user: USER_ID }],
+            permission2roles: vec![Permission2Role { permission: PERMISSION_ID, role: ROLE_ID }],
+            ..Default::default()
+        };
+        let proof = commitment::access_proof(&policy, &USER_ID, &PERMISSION_ID).unwrap();
+        RBAC::<T>::publish_policy_root(
+            RawOrigin::Signed(owner.clone()).into(), commitment::policy_root(&policy))?;
+    }: _(RawOrigin::Signed(caller.clone()), owner.clone(), USER_ID.clone(), PERMISSION_ID.clone(), proof)
+    verify {
+        assert_last_event::<T>(Event::<T>::AccessAttested(
+            caller.clone(),
+            owner.clone(),
+            USER_ID.clone(),
+            PERMISSION_ID.clone(),
+            ROLE_ID.clone(),
+        ).into());
+    }
+
+    snapshot_policy {
+        let n in 1 .. T::MaxSnapshotItems::get();
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+        for i in 0 .. n {
+            RBAC::<T>::create_role(&caller, indexed_id(ROLE_ID, i), ADMIN_STR)
+                .map_err(|_| "cannot create role")?;
+        }
+    }: _(RawOrigin::Signed(caller.clone()), bounded_label::<T>(ADMIN_STR))
+    verify {
+        assert_last_event::<T>(Event::<T>::PolicySnapshotted(
+            caller.clone(),
+            0,
+            ADMIN_STR.to_vec(),
+            n,
+        ).into());
+    }
+
+    rollback_policy {
+        let n in 1 .. T::MaxSnapshotItems::get();
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+        for i in 0 .. n {
+            RBAC::<T>::create_role(&caller, indexed_id(ROLE_ID, i), ADMIN_STR)
+                .map_err(|_| "cannot create role")?;
+        }
+        RBAC::<T>::snapshot_policy(RawOrigin::Signed(caller.clone()).into(), bounded_label::<T>(ADMIN_STR))?;
+        for i in 0 .. n {
+            RBAC::<T>::disable_existing_role(&caller, indexed_id(ROLE_ID, i))
+                .map_err(|_| "cannot disable role")?;
+        }
+    }: _(RawOrigin::Signed(caller.clone()), 0)
+    verify {
+        assert_last_event::<T>(Event::<T>::RollbackStarted(caller.clone(), 0, n).into());
+    }
+
+    cancel_rollback {
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+        RBAC::<T>::create_role(&caller, ROLE_ID, ADMIN_STR).map_err(|_| "cannot create role")?;
+        RBAC::<T>::snapshot_policy(RawOrigin::Signed(caller.clone()).into(), bounded_label::<T>(ADMIN_STR))?;
+        RBAC::<T>::disable_existing_role(&caller, ROLE_ID).map_err(|_| "cannot disable role")?;
+        RBAC::<T>::rollback_policy(RawOrigin::Signed(caller.clone()).into(), 0)?;
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert_last_event::<T>(Event::<T>::RollbackCancelled(caller.clone(), 0, 0).into());
+    }
+
+    remove_snapshot {
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+        RBAC::<T>::snapshot_policy(RawOrigin::Signed(caller.clone()).into(), bounded_label::<T>(ADMIN_STR))?;
+    }: _(RawOrigin::Signed(caller.clone()), 0)
+    verify {
+        assert_last_event::<T>(Event::<T>::SnapshotRemoved(caller.clone(), 0).into());
+    }
+
+    stage_policy_ops {
+        let n in 1 .. T::MaxDraftOps::get();
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        let staged: Vec<_> = (1 .. n)
+            .map(|i| PolicyOp::AddRole(indexed_id(ROLE_ID, i), ADMIN_STR.to_vec()))
+            .collect();
+        <PolicyDraftStore<T>>::insert(&caller, staged);
+        let ops = vec![PolicyOp::AddRole(indexed_id(ROLE_ID, 0), ADMIN_STR.to_vec())];
+    }: _(RawOrigin::Signed(caller.clone()), ops.try_into().unwrap())
+    verify {
+        assert_last_event::<T>(Event::<T>::DraftStaged(caller.clone(), 1, n).into());
+    }
+
+    publish_draft {
+        let n in 1 .. T::MaxDraftOps::get();
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        let draft: Vec<_> = (0 .. n)
+            .map(|i| PolicyOp::AddRole(indexed_id(ROLE_ID, i), ADMIN_STR.to_vec()))
+            .collect();
+        <PolicyDraftStore<T>>::insert(&caller, draft);
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert_last_event::<T>(Event::<T>::DraftPublished(caller.clone(), n).into());
+    }
+
+    discard_draft {
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        <PolicyDraftStore<T>>::insert(
+            &caller,
+            vec![PolicyOp::AddRole(indexed_id(ROLE_ID, 0), ADMIN_STR.to_vec())],
+        );
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert_last_event::<T>(Event::<T>::DraftDiscarded(caller.clone()).into());
+    }
+
+    update_policy_tree {
+        let d in 1 .. T::MaxPolicyTreeDepth::get();
+        let caller : T::AccountId = account(CALLER_ACCOUNT_STR, 0, 0);
+        // Each key splits from the zero key at a later bit, putting it d branches deep
+        for i in 0 .. d {
+            RBAC::<T>::update_policy_tree(&caller, None, Some(tree_key(i)))
+                .map_err(|_| "cannot insert leaf")?;
+        }
+        RBAC::<T>::update_policy_tree(&caller, None, Some(H256::zero()))
+            .map_err(|_| "cannot insert leaf")?;
+    }: {
+        RBAC::<T>::update_policy_tree(&caller, Some(H256::zero()), Some(H256::zero()))
+            .map_err(|_| "cannot replace leaf")?;
+    }
+    verify {
+        assert_eq!(RBAC::<T>::policy_tree_root_of(&caller).map(|root| root.height()), Some(d));
+    }
 }
 
 impl_benchmark_test_suite!(RBAC, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,511 @@
+//! Commitment to an owner's whole RBAC policy.
+//!
+//! Every entity and assignment of an owner is a leaf of a binary crit-bit tree, keyed by its
+//! leaf hash: the blake2-256 hash of [`LEAF_PREFIX`] and the SCALE encoded [`PolicyLeaf`].
+//! A branch splits its keys at their first differing bit, the critical bit, into those with
+//! the bit unset on the left and set on the right. Its hash covers [`NODE_PREFIX`], the
+//! critical bit and both children's hashes, so no branch can be passed off as a leaf. The
+//! shape of the tree only depends on its leaves, never on the order they were added in,
+//! and adding or removing a leaf only touches the branches on its path. The root of an
+//! empty policy is zero.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_core::{ConstU32, RuntimeDebug, H256};
+use sp_io::hashing::blake2_256;
+use sp_runtime::BoundedVec;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+use crate::structs::{Entity, GenesisPolicy, Permission2Role, Role2Group, Role2User, User2Group};
+
+/// Item of an owner's policy which is committed to
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub enum PolicyLeaf<EntityId> {
+    Role(Entity<EntityId>),
+    Permission(Entity<EntityId>),
+    Group(Entity<EntityId>),
+    Role2User(Role2User<EntityId>),
+    Role2Group(Role2Group<EntityId>),
+    User2Group(User2Group<EntityId>),
+    Permission2Role(Permission2Role<EntityId>),
+}
+
+/// First byte hashed for a leaf
+pub const LEAF_PREFIX: u8 = 0;
+/// First byte hashed for a branch
+pub const NODE_PREFIX: u8 = 1;
+
+impl<EntityId: Encode> PolicyLeaf<EntityId> {
+    /// Leaf hash of this item, its key in the tree
+    pub fn hash(&self) -> H256 {
+        hash_leaf(&self.encode())
+    }
+}
+
+/// Hash of a leaf with the given encoding
+pub fn hash_leaf(encoded: &[u8]) -> H256 {
+    let mut bytes = Vec::with_capacity(1 + encoded.len());
+    bytes.push(LEAF_PREFIX);
+    bytes.extend_from_slice(encoded);
+    H256(blake2_256(&bytes))
+}
+
+/// Hash of a branch splitting its keys at `bit`
+pub fn hash_node(bit: u8, left: &H256, right: &H256) -> H256 {
+    let mut bytes = [0u8; 66];
+    bytes[0] = NODE_PREFIX;
+    bytes[1] = bit;
+    bytes[2..34].copy_from_slice(left.as_bytes());
+    bytes[34..].copy_from_slice(right.as_bytes());
+    H256(blake2_256(&bytes))
+}
+
+/// Whether bit `bit` of the key is set, bit 0 being the most significant bit of the first
+/// byte
+fn bit_at(key: &H256, bit: u8) -> bool {
+    key.0[bit as usize / 8] & (0x80 >> (bit % 8)) != 0
+}
+
+/// The key with bit `bit` and all following bits unset
+fn prefix(key: &H256, bit: u8) -> H256 {
+    let mut prefix = *key;
+    let byte = bit as usize / 8;
+    prefix.0[byte] &= !(0xffu8 >> (bit % 8));
+    prefix.0[byte + 1..].iter_mut().for_each(|b| *b = 0);
+    prefix
+}
+
+/// First bit the two keys differ in, if they do
+fn crit_bit(a: &H256, b: &H256) -> Option<u8> {
+    a.0.iter()
+        .zip(b.0.iter())
+        .enumerate()
+        .find(|(_, (a, b))| a != b)
+        .map(|(i, (a, b))| (i * 8) as u8 + (a ^ b).leading_zeros() as u8)
+}
+
+/// Identifies a branch within an owner's tree: its critical bit and the bits all of its
+/// keys share in front of it
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct NodeId {
+    pub bit: u8,
+    pub prefix: H256,
+}
+
+/// Reference to a leaf or a branch
+#[derive(Clone, Copy, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub enum TreeChild {
+    Leaf(H256),
+    Branch {
+        id: NodeId,
+        hash: H256,
+        /// Number of branches on the longest path down to a leaf, including this one
+        height: u32,
+    },
+}
+
+impl TreeChild {
+    pub fn hash(&self) -> H256 {
+        match self {
+            TreeChild::Leaf(key) => *key,
+            TreeChild::Branch { hash, .. } => *hash,
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        match self {
+            TreeChild::Leaf(_) => 0,
+            TreeChild::Branch { height, .. } => *height,
+        }
+    }
+}
+
+/// Branch of the tree
+#[derive(Clone, Copy, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct TreeNode {
+    /// Subtree of the keys with the critical bit unset
+    pub left: TreeChild,
+    /// Subtree of the keys with the critical bit set
+    pub right: TreeChild,
+}
+
+impl TreeNode {
+    fn child(&self, right: bool) -> &TreeChild {
+        if right {
+            &self.right
+        } else {
+            &self.left
+        }
+    }
+
+    fn set_child(&mut self, right: bool, child: TreeChild) {
+        if right {
+            self.right = child;
+        } else {
+            self.left = child;
+        }
+    }
+
+    /// Reference to this branch, stored as `id`
+    fn to_child(self, id: NodeId) -> TreeChild {
+        TreeChild::Branch {
+            id,
+            hash: hash_node(id.bit, &self.left.hash(), &self.right.hash()),
+            height: 1 + self.left.height().max(self.right.height()),
+        }
+    }
+}
+
+/// Storage of the branches of one tree
+pub trait TreeNodes {
+    fn node(&self, id: &NodeId) -> Option<TreeNode>;
+    fn insert_node(&mut self, id: NodeId, node: TreeNode);
+    fn remove_node(&mut self, id: &NodeId);
+}
+
+impl TreeNodes for BTreeMap<NodeId, TreeNode> {
+    fn node(&self, id: &NodeId) -> Option<TreeNode> {
+        self.get(id).copied()
+    }
+
+    fn insert_node(&mut self, id: NodeId, node: TreeNode) {
+        self.insert(id, node);
+    }
+
+    fn remove_node(&mut self, id: &NodeId) {
+        self.remove(id);
+    }
+}
+
+/// Branches passed from the root down to the leaf `key` is or would be stored at
+type TreePath = Vec<(NodeId, TreeNode)>;
+
+/// Descends from `root` towards `key`, returning the branches passed and the leaf reached.
+/// `None` if a branch is missing from the storage.
+fn descend<N: TreeNodes>(nodes: &N, root: &TreeChild, key: &H256) -> Option<(TreePath, H256)> {
+    let mut path = Vec::new();
+    let mut child = *root;
+    loop {
+        match child {
+            TreeChild::Leaf(leaf) => return Some((path, leaf)),
+            TreeChild::Branch { id, .. } => {
+                let node = nodes.node(&id)?;
+                child = *node.child(bit_at(key, id.bit));
+                path.push((id, node));
+            }
+        }
+    }
+}
+
+/// Stores the branches of `path` with their subtree towards `key` replaced by `child`,
+/// returning the new root
+fn ascend<N: TreeNodes>(nodes: &mut N, path: TreePath, key: &H256, child: TreeChild) -> TreeChild {
+    path.into_iter().rev().fold(child, |child, (id, mut node)| {
+        node.set_child(bit_at(key, id.bit), child);
+        nodes.insert_node(id, node);
+        node.to_child(id)
+    })
+}
+
+/// Adds the leaf `key` to the tree with the given root, returning the new root
+pub fn insert_leaf<N: TreeNodes>(
+    nodes: &mut N,
+    root: Option<TreeChild>,
+    key: H256,
+) -> Option<TreeChild> {
+    let root = match root {
+        Some(root) => root,
+        None => return Some(TreeChild::Leaf(key)),
+    };
+    let (mut path, leaf) = match descend(nodes, &root, &key) {
+        Some(found) => found,
+        None => return Some(root),
+    };
+    // Already included otherwise
+    let bit = match crit_bit(&key, &leaf) {
+        Some(bit) => bit,
+        None => return Some(root),
+    };
+
+    // The new branch takes the place of the first subtree splitting its keys behind `bit`,
+    // all keys of which share the bits in front of it with `key`
+    let split = path
+        .iter()
+        .position(|(id, _)| id.bit > bit)
+        .unwrap_or(path.len());
+    let sibling = match path.get(split) {
+        Some((id, node)) => node.to_child(*id),
+        None => TreeChild::Leaf(leaf),
+    };
+    path.truncate(split);
+
+    let id = NodeId {
+        bit,
+        prefix: prefix(&key, bit),
+    };
+    let mut node = TreeNode {
+        left: sibling,
+        right: sibling,
+    };
+    node.set_child(bit_at(&key, bit), TreeChild::Leaf(key));
+    nodes.insert_node(id, node);
+
+    Some(ascend(nodes, path, &key, node.to_child(id)))
+}
+
+/// Removes the leaf `key` from the tree with the given root, returning the new root
+pub fn remove_leaf<N: TreeNodes>(
+    nodes: &mut N,
+    root: Option<TreeChild>,
+    key: &H256,
+) -> Option<TreeChild> {
+    let root = root?;
+    let (mut path, leaf) = match descend(nodes, &root, key) {
+        Some(found) => found,
+        None => return Some(root),
+    };
+    if leaf != *key {
+        return Some(root);
+    }
+
+    // The leaf's sibling takes the place of its parent
+    let (id, node) = path.pop()?;
+    nodes.remove_node(&id);
+    let sibling = *node.child(!bit_at(key, id.bit));
+
+    Some(ascend(nodes, path, key, sibling))
+}
+
+/// Hash of the tree with the given root
+pub fn root_hash(root: Option<&TreeChild>) -> H256 {
+    root.map(TreeChild::hash).unwrap_or_default()
+}
+
+/// Maximum depth of a tree, as every branch on a path splits at a later bit of the 256 bit
+/// keys
+pub const MAX_PROOF_DEPTH: u32 = 256;
+
+/// Branch passed on the way to a proven leaf
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct ProofStep {
+    /// Critical bit of the branch
+    pub bit: u8,
+    /// Hash of the branch's other child
+    pub sibling: H256,
+}
+
+/// Inclusion proof of a single leaf
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Default, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct MerkleProof {
+    /// Branches from the root down to the leaf
+    pub steps: BoundedVec<ProofStep, ConstU32<MAX_PROOF_DEPTH>>,
+}
+
+/// Proof of a (user -> role -> permission) path within a committed policy. The role's and
+/// the permission's entities are proven too, so disabled ones can be rejected.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct AccessProof<EntityId> {
+    /// Role linking the user to the permission
+    pub role: Entity<EntityId>,
+    /// Permission which is accessed
+    pub permission: Entity<EntityId>,
+    pub role_proof: MerkleProof,
+    pub permission_proof: MerkleProof,
+    pub role2user_proof: MerkleProof,
+    pub permission2role_proof: MerkleProof,
+}
+
+/// Builds the inclusion proof of the leaf `key` in the tree with the given root
+pub fn merkle_proof<N: TreeNodes>(nodes: &N, root: &TreeChild, key: &H256) -> Option<MerkleProof> {
+    let (path, leaf) = descend(nodes, root, key)?;
+    if leaf != *key {
+        return None;
+    }
+
+    let steps: Vec<ProofStep> = path
+        .iter()
+        .map(|(id, node)| ProofStep {
+            bit: id.bit,
+            sibling: node.child(!bit_at(key, id.bit)).hash(),
+        })
+        .collect();
+    Some(MerkleProof {
+        steps: steps.try_into().ok()?,
+    })
+}
+
+/// Checks that `leaf` is included in the tree with the given `root`
+pub fn verify_merkle_proof(root: &H256, leaf: H256, proof: &MerkleProof) -> bool {
+    // Critical bits grow from the root down, so every tree has one proof per leaf
+    if proof
+        .steps
+        .windows(2)
+        .any(|steps| steps[0].bit >= steps[1].bit)
+    {
+        return false;
+    }
+
+    let node = proof.steps.iter().rev().fold(leaf, |node, step| {
+        if bit_at(&leaf, step.bit) {
+            hash_node(step.bit, &step.sibling, &node)
+        } else {
+            hash_node(step.bit, &node, &step.sibling)
+        }
+    });
+    node == *root
+}
+
+/// Checks an access proof of the user holding the permission against a policy root
+pub fn verify_access_proof<EntityId: Encode + Clone + PartialEq>(
+    root: &H256,
+    user_id: &EntityId,
+    permission_id: &EntityId,
+    proof: &AccessProof<EntityId>,
+) -> bool {
+    let role_id = proof.role.id.clone();
+    let role2user = PolicyLeaf::Role2User(Role2User {
+        role: role_id.clone(),
+        user: user_id.clone(),
+    });
+    let permission2role = PolicyLeaf::Permission2Role(Permission2Role {
+        permission: permission_id.clone(),
+        role: role_id,
+    });
+
+    proof.permission.id == *permission_id
+        && proof.role.enabled
+        && proof.permission.enabled
+        && verify_merkle_proof(
+            root,
+            PolicyLeaf::Role(proof.role.clone()).hash(),
+            &proof.role_proof,
+        )
+        && verify_merkle_proof(
+            root,
+            PolicyLeaf::Permission(proof.permission.clone()).hash(),
+            &proof.permission_proof,
+        )
+        && verify_merkle_proof(root, role2user.hash(), &proof.role2user_proof)
+        && verify_merkle_proof(root, permission2role.hash(), &proof.permission2role_proof)
+}
+
+/// Tree held in memory, e.g. to prove leaves of a policy held off-chain
+#[derive(Default)]
+pub struct PolicyTree {
+    root: Option<TreeChild>,
+    nodes: BTreeMap<NodeId, TreeNode>,
+}
+
+impl PolicyTree {
+    /// Builds the tree of the given leaf hashes
+    pub fn from_leaves(leaves: impl IntoIterator<Item = H256>) -> Self {
+        let mut tree = Self::default();
+        for leaf in leaves {
+            tree.insert(leaf);
+        }
+        tree
+    }
+
+    pub fn insert(&mut self, leaf: H256) {
+        self.root = insert_leaf(&mut self.nodes, self.root, leaf);
+    }
+
+    pub fn remove(&mut self, leaf: &H256) {
+        self.root = remove_leaf(&mut self.nodes, self.root, leaf);
+    }
+
+    pub fn root(&self) -> H256 {
+        root_hash(self.root.as_ref())
+    }
+
+    /// Number of branches on the longest path down to a leaf
+    pub fn height(&self) -> u32 {
+        self.root.map_or(0, |root| root.height())
+    }
+
+    pub fn proof(&self, leaf: &H256) -> Option<MerkleProof> {
+        merkle_proof(&self.nodes, self.root.as_ref()?, leaf)
+    }
+}
+
+/// Leaf hashes of a complete policy, e.g. an exported policy or a genesis specification
+pub fn policy_leaves<EntityId: Encode + Clone>(policy: &GenesisPolicy<EntityId>) -> Vec<H256> {
+    let mut leaves = Vec::new();
+    let mut add = |leaf: PolicyLeaf<EntityId>| leaves.push(leaf.hash());
+
+    for role in policy.roles.iter() {
+        add(PolicyLeaf::Role(role.clone()));
+    }
+    for permission in policy.permissions.iter() {
+        add(PolicyLeaf::Permission(permission.clone()));
+    }
+    for group in policy.groups.iter() {
+        add(PolicyLeaf::Group(group.clone()));
+    }
+    for r2u in policy.role2users.iter() {
+        add(PolicyLeaf::Role2User(r2u.clone()));
+    }
+    for r2g in policy.role2groups.iter() {
+        add(PolicyLeaf::Role2Group(r2g.clone()));
+    }
+    for u2g in policy.user2groups.iter() {
+        add(PolicyLeaf::User2Group(u2g.clone()));
+    }
+    for p2r in policy.permission2roles.iter() {
+        add(PolicyLeaf::Permission2Role(p2r.clone()));
+    }
+
+    leaves
+}
+
+/// Rebuilds the commitment of a complete policy, to be compared with the one stored on chain
+pub fn policy_root<EntityId: Encode + Clone>(policy: &GenesisPolicy<EntityId>) -> H256 {
+    PolicyTree::from_leaves(policy_leaves(policy)).root()
+}
+
+/// Builds an access proof of the user holding the permission directly through one of its
+/// roles, if the policy grants it that way
+pub fn access_proof<EntityId: Encode + Clone + PartialEq>(
+    policy: &GenesisPolicy<EntityId>,
+    user_id: &EntityId,
+    permission_id: &EntityId,
+) -> Option<AccessProof<EntityId>> {
+    let tree = PolicyTree::from_leaves(policy_leaves(policy));
+    let prove = |leaf: PolicyLeaf<EntityId>| tree.proof(&leaf.hash());
+
+    let permission = policy
+        .permissions
+        .iter()
+        .find(|p| p.id == *permission_id && p.enabled)?;
+    let role = policy.roles.iter().find(|r| {
+        r.enabled
+            && policy
+                .role2users
+                .iter()
+                .any(|r2u| r2u.role == r.id && r2u.user == *user_id)
+            && policy
+                .permission2roles
+                .iter()
+                .any(|p2r| p2r.role == r.id && p2r.permission == *permission_id)
+    })?;
+
+    Some(AccessProof {
+        role_proof: prove(PolicyLeaf::Role(role.clone()))?,
+        permission_proof: prove(PolicyLeaf::Permission(permission.clone()))?,
+        role2user_proof: prove(PolicyLeaf::Role2User(Role2User {
+            role: role.id.clone(),
+            user: user_id.clone(),
+        }))?,
+        permission2role_proof: prove(PolicyLeaf::Permission2Role(Permission2Role {
+            permission: permission_id.clone(),
+            role: role.id.clone(),
+        }))?,
+        role: role.clone(),
+        permission: permission.clone(),
+    })
+}
@@ -25,8 +25,44 @@ pub enum RbacErrorType {
     AssignmentAlreadyExist,
     /// Returned if an assignment does not exist
     AssignmentDoesNotExist,
-    /// Exceeds max characters
+    /// Returned if a name exceeds the configured maximum length
     NameExceedMaxChar,
+    /// Returned if an entity holds more assignments than the configured limit
+    AssignmentLimitExceeded,
+    /// Returned if the user does not hold the required permission
+    PermissionDenied,
+    /// Returned if a name is empty
+    NameEmpty,
+    /// Returned if a name must be, but is not valid UTF-8
+    NameInvalidUtf8,
+    /// Returned if a name contains a character not allowed by the configured rules
+    NameInvalidChar,
+    /// Returned if a name is already held by another entity of the same kind
+    NameAlreadyExist,
+    /// Returned if a change would grow the owner's policy tree beyond the configured depth
+    PolicyTreeTooDeep,
+}
+
+impl RbacErrorType {
+    /// Stable numeric code of this error type, e.g. used as custom transaction validity code
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::EntityAlreadyExist => 1,
+            Self::EntityDoesNotExist => 2,
+            Self::EntityAuthorizationFailed => 3,
+            Self::EntityDisabled => 4,
+            Self::AssignmentAlreadyExist => 5,
+            Self::AssignmentDoesNotExist => 6,
+            Self::NameExceedMaxChar => 7,
+            Self::AssignmentLimitExceeded => 8,
+            Self::PermissionDenied => 9,
+            Self::NameEmpty => 10,
+            Self::NameInvalidUtf8 => 11,
+            Self::NameInvalidChar => 12,
+            Self::NameAlreadyExist => 13,
+            Self::PolicyTreeTooDeep => 14,
+        }
+    }
 }
 
 /// Struct encapsules all informations about occured error: error type and passed
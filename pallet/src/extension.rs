@@ -0,0 +1,154 @@
+//! Transaction extension gating runtime calls behind RBAC permissions. Transactions whose
+//! signer does not hold the permission mapped to their call are rejected during validation
+//! and never make it into a block.
+
+use crate::{error::RbacErrorType, rbac::Permission, weightinfo::WeightInfo, Pallet};
+use codec::{Decode, Encode};
+use frame_support::{dispatch::DispatchInfo, traits::Get, weights::Weight};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{Convert, DispatchInfoOf, Dispatchable, SignedExtension},
+    transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+};
+use sp_std::{fmt, marker::PhantomData};
+
+/// Configures which calls are gated by [`CheckRbacPermission`].
+pub trait Config: crate::Config {
+    /// Resolves a signing account to the user entity it acts as.
+    type UserMapper: Convert<Self::AccountId, Self::EntityId>;
+    /// Maps a call to the owner and the permission its signer must hold.
+    type CallPermissions: CallPermissions<
+        Self::AccountId,
+        Self::EntityId,
+        <Self as frame_system::Config>::RuntimeCall,
+    >;
+    /// Maximum number of roles and groups examined when checking the permission of a
+    /// signer. Transactions needing more are rejected.
+    type MaxPermissionLookups: Get<u32>;
+}
+
+/// Maps a call to the (owner, permission) pair required to submit it.
+pub trait CallPermissions<AccountId, EntityId, Call> {
+    /// Returns `None` for calls which are not gated.
+    fn required_permission(call: &Call) -> Option<(AccountId, EntityId)>;
+}
+
+impl<AccountId, EntityId, Call> CallPermissions<AccountId, EntityId, Call> for () {
+    fn required_permission(_call: &Call) -> Option<(AccountId, EntityId)> {
+        None
+    }
+}
+
+/// Rejects transactions whose signer's user entity does not hold the permission mapped to
+/// the call. The validity error is `InvalidTransaction::Custom` carrying the code of the
+/// `RbacErrorType` which caused the rejection, `AssignmentLimitExceeded` if the check
+/// would examine more than `MaxPermissionLookups` roles and groups.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckRbacPermission<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckRbacPermission<T> {
+    /// Create new `SignedExtension` to check RBAC permissions.
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// Checks that the signer holds the permission required by the call, if any. Returns
+    /// the number of roles and groups examined for gated calls.
+    fn check(
+        who: &T::AccountId,
+        call: &<T as frame_system::Config>::RuntimeCall,
+    ) -> Result<Option<u32>, TransactionValidityError> {
+        let (owner, permission_id) = match T::CallPermissions::required_permission(call) {
+            Some(required) => required,
+            None => return Ok(None),
+        };
+        let reject = |typ: RbacErrorType| -> TransactionValidityError {
+            InvalidTransaction::Custom(typ.code()).into()
+        };
+
+        Pallet::<T>::get_permission(&owner, permission_id).map_err(|e| reject(e.typ))?;
+        let user_id = T::UserMapper::convert(who.clone());
+        match Pallet::<T>::permission_lookups(
+            &owner,
+            user_id,
+            permission_id,
+            T::MaxPermissionLookups::get(),
+        ) {
+            Ok((true, lookups)) => Ok(Some(lookups)),
+            Ok((false, _)) => Err(reject(RbacErrorType::PermissionDenied)),
+            Err(e) => Err(reject(e.typ)),
+        }
+    }
+
+    /// Weight of a check examining `lookups` roles and groups, charged like the lookups
+    /// of `dispatch_with_permission`
+    fn check_weight(lookups: u32) -> Weight {
+        let weight_of = <T as crate::Config>::WeightInfo::dispatch_with_permission;
+        <T as crate::Config>::WeightInfo::fetch_user_permissions()
+            .saturating_add(weight_of(lookups).saturating_sub(weight_of(0)))
+    }
+}
+
+impl<T: Config + Send + Sync> Default for CheckRbacPermission<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config + Send + Sync> fmt::Debug for CheckRbacPermission<T> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CheckRbacPermission")
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckRbacPermission<T>
+where
+    <T as frame_system::Config>::RuntimeCall: Dispatchable<Info = DispatchInfo>,
+{
+    const IDENTIFIER: &'static str = "CheckRbacPermission";
+    type AccountId = T::AccountId;
+    type Call = <T as frame_system::Config>::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        Self::check(who, call)?;
+        Ok(ValidTransaction::default())
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        if let Some(lookups) = Self::check(who, call)? {
+            // The lookups run on top of the call itself, so account for them in the block
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                Self::check_weight(lookups),
+                info.class,
+            );
+        }
+        Ok(())
+    }
+}
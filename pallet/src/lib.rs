@@ -17,7 +17,13 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod commitment;
 pub mod error;
+pub mod extension;
+pub mod migrations;
+pub mod name;
+pub mod origin;
+pub mod proof;
 pub mod rbac;
 pub mod structs;
 
@@ -28,25 +34,40 @@ pub use weightinfo::WeightInfo;
 #[frame_support::pallet]
 pub mod pallet {
 
-    use codec::{Encode, MaxEncodedLen};
-    use frame_support::pallet_prelude::*;
+    use codec::{DecodeAll, MaxEncodedLen};
+    use frame_support::{
+        dispatch::{extract_actual_weight, GetDispatchInfo, PostDispatchInfo},
+        pallet_prelude::*,
+        storage::{with_transaction_unchecked, TransactionOutcome},
+        traits::{Contains, Currency, GetCallMetadata, PalletInfoAccess, ReservableCurrency},
+        BoundedSlice,
+    };
     use frame_system::pallet_prelude::*;
-    use sp_io::hashing::blake2_256;
+    use sp_core::H256;
+    use sp_runtime::traits::{Convert, Dispatchable, UniqueSaturatedInto};
     use sp_std::fmt::Debug;
-    use sp_std::{vec, vec::Vec};
+    use sp_std::{boxed::Box, collections::btree_map::BTreeMap, vec, vec::Vec};
 
     use super::WeightInfo;
     use crate::{
+        commitment::{self, AccessProof, NodeId, PolicyLeaf, TreeChild, TreeNode, TreeNodes},
         error::{
             RbacError,
             RbacErrorType::{
-                AssignmentAlreadyExist, AssignmentDoesNotExist, EntityAlreadyExist,
-                EntityAuthorizationFailed, EntityDisabled, EntityDoesNotExist, NameExceedMaxChar,
+                AssignmentAlreadyExist, AssignmentDoesNotExist, AssignmentLimitExceeded,
+                EntityAlreadyExist, EntityAuthorizationFailed, EntityDisabled, EntityDoesNotExist,
+                NameAlreadyExist, NameEmpty, NameExceedMaxChar, NameInvalidChar, NameInvalidUtf8,
+                PermissionDenied, PolicyTreeTooDeep,
             },
             Result,
         },
-        rbac::{Group, Permission, Rbac, RbacKeyType, Role, Tag},
-        structs::{Entity, Permission2Role, Role2Group, Role2User, User2Group},
+        name::NameRules,
+        rbac::{self, Group, Permission, Rbac, RbacKeyType, Role, Tag},
+        structs::{
+            AllowedCall, AuditRecord, Entity, GenesisPolicy, NameClaim, PendingRollback,
+            Permission2Role, PolicyChange, PolicyDiff, PolicyOp, PolicySnapshot, Role2Group,
+            Role2User, SnapshotInfo, User2Group,
+        },
     };
 
     macro_rules! dpatch_dposit {
@@ -73,13 +94,17 @@ pub mod pallet {
         };
     }
 
+    /// Version of the pallet's storage, see `migrations`
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(_);
 
     /// Configure the pallet by specifying the parameters and types on which it depends.
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + pallet_timestamp::Config {
         /// Because this pallet emits events, it depends on the runtime's definition of an event.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         type EntityId: Parameter
@@ -93,8 +118,84 @@ pub mod pallet {
             + Default;
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
+        /// Maximum number of assignments copied when cloning a role or a group.
+        #[pallet::constant]
+        type MaxCloneAssignments: Get<u32>;
+        /// Maximum number of operations applied by one `apply_policy_ops` call.
+        #[pallet::constant]
+        type MaxPolicyOps: Get<u32>;
+        /// Resolves the signer of `dispatch_with_permission` to the user entity it acts as.
+        type DispatchUserMapper: Convert<Self::AccountId, Self::EntityId>;
+        /// The overarching call type, dispatched by `dispatch_with_permission`.
+        type RuntimeCall: Parameter
+            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+            + GetDispatchInfo
+            + GetCallMetadata
+            + From<frame_system::Call<Self>>
+            + IsType<<Self as frame_system::Config>::RuntimeCall>;
+        /// Maximum number of calls a permission's call filter may allow.
+        #[pallet::constant]
+        type MaxAllowedCalls: Get<u32>;
+        /// Calls which may be dispatched on behalf of an owner at all, on top of the
+        /// permission's call filter. Only the outer call is checked, so calls dispatching
+        /// other calls, e.g. of `pallet_utility` or `pallet_proxy`, must be excluded. Calls
+        /// of this pallet are always rejected.
+        type DispatchFilter: Contains<<Self as Config>::RuntimeCall>;
+        /// Maximum number of roles and groups examined when checking the permission of a
+        /// `dispatch_with_permission` caller.
+        #[pallet::constant]
+        type MaxDispatchLookups: Get<u32>;
+        /// Number of policy changes kept in each owner's audit log, zero disables it.
+        #[pallet::constant]
+        type MaxAuditLogEntries: Get<u32>;
+        /// Maximum number of branches on a path of an owner's policy tree, see `commitment`.
+        /// Every policy change is charged for updating a path this long, changes growing a
+        /// tree beyond it are rejected. At most 256, the number of bits of a leaf hash.
+        #[pallet::constant]
+        type MaxPolicyTreeDepth: Get<u32>;
+        /// Currency in which snapshot deposits are reserved.
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// Deposit reserved for storing a policy snapshot.
+        #[pallet::constant]
+        type SnapshotDepositBase: Get<BalanceOf<Self>>;
+        /// Additional deposit per entity and assignment stored in a policy snapshot.
+        #[pallet::constant]
+        type SnapshotDepositPerItem: Get<BalanceOf<Self>>;
+        /// Maximum number of snapshots stored per owner.
+        #[pallet::constant]
+        type MaxSnapshots: Get<u32>;
+        /// Maximum length of a snapshot label in bytes.
+        #[pallet::constant]
+        type MaxSnapshotLabelLength: Get<u32>;
+        /// Maximum number of entities and assignments of a policy which can be snapshotted
+        /// or rolled back.
+        #[pallet::constant]
+        type MaxSnapshotItems: Get<u32>;
+        /// Number of operations of a pending rollback applied per block.
+        #[pallet::constant]
+        type RollbackChunkSize: Get<u32>;
+        /// Maximum number of operations staged in a policy draft, which are all published
+        /// within one block.
+        #[pallet::constant]
+        type MaxDraftOps: Get<u32>;
+        /// Maximum length of role, permission and group names in bytes. Dispatchables taking
+        /// a name bound it by this length, operation batches and other pallets are checked
+        /// against it.
+        #[pallet::constant]
+        type MaxNameLength: Get<u32>;
+        /// Further rules role, permission and group names must follow, `()` accepts any
+        /// non-empty name within `MaxNameLength`.
+        type NameRules: NameRules;
+        /// Whether role, permission and group names must be unique per owner and kind.
+        /// Only then names are indexed, so enabling it on a live chain requires running
+        /// `migrations::v3::BuildNameIndex` in the same runtime upgrade.
+        #[pallet::constant]
+        type UniqueNames: Get<bool>;
     }
 
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
     // The pallet's runtime storage items.
     // https://docs.substrate.io/main-docs/build/runtime-storage/
     #[pallet::storage]
@@ -137,6 +238,210 @@ pub mod pallet {
     pub type KeysLookUpStore<T: Config> =
         StorageMap<_, Blake2_128Concat, RbacKeyType, Entity<T::EntityId>, ValueQuery>;
 
+    /// Entity holding a name, keyed like entities by owner, name and kind. Only
+    /// maintained if `UniqueNames` is enabled.
+    #[pallet::storage]
+    #[pallet::getter(fn name_index_of)]
+    pub type NameIndexStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, RbacKeyType, T::EntityId, OptionQuery>;
+
+    /// Names claimed by the batch of policy operations being applied, only set during
+    /// `Pallet::execute_policy_ops`
+    #[pallet::storage]
+    pub(super) type NameClaimsStore<T: Config> =
+        StorageValue<_, Vec<NameClaim<T::EntityId>>, OptionQuery>;
+
+    /// Calls which holders of a permission may dispatch on behalf of its owner
+    #[pallet::storage]
+    #[pallet::getter(fn permission_calls_of)]
+    pub type PermissionCallFilterStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, RbacKeyType, Vec<AllowedCall>, ValueQuery>;
+
+    /// Branches of each owner's policy tree, see `commitment`. Trees of policies created
+    /// before its introduction are built by `migrations::v2::BuildPolicyTrees`.
+    #[pallet::storage]
+    #[pallet::getter(fn policy_tree_node_of)]
+    pub type PolicyTreeStore<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        NodeId,
+        TreeNode,
+        OptionQuery,
+    >;
+
+    /// Root of each owner's policy tree, missing for an empty policy
+    #[pallet::storage]
+    #[pallet::getter(fn policy_tree_root_of)]
+    pub type PolicyTreeRootStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, TreeChild, OptionQuery>;
+
+    /// Hash of the root of `PolicyTreeStore`, committing to an owner's whole policy
+    #[pallet::storage]
+    #[pallet::getter(fn policy_commitment_of)]
+    pub type PolicyCommitmentStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, H256, ValueQuery>;
+
+    /// Policy roots published by owners who hold their policy off-chain
+    #[pallet::storage]
+    #[pallet::getter(fn published_root_of)]
+    pub type PublishedPolicyRootStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, H256, OptionQuery>;
+
+    /// Audit log of each owner's policy changes, a ring buffer where a record lives in slot
+    /// `seq % MaxAuditLogEntries` until it gets overwritten
+    #[pallet::storage]
+    #[pallet::getter(fn audit_record_of)]
+    pub type AuditLogStore<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        u32,
+        AuditRecord<T::AccountId, T::EntityId>,
+        OptionQuery,
+    >;
+
+    /// Number of changes ever recorded in each owner's audit log
+    #[pallet::storage]
+    #[pallet::getter(fn audit_log_count_of)]
+    pub type AuditLogCountStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+    /// Account making policy changes on behalf of their owners, only set during
+    /// `Pallet::with_actor`
+    #[pallet::storage]
+    #[pallet::getter(fn actor)]
+    pub type ActorStore<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+
+    /// Sorted ids of the users each owner has assigned roles or groups to. Assignments made
+    /// before its introduction are added by `migrations::v1::BackfillPolicyUsers`.
+    #[pallet::storage]
+    #[pallet::getter(fn users_of)]
+    pub type PolicyUsersStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Vec<T::EntityId>, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn snapshot_of)]
+    pub type PolicySnapshotStore<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        u32,
+        PolicySnapshot<T::EntityId, BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    /// Ids of each owner's stored snapshots
+    #[pallet::storage]
+    #[pallet::getter(fn snapshot_ids_of)]
+    pub type SnapshotIdsStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Vec<u32>, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_snapshot_id_of)]
+    pub type NextSnapshotIdStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Rollbacks still being applied, continued in `on_idle`
+    #[pallet::storage]
+    #[pallet::getter(fn pending_rollback_of)]
+    pub type PendingRollbackStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, PendingRollback, OptionQuery>;
+
+    /// Names claimed by the chunks of a pending rollback applied so far, checked once its
+    /// last chunk is applied
+    #[pallet::storage]
+    pub(super) type RollbackNameClaimsStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Vec<NameClaim<T::EntityId>>, ValueQuery>;
+
+    /// Operations staged by each owner, applied to the live policy by `publish_draft`
+    #[pallet::storage]
+    #[pallet::getter(fn draft_of)]
+    pub type PolicyDraftStore<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Vec<PolicyOp<T::EntityId>>, ValueQuery>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// Policies to seed the chain with, per owner
+        pub policies: Vec<(T::AccountId, GenesisPolicy<T::EntityId>)>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            Self {
+                policies: Default::default(),
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+        fn build(&self) {
+            for (owner, policy) in self.policies.iter() {
+                // Seed through the same paths as the extrinsics, so an inconsistent
+                // specification is rejected the same way
+                let expect = |res: Result<(), RbacError>, what: &str, id: &T::EntityId| {
+                    if let Err(e) = res {
+                        panic!(
+                            "Invalid RBAC genesis of {:?}: cannot {} {:?}: {:?}",
+                            owner, what, id, e.typ
+                        );
+                    }
+                };
+
+                for role in policy.roles.iter() {
+                    let res = Pallet::<T>::create_role(owner, role.id, &role.name);
+                    expect(res, "create role", &role.id);
+                }
+                for permission in policy.permissions.iter() {
+                    let res =
+                        Pallet::<T>::create_permission(owner, permission.id, &permission.name);
+                    expect(res, "create permission", &permission.id);
+                }
+                for group in policy.groups.iter() {
+                    let res = Pallet::<T>::create_group(owner, group.id, &group.name);
+                    expect(res, "create group", &group.id);
+                }
+
+                for r2u in policy.role2users.iter() {
+                    let res = Pallet::<T>::create_role_to_user(owner, r2u.role, r2u.user);
+                    expect(res, "assign role to user", &r2u.user);
+                }
+                for r2g in policy.role2groups.iter() {
+                    let res = Pallet::<T>::create_role_to_group(owner, r2g.role, r2g.group);
+                    expect(res, "assign role to group", &r2g.group);
+                }
+                for u2g in policy.user2groups.iter() {
+                    let res = Pallet::<T>::create_user_to_group(owner, u2g.user, u2g.group);
+                    expect(res, "assign user to group", &u2g.user);
+                }
+                for p2r in policy.permission2roles.iter() {
+                    let res =
+                        Pallet::<T>::create_permission_to_role(owner, p2r.permission, p2r.role);
+                    expect(res, "assign permission to role", &p2r.role);
+                }
+
+                // Disable entities last, so they can still be referenced by assignments above
+                for role in policy.roles.iter().filter(|e| !e.enabled) {
+                    let res = Pallet::<T>::disable_existing_role(owner, role.id);
+                    expect(res, "disable role", &role.id);
+                }
+                for permission in policy.permissions.iter().filter(|e| !e.enabled) {
+                    let res = Pallet::<T>::disable_existing_permission(owner, permission.id);
+                    expect(res, "disable permission", &permission.id);
+                }
+                for group in policy.groups.iter().filter(|e| !e.enabled) {
+                    let res = Pallet::<T>::disable_existing_group(owner, group.id);
+                    expect(res, "disable group", &group.id);
+                }
+            }
+        }
+    }
+
     // Pallets use events to inform users when important changes are made.
     // https://docs.substrate.io/main-docs/build/events-errors/
     #[pallet::event]
@@ -190,13 +495,82 @@ pub mod pallet {
         UserAssignedToGroup(T::AccountId, T::EntityId, T::EntityId),
         /// Event emitted when a user to group relationship has been removed. [who, userId, groupId]
         UserUnAssignedToGroup(T::AccountId, T::EntityId, T::EntityId),
+        /// Event emitted when a role has been cloned.
+        /// [who, sourceRoleId, roleId, roleName, permissionIds]
+        RoleCloned(
+            T::AccountId,
+            T::EntityId,
+            T::EntityId,
+            Vec<u8>,
+            Vec<T::EntityId>,
+        ),
+        /// Event emitted when a group has been cloned.
+        /// [who, sourceGroupId, groupId, groupName, roleIds]
+        GroupCloned(
+            T::AccountId,
+            T::EntityId,
+            T::EntityId,
+            Vec<u8>,
+            Vec<T::EntityId>,
+        ),
+        /// Event emitted when a batch of policy operations has been applied. [who, opsCount]
+        PolicyOpsApplied(T::AccountId, u32),
+        /// Event emitted when the calls allowed by a permission have been set.
+        /// [who, permissionId, calls]
+        PermissionCallsSet(T::AccountId, T::EntityId, Vec<AllowedCall>),
+        /// Event emitted when a call has been dispatched on behalf of an owner.
+        /// [who, owner, permissionId, result]
+        DispatchedWithPermission(T::AccountId, T::AccountId, T::EntityId, DispatchResult),
+        /// Event emitted when an owner has published the root of an off-chain policy.
+        /// [who, root]
+        PolicyRootPublished(T::AccountId, H256),
+        /// Event emitted when an access proof has been verified against a published root.
+        /// [who, owner, userId, permissionId, roleId]
+        AccessAttested(
+            T::AccountId,
+            T::AccountId,
+            T::EntityId,
+            T::EntityId,
+            T::EntityId,
+        ),
+        /// Event emitted when a role has been enabled again. [who, roleId]
+        RoleEnabled(T::AccountId, T::EntityId),
+        /// Event emitted when a permission has been enabled again. [who, permissionId]
+        PermissionEnabled(T::AccountId, T::EntityId),
+        /// Event emitted when a group has been enabled again. [who, groupId]
+        GroupEnabled(T::AccountId, T::EntityId),
+        /// Event emitted when a policy snapshot has been stored.
+        /// [who, snapshotId, label, itemsCount]
+        PolicySnapshotted(T::AccountId, u32, Vec<u8>, u32),
+        /// Event emitted when a policy snapshot has been removed. [who, snapshotId]
+        SnapshotRemoved(T::AccountId, u32),
+        /// Event emitted when a rollback has been scheduled. [who, snapshotId, opsCount]
+        RollbackStarted(T::AccountId, u32, u32),
+        /// Event emitted when a rollback has been completed. [who, snapshotId, appliedOpsCount]
+        PolicyRolledBack(T::AccountId, u32, u32),
+        /// Event emitted when a rollback has been stopped at an operation which failed,
+        /// keeping the operations applied before. [who, snapshotId, appliedOpsCount, error]
+        RollbackAborted(T::AccountId, u32, u32, DispatchError),
+        /// Event emitted when a rollback has been cancelled by its owner.
+        /// [who, snapshotId, appliedOpsCount]
+        RollbackCancelled(T::AccountId, u32, u32),
+        /// Event emitted when operations have been staged in a policy draft.
+        /// [who, opsCount, draftOpsCount]
+        DraftStaged(T::AccountId, u32, u32),
+        /// Event emitted when a policy draft has been published. [who, opsCount]
+        DraftPublished(T::AccountId, u32),
+        /// Event emitted when a policy draft has been discarded. [who]
+        DraftDiscarded(T::AccountId),
+        /// Event emitted for every change of a policy, whichever call made it.
+        /// [owner, who, operation]
+        PolicyChanged(T::AccountId, T::AccountId, PolicyOp<T::EntityId>),
     }
 
     // Errors inform users that something went wrong.
     #[pallet::error]
     pub enum Error<T> {
-        /// Name exceeds 64
-        EntityNameExceedMax64,
+        /// Name exceeds `MaxNameLength`
+        EntityNameTooLong,
         /// Returned if the Role already exists
         EntityAlreadyExist,
         /// Returned if the Role does not exists
@@ -209,22 +583,72 @@ pub mod pallet {
         AssignmentAlreadyExist,
         /// Returned if assignment does not exist
         AssignmentDoesNotExist,
+        /// Returned if an entity holds more assignments than can be processed at once
+        AssignmentLimitExceeded,
+        /// Returned if the user does not hold the required permission
+        PermissionDenied,
+        /// Returned if the call is not allowed by the permission's call filter
+        CallNotAllowed,
+        /// Returned if the owner has not published a policy root
+        PolicyRootNotPublished,
+        /// Returned if an access proof does not match the published policy root
+        InvalidAccessProof,
+        /// Returned if the owner already stores the maximum number of snapshots
+        TooManySnapshots,
+        /// Returned if a policy has more items than can be snapshotted or rolled back
+        PolicyTooLarge,
+        /// Returned if the snapshot does not exist
+        SnapshotDoesNotExist,
+        /// Returned if a rollback of the owner's policy is still being applied
+        RollbackPending,
+        /// Returned if no rollback of the owner's policy is being applied
+        NoRollbackPending,
+        /// Returned if a draft would hold more operations than can be published at once
+        DraftTooLarge,
+        /// Returned if the owner has no staged operations
+        DraftDoesNotExist,
+        /// Returned for operations only rollbacks may apply
+        PolicyOpNotAllowed,
+        /// Name is empty
+        EntityNameEmpty,
+        /// Name is not valid UTF-8
+        EntityNameInvalidUtf8,
+        /// Name contains a character not allowed by `NameRules`
+        EntityNameInvalidChar,
+        /// Name is already held by another entity of the same kind
+        EntityNameAlreadyExist,
+        /// Returned if a change would grow the owner's policy tree beyond `MaxPolicyTreeDepth`
+        PolicyTreeTooDeep,
     }
 
     impl<T: Config> Error<T> {
         fn dispatch_error(err: RbacError) -> DispatchResult {
             match err.typ {
-                NameExceedMaxChar => Err(Error::<T>::EntityNameExceedMax64.into()),
+                NameExceedMaxChar => Err(Error::<T>::EntityNameTooLong.into()),
                 EntityAlreadyExist => Err(Error::<T>::EntityAlreadyExist.into()),
                 EntityDoesNotExist => Err(Error::<T>::EntityDoesNotExist.into()),
                 EntityAuthorizationFailed => Err(Error::<T>::EntityAuthorizationFailed.into()),
                 EntityDisabled => Err(Error::<T>::EntityDisabled.into()),
                 AssignmentAlreadyExist => Err(Error::<T>::AssignmentAlreadyExist.into()),
                 AssignmentDoesNotExist => Err(Error::<T>::AssignmentDoesNotExist.into()),
+                AssignmentLimitExceeded => Err(Error::<T>::AssignmentLimitExceeded.into()),
+                PermissionDenied => Err(Error::<T>::PermissionDenied.into()),
+                NameEmpty => Err(Error::<T>::EntityNameEmpty.into()),
+                NameInvalidUtf8 => Err(Error::<T>::EntityNameInvalidUtf8.into()),
+                NameInvalidChar => Err(Error::<T>::EntityNameInvalidChar.into()),
+                NameAlreadyExist => Err(Error::<T>::EntityNameAlreadyExist.into()),
+                PolicyTreeTooDeep => Err(Error::<T>::PolicyTreeTooDeep.into()),
             }
         }
     }
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::continue_rollback(remaining_weight)
+        }
+    }
+
     // Dispatchable functions allows users to interact with the pallet and invoke state changes.
     // These functions materialize as "extrinsics", which are often compared to transactions.
     // Dispatchable functions must be annotated with a weight and must return a DispatchResult.
@@ -255,44 +679,44 @@ pub mod pallet {
 
         /// create role call
         #[pallet::call_index(2)]
-        #[pallet::weight(T::WeightInfo::add_role())]
+        #[pallet::weight(
+            T::WeightInfo::add_role().saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn add_role(
             origin: OriginFor<T>,
             role_id: T::EntityId,
-            name: Vec<u8>,
+            name: BoundedVec<u8, T::MaxNameLength>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Verify that the name len is 64 max
-            ensure!(name.len() <= 64, Error::<T>::EntityNameExceedMax64);
-
             dpatch_dposit_par!(
                 Self::create_role(&sender, role_id, &name),
-                Event::RoleAdded(sender, role_id, name)
+                Event::RoleAdded(sender, role_id, name.into_inner())
             )
         }
 
         /// update role call
         #[pallet::call_index(3)]
-        #[pallet::weight(T::WeightInfo::update_role())]
+        #[pallet::weight(
+            T::WeightInfo::update_role().saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn update_role(
             origin: OriginFor<T>,
             role_id: T::EntityId,
-            name: Vec<u8>,
+            name: BoundedVec<u8, T::MaxNameLength>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Verify that the name len is 64 max
-            ensure!(name.len() <= 64, Error::<T>::EntityNameExceedMax64);
-
             dpatch_dposit_par!(
                 Self::update_existing_role(&sender, role_id, &name),
-                Event::RoleUpdated(sender, role_id, name)
+                Event::RoleUpdated(sender, role_id, name.into_inner())
             )
         }
 
         #[pallet::call_index(4)]
-        #[pallet::weight(T::WeightInfo::disable_role())]
+        #[pallet::weight(
+            T::WeightInfo::disable_role().saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn disable_role(origin: OriginFor<T>, role_id: T::EntityId) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
@@ -319,7 +743,10 @@ pub mod pallet {
 
         /// assign a role to user call
         #[pallet::call_index(6)]
-        #[pallet::weight(T::WeightInfo::assign_role_to_user())]
+        #[pallet::weight(
+            T::WeightInfo::assign_role_to_user()
+                .saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn assign_role_to_user(
             origin: OriginFor<T>,
             role_id: T::EntityId,
@@ -335,7 +762,10 @@ pub mod pallet {
 
         /// unassign role to user relationship call
         #[pallet::call_index(7)]
-        #[pallet::weight(T::WeightInfo::unassign_role_to_user())]
+        #[pallet::weight(
+            T::WeightInfo::unassign_role_to_user()
+                .saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn unassign_role_to_user(
             origin: OriginFor<T>,
             role_id: T::EntityId,
@@ -374,44 +804,44 @@ pub mod pallet {
 
         /// create permission call
         #[pallet::call_index(10)]
-        #[pallet::weight(T::WeightInfo::add_permission())]
+        #[pallet::weight(
+            T::WeightInfo::add_permission().saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn add_permission(
             origin: OriginFor<T>,
             permission_id: T::EntityId,
-            name: Vec<u8>,
+            name: BoundedVec<u8, T::MaxNameLength>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Verify that the name len is 64 max
-            ensure!(name.len() <= 64, Error::<T>::EntityNameExceedMax64);
-
             dpatch_dposit_par!(
                 Self::create_permission(&sender, permission_id, &name),
-                Event::PermissionAdded(sender, permission_id, name)
+                Event::PermissionAdded(sender, permission_id, name.into_inner())
             )
         }
 
         /// update permission call
         #[pallet::call_index(11)]
-        #[pallet::weight(T::WeightInfo::update_permission())]
+        #[pallet::weight(
+            T::WeightInfo::update_permission().saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn update_permission(
             origin: OriginFor<T>,
             permission_id: T::EntityId,
-            name: Vec<u8>,
+            name: BoundedVec<u8, T::MaxNameLength>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Verify that the name len is 64 max
-            ensure!(name.len() <= 64, Error::<T>::EntityNameExceedMax64);
-
             dpatch_dposit_par!(
                 Self::update_existing_permission(&sender, permission_id, &name),
-                Event::PermissionUpdated(sender, permission_id, name)
+                Event::PermissionUpdated(sender, permission_id, name.into_inner())
             )
         }
 
         #[pallet::call_index(12)]
-        #[pallet::weight(T::WeightInfo::disable_permission())]
+        #[pallet::weight(
+            T::WeightInfo::disable_permission().saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn disable_permission(
             origin: OriginFor<T>,
             permission_id: T::EntityId,
@@ -441,7 +871,10 @@ pub mod pallet {
 
         /// assign a permission to role call
         #[pallet::call_index(14)]
-        #[pallet::weight(T::WeightInfo::assign_permission_to_role())]
+        #[pallet::weight(
+            T::WeightInfo::assign_permission_to_role()
+                .saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn assign_permission_to_role(
             origin: OriginFor<T>,
             permission_id: T::EntityId,
@@ -457,7 +890,10 @@ pub mod pallet {
 
         /// unassign permission to role relationship call
         #[pallet::call_index(15)]
-        #[pallet::weight(T::WeightInfo::unassign_permission_to_role())]
+        #[pallet::weight(
+            T::WeightInfo::unassign_permission_to_role()
+                .saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn unassign_permission_to_role(
             origin: OriginFor<T>,
             permission_id: T::EntityId,
@@ -493,45 +929,45 @@ pub mod pallet {
 
         /// create group call
         #[pallet::call_index(18)]
-        #[pallet::weight(T::WeightInfo::add_group())]
+        #[pallet::weight(
+            T::WeightInfo::add_group().saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn add_group(
             origin: OriginFor<T>,
             group_id: T::EntityId,
-            name: Vec<u8>,
+            name: BoundedVec<u8, T::MaxNameLength>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Verify that the name len is 64 max
-            ensure!(name.len() <= 64, Error::<T>::EntityNameExceedMax64);
-
             dpatch_dposit_par!(
                 Self::create_group(&sender, group_id, &name),
-                Event::GroupAdded(sender, group_id, name)
+                Event::GroupAdded(sender, group_id, name.into_inner())
             )
         }
 
         /// update group call
         #[pallet::call_index(19)]
-        #[pallet::weight(T::WeightInfo::update_group())]
+        #[pallet::weight(
+            T::WeightInfo::update_group().saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn update_group(
             origin: OriginFor<T>,
             group_id: T::EntityId,
-            name: Vec<u8>,
+            name: BoundedVec<u8, T::MaxNameLength>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
-            // Verify that the name len is 64 max
-            ensure!(name.len() <= 64, Error::<T>::EntityNameExceedMax64);
-
             dpatch_dposit_par!(
                 Self::update_existing_group(&sender, group_id, &name),
-                Event::GroupUpdated(sender, group_id, name)
+                Event::GroupUpdated(sender, group_id, name.into_inner())
             )
         }
 
         /// disable group call
         #[pallet::call_index(20)]
-        #[pallet::weight(T::WeightInfo::disable_group())]
+        #[pallet::weight(
+            T::WeightInfo::disable_group().saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn disable_group(origin: OriginFor<T>, group_id: T::EntityId) -> DispatchResult {
             let sender = ensure_signed(origin)?;
 
@@ -543,7 +979,10 @@ pub mod pallet {
 
         /// assign a role to group call
         #[pallet::call_index(21)]
-        #[pallet::weight(T::WeightInfo::assign_role_to_group())]
+        #[pallet::weight(
+            T::WeightInfo::assign_role_to_group()
+                .saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn assign_role_to_group(
             origin: OriginFor<T>,
             role_id: T::EntityId,
@@ -559,7 +998,10 @@ pub mod pallet {
 
         /// unassign role to group relationship call
         #[pallet::call_index(22)]
-        #[pallet::weight(T::WeightInfo::unassign_role_to_group())]
+        #[pallet::weight(
+            T::WeightInfo::unassign_role_to_group()
+                .saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn unassign_role_to_group(
             origin: OriginFor<T>,
             role_id: T::EntityId,
@@ -590,7 +1032,10 @@ pub mod pallet {
 
         /// assign a user to group call
         #[pallet::call_index(24)]
-        #[pallet::weight(T::WeightInfo::assign_user_to_group())]
+        #[pallet::weight(
+            T::WeightInfo::assign_user_to_group()
+                .saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn assign_user_to_group(
             origin: OriginFor<T>,
             user_id: T::EntityId,
@@ -606,7 +1051,10 @@ pub mod pallet {
 
         /// unassign a user to group call
         #[pallet::call_index(25)]
-        #[pallet::weight(T::WeightInfo::unassign_user_to_group())]
+        #[pallet::weight(
+            T::WeightInfo::unassign_user_to_group()
+                .saturating_add(Pallet::<T>::policy_change_weight(1))
+        )]
         pub fn unassign_user_to_group(
             origin: OriginFor<T>,
             user_id: T::EntityId,
@@ -664,231 +1112,1421 @@ pub mod pallet {
                 Event::FetchedGroupPermissions
             )
         }
-    }
-
-    // implement the Rbac trait to satify the methods
-    impl<T: Config> Rbac<T::AccountId, T::EntityId> for Pallet<T> {
-        fn get_entity(
-            owner: &T::AccountId,
-            entity_id: &T::EntityId,
-            tag: Tag,
-        ) -> Result<Entity<T::EntityId>, RbacError> {
-            let key = Self::generate_key(owner, entity_id, tag);
 
-            if !<KeysLookUpStore<T>>::contains_key(key) {
-                return RbacError::err(EntityDoesNotExist, entity_id);
-            }
+        /// clone a role together with its permission assignments call
+        #[pallet::call_index(29)]
+        #[pallet::weight(T::WeightInfo::clone_role(T::MaxCloneAssignments::get()).saturating_add(
+            Pallet::<T>::policy_change_weight(T::MaxCloneAssignments::get().saturating_add(1))
+        ))]
+        pub fn clone_role(
+            origin: OriginFor<T>,
+            source_role_id: T::EntityId,
+            role_id: T::EntityId,
+            name: BoundedVec<u8, T::MaxNameLength>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-            let entity = <KeysLookUpStore<T>>::get(key);
+            dpatch_dposit!(
+                Self::clone_existing_role(&sender, source_role_id, role_id, &name),
+                |permissions| Event::RoleCloned(
+                    sender,
+                    source_role_id,
+                    role_id,
+                    name.into_inner(),
+                    permissions
+                )
+            )
+        }
 
-            if !entity.enabled {
-                return RbacError::err(EntityDisabled, entity_id);
-            }
+        /// clone a group together with its role assignments call
+        #[pallet::call_index(30)]
+        #[pallet::weight(T::WeightInfo::clone_group(T::MaxCloneAssignments::get()).saturating_add(
+            Pallet::<T>::policy_change_weight(T::MaxCloneAssignments::get().saturating_add(1))
+        ))]
+        pub fn clone_group(
+            origin: OriginFor<T>,
+            source_group_id: T::EntityId,
+            group_id: T::EntityId,
+            name: BoundedVec<u8, T::MaxNameLength>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-            Ok(entity)
+            dpatch_dposit!(
+                Self::clone_existing_group(&sender, source_group_id, group_id, &name),
+                |roles| Event::GroupCloned(
+                    sender,
+                    source_group_id,
+                    group_id,
+                    name.into_inner(),
+                    roles
+                )
+            )
         }
 
-        fn check_entity_get_key(
-            owner: &T::AccountId,
-            entity_id: &T::EntityId,
-            tag: Tag,
-        ) -> Result<RbacKeyType, RbacError> {
-            let key = Self::generate_key(owner, entity_id, tag);
-
-            if !<KeysLookUpStore<T>>::contains_key(key) {
-                return RbacError::err(EntityDoesNotExist, entity_id);
+        /// apply a batch of policy operations atomically call
+        #[pallet::call_index(31)]
+        #[pallet::weight(Pallet::<T>::policy_ops_weight(ops))]
+        pub fn apply_policy_ops(
+            origin: OriginFor<T>,
+            ops: BoundedVec<PolicyOp<T::EntityId>, T::MaxPolicyOps>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            ensure!(
+                !ops.iter().any(PolicyOp::is_internal),
+                Error::<T>::PolicyOpNotAllowed
+            );
+
+            let count = ops.len() as u32;
+            match Self::execute_policy_ops(&sender, &ops) {
+                Ok(()) => {
+                    for op in ops.into_iter() {
+                        Self::deposit_event(Self::policy_op_event(sender.clone(), op));
+                    }
+                    Self::deposit_event(Event::PolicyOpsApplied(sender, count));
+                    Ok(())
+                }
+                Err(e) => Error::<T>::dispatch_error(e),
             }
+        }
 
-            let entity = <KeysLookUpStore<T>>::get(key);
+        /// set the calls holders of a permission may dispatch on behalf of the owner call
+        #[pallet::call_index(32)]
+        #[pallet::weight(T::WeightInfo::set_permission_calls())]
+        pub fn set_permission_calls(
+            origin: OriginFor<T>,
+            permission_id: T::EntityId,
+            calls: BoundedVec<AllowedCall, T::MaxAllowedCalls>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-            if !entity.enabled {
-                return RbacError::err(EntityDisabled, entity_id);
+            // Check if permission exists and it's enabled
+            if let Err(e) = Self::check_entity_get_key(&sender, &permission_id, Tag::Permission) {
+                return Error::<T>::dispatch_error(e);
             }
 
-            Ok(key)
-        }
-
-        fn get_user_roles(
-            owner: &T::AccountId,
-            user_id: T::EntityId,
-        ) -> Result<Vec<Role2User<T::EntityId>>, RbacError> {
-            // Generate key for integrity check
-            let key = Self::generate_key(owner, &user_id, Tag::Role2User);
-
-            if <Role2UserStore<T>>::contains_key(key) {
-                Ok(Self::role_to_user_of(key))
+            let calls = calls.into_inner();
+            let key = Self::generate_key(&sender, &permission_id, Tag::CallFilter);
+            if calls.is_empty() {
+                <PermissionCallFilterStore<T>>::remove(key);
             } else {
-                RbacError::err(AssignmentDoesNotExist, &user_id)
+                <PermissionCallFilterStore<T>>::insert(key, calls.clone());
             }
+
+            Self::deposit_event(Event::PermissionCallsSet(sender, permission_id, calls));
+            Ok(())
         }
 
-        fn get_user_groups(
-            owner: &T::AccountId,
-            user_id: T::EntityId,
-        ) -> Result<Vec<User2Group<T::EntityId>>, RbacError> {
-            // Generate key for integrity check
-            let key = Self::generate_key(owner, &user_id, Tag::User2Group);
+        /// dispatch a call as the owner, if the caller holds the permission allowing it
+        #[pallet::call_index(33)]
+        #[pallet::weight({
+            let di = call.get_dispatch_info();
+            let lookups = T::MaxDispatchLookups::get();
+            (T::WeightInfo::dispatch_with_permission(lookups).saturating_add(di.weight), di.class)
+        })]
+        pub fn dispatch_with_permission(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            permission_id: T::EntityId,
+            call: Box<<T as Config>::RuntimeCall>,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
 
-            if <User2GroupStore<T>>::contains_key(key) {
-                Ok(Self::user_to_group_of(key))
-            } else {
-                RbacError::err(AssignmentDoesNotExist, &user_id)
-            }
+            let user_id = T::DispatchUserMapper::convert(sender.clone());
+            let (granted, lookups) = Self::permission_lookups(
+                &owner,
+                user_id,
+                permission_id,
+                T::MaxDispatchLookups::get(),
+            )
+            .or_else(|e| Error::<T>::dispatch_error(e).map(|_| (false, 0)))?;
+            ensure!(granted, Error::<T>::PermissionDenied);
+
+            // Calls of this pallet could change the owner's policy or dispatch further calls,
+            // neither of which is covered by the call filter
+            let metadata = call.get_call_metadata();
+            ensure!(
+                metadata.pallet_name != <Self as PalletInfoAccess>::name()
+                    && T::DispatchFilter::contains(&call),
+                Error::<T>::CallNotAllowed
+            );
+            let key = Self::generate_key(&owner, &permission_id, Tag::CallFilter);
+            ensure!(
+                <PermissionCallFilterStore<T>>::get(key)
+                    .iter()
+                    .any(|c| c.matches(metadata.pallet_name, metadata.function_name)),
+                Error::<T>::CallNotAllowed
+            );
+
+            let di = call.get_dispatch_info();
+            let res = Self::with_actor(&sender, || {
+                call.dispatch(frame_system::RawOrigin::Signed(owner.clone()).into())
+            });
+            let weight = T::WeightInfo::dispatch_with_permission(lookups)
+                .saturating_add(extract_actual_weight(&res, &di));
+
+            Self::deposit_event(Event::DispatchedWithPermission(
+                sender,
+                owner,
+                permission_id,
+                res.map(|_| ()).map_err(|e| e.error),
+            ));
+            Ok(Some(weight).into())
         }
 
-        fn get_group_roles(
-            owner: &T::AccountId,
-            group_id: T::EntityId,
-        ) -> Result<Vec<Role2Group<T::EntityId>>, RbacError> {
-            // Generate key for integrity check
-            let key = Self::generate_key(owner, &group_id, Tag::Role2Group);
+        /// publish the root of a policy held off-chain, see `commitment`
+        #[pallet::call_index(34)]
+        #[pallet::weight(T::WeightInfo::publish_policy_root())]
+        pub fn publish_policy_root(origin: OriginFor<T>, root: H256) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-            if <Role2GroupStore<T>>::contains_key(key) {
-                Ok(Self::role_to_group_of(key))
-            } else {
-                RbacError::err(AssignmentDoesNotExist, &group_id)
-            }
+            <PublishedPolicyRootStore<T>>::insert(&sender, root);
+
+            Self::deposit_event(Event::PolicyRootPublished(sender, root));
+            Ok(())
         }
 
-        fn get_role_permissions(
-            owner: &T::AccountId,
-            role_id: T::EntityId,
-        ) -> Result<Vec<Permission2Role<T::EntityId>>, RbacError> {
-            // Generate key for integrity check
-            let key = Self::generate_key(owner, &role_id, Tag::Permission2Role);
+        /// verify that a user holds a permission within an owner's published policy
+        #[pallet::call_index(35)]
+        #[pallet::weight(T::WeightInfo::verify_access_proof())]
+        pub fn verify_access_proof(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            user_id: T::EntityId,
+            permission_id: T::EntityId,
+            proof: AccessProof<T::EntityId>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-            if <Permission2RoleStore<T>>::contains_key(key) {
-                Ok(Self::permission_to_role_of(key))
-            } else {
-                RbacError::err(AssignmentDoesNotExist, &role_id)
-            }
+            Self::check_access_proof(&owner, &user_id, &permission_id, &proof)?;
+
+            Self::deposit_event(Event::AccessAttested(
+                sender,
+                owner,
+                user_id,
+                permission_id,
+                proof.role.id,
+            ));
+            Ok(())
         }
 
-        fn get_user_permissions(
-            owner: &T::AccountId,
-            user_id: T::EntityId,
-        ) -> Result<Vec<Entity<T::EntityId>>, RbacError> {
-            // Generate key for integrity check
-            let role_2_user_key = Self::generate_key(owner, &user_id, Tag::Role2User);
-            let user_2_group_key = Self::generate_key(owner, &user_id, Tag::User2Group);
+        /// store a copy of the caller's policy, reserving a deposit for it
+        #[pallet::call_index(36)]
+        #[pallet::weight(T::WeightInfo::snapshot_policy(T::MaxSnapshotItems::get()))]
+        pub fn snapshot_policy(
+            origin: OriginFor<T>,
+            label: BoundedVec<u8, T::MaxSnapshotLabelLength>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let label = label.into_inner();
+
+            let mut ids = <SnapshotIdsStore<T>>::get(&sender);
+            ensure!(
+                ids.len() < T::MaxSnapshots::get() as usize,
+                Error::<T>::TooManySnapshots
+            );
+
+            let policy = Self::export_policy_within(&sender, T::MaxSnapshotItems::get() as usize)
+                .ok_or(Error::<T>::PolicyTooLarge)?;
+            let items = policy.item_count();
+
+            let deposit = T::SnapshotDepositBase::get().saturating_add(
+                T::SnapshotDepositPerItem::get().saturating_mul((items as u32).into()),
+            );
+            T::Currency::reserve(&sender, deposit)?;
+
+            let snapshot_id = <NextSnapshotIdStore<T>>::get(&sender);
+            let snapshot = PolicySnapshot {
+                label: label.clone(),
+                block_number: <frame_system::Pallet<T>>::block_number().unique_saturated_into(),
+                policy,
+                deposit,
+            };
+            <PolicySnapshotStore<T>>::insert(&sender, snapshot_id, snapshot);
+            ids.push(snapshot_id);
+            <SnapshotIdsStore<T>>::insert(&sender, ids);
+            <NextSnapshotIdStore<T>>::insert(&sender, snapshot_id.saturating_add(1));
+
+            Self::deposit_event(Event::PolicySnapshotted(
+                sender,
+                snapshot_id,
+                label,
+                items as u32,
+            ));
+            Ok(())
+        }
 
-            let mut permissions: Vec<Entity<T::EntityId>> = vec![];
-            // use to avoid duplicate transverval
-            let mut processed_roles: Vec<T::EntityId> = vec![];
+        /// restore the caller's policy to a snapshot, applied in chunks in the following blocks
+        #[pallet::call_index(37)]
+        #[pallet::weight(T::WeightInfo::rollback_policy(T::MaxSnapshotItems::get()))]
+        pub fn rollback_policy(origin: OriginFor<T>, snapshot_id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-            if <Role2UserStore<T>>::contains_key(role_2_user_key) {
-                let val = <Role2UserStore<T>>::get(role_2_user_key);
+            ensure!(
+                !<PendingRollbackStore<T>>::contains_key(&sender),
+                Error::<T>::RollbackPending
+            );
+            let ops = Self::rollback_ops(&sender, snapshot_id)?;
+            let ops_count = ops.len() as u32;
+            if ops.is_empty() {
+                Self::deposit_event(Event::PolicyRolledBack(sender, snapshot_id, 0));
+                return Ok(());
+            }
 
-                let itr = val.iter();
+            <PendingRollbackStore<T>>::insert(
+                &sender,
+                PendingRollback {
+                    snapshot_id,
+                    applied: 0,
+                },
+            );
 
-                for r2u in itr {
-                    // use to avoid duplicate transversal
-                    processed_roles.push(r2u.role);
+            Self::deposit_event(Event::RollbackStarted(sender, snapshot_id, ops_count));
+            Ok(())
+        }
 
-                    let p2r_option = Self::get_role_permissions(owner, r2u.role)?;
+        /// remove a snapshot of the caller's policy and release its deposit
+        #[pallet::call_index(38)]
+        #[pallet::weight(T::WeightInfo::remove_snapshot())]
+        pub fn remove_snapshot(origin: OriginFor<T>, snapshot_id: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-                    for p2r in p2r_option.iter() {
-                        let perm_option = Self::get_permission(owner, p2r.permission)?;
-                        permissions.push(perm_option);
-                    }
-                }
-            }
+            ensure!(
+                Self::pending_rollback_of(&sender).map(|p| p.snapshot_id) != Some(snapshot_id),
+                Error::<T>::RollbackPending
+            );
+            let snapshot = <PolicySnapshotStore<T>>::take(&sender, snapshot_id)
+                .ok_or(Error::<T>::SnapshotDoesNotExist)?;
+            T::Currency::unreserve(&sender, snapshot.deposit);
+            <SnapshotIdsStore<T>>::mutate(&sender, |ids| ids.retain(|id| *id != snapshot_id));
 
-            if <User2GroupStore<T>>::contains_key(user_2_group_key) {
-                let val = <User2GroupStore<T>>::get(user_2_group_key);
+            Self::deposit_event(Event::SnapshotRemoved(sender, snapshot_id));
+            Ok(())
+        }
 
-                let itr = val.iter();
+        /// stop the caller's pending rollback, keeping the operations already applied
+        #[pallet::call_index(42)]
+        #[pallet::weight(T::WeightInfo::cancel_rollback())]
+        pub fn cancel_rollback(origin: OriginFor<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-                for u2g in itr {
-                    let key = Self::generate_key(owner, &u2g.group, Tag::Role2Group);
+            let pending =
+                <PendingRollbackStore<T>>::take(&sender).ok_or(Error::<T>::NoRollbackPending)?;
+            <RollbackNameClaimsStore<T>>::remove(&sender);
 
-                    if <Role2GroupStore<T>>::contains_key(key) {
-                        let val = <Role2GroupStore<T>>::get(key);
+            Self::deposit_event(Event::RollbackCancelled(
+                sender,
+                pending.snapshot_id,
+                pending.applied,
+            ));
+            Ok(())
+        }
 
-                        let r2g_itr = val.iter();
+        /// stage policy operations in the caller's draft, checked against the draft's state.
+        /// The whole draft is checked again, so a full draft is charged and the weight of
+        /// the staged operations refunded.
+        #[pallet::call_index(39)]
+        #[pallet::weight(T::WeightInfo::stage_policy_ops(T::MaxDraftOps::get())
+            .saturating_add(Pallet::<T>::policy_change_weight(T::MaxDraftOps::get())))]
+        pub fn stage_policy_ops(
+            origin: OriginFor<T>,
+            ops: BoundedVec<PolicyOp<T::EntityId>, T::MaxPolicyOps>,
+        ) -> DispatchResultWithPostInfo {
+            let sender = ensure_signed(origin)?;
+            ensure!(
+                !ops.iter().any(PolicyOp::is_internal),
+                Error::<T>::PolicyOpNotAllowed
+            );
+
+            let mut draft = <PolicyDraftStore<T>>::get(&sender);
+            ensure!(
+                draft.len().saturating_add(ops.len()) <= T::MaxDraftOps::get() as usize,
+                Error::<T>::DraftTooLarge
+            );
+
+            let count = ops.len() as u32;
+            draft.extend(ops);
+            Self::check_policy_ops(&sender, &draft).or_else(Error::<T>::dispatch_error)?;
+
+            let draft_count = draft.len() as u32;
+            <PolicyDraftStore<T>>::insert(&sender, draft);
+
+            Self::deposit_event(Event::DraftStaged(sender, count, draft_count));
+            Ok(Some(
+                T::WeightInfo::stage_policy_ops(draft_count)
+                    .saturating_add(Self::policy_change_weight(draft_count)),
+            )
+            .into())
+        }
 
-                        for r2g in r2g_itr {
-                            // use to avoid duplicate transversal
-                            if !processed_roles.contains(&r2g.role) {
-                                let p2r_option = Self::get_role_permissions(owner, r2g.role)?;
+        /// apply all operations of the caller's draft to the live policy atomically
+        #[pallet::call_index(40)]
+        #[pallet::weight(T::WeightInfo::publish_draft(T::MaxDraftOps::get())
+            .saturating_add(Pallet::<T>::policy_change_weight(T::MaxDraftOps::get())))]
+        pub fn publish_draft(origin: OriginFor<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-                                for p2r in p2r_option.iter() {
-                                    let perm_option = Self::get_permission(owner, p2r.permission)?;
-                                    permissions.push(perm_option);
-                                }
-                            }
-                        }
+            let draft = <PolicyDraftStore<T>>::get(&sender);
+            ensure!(!draft.is_empty(), Error::<T>::DraftDoesNotExist);
+
+            // The live policy may have changed since staging, then nothing is applied and
+            // the draft is kept
+            let count = draft.len() as u32;
+            match Self::execute_policy_ops(&sender, &draft) {
+                Ok(()) => {
+                    <PolicyDraftStore<T>>::remove(&sender);
+                    for op in draft.into_iter() {
+                        Self::deposit_event(Self::policy_op_event(sender.clone(), op));
                     }
+                    Self::deposit_event(Event::DraftPublished(sender, count));
+                    Ok(())
                 }
+                Err(e) => Error::<T>::dispatch_error(e),
             }
-
-            Ok(permissions)
         }
 
-        fn get_group_permissions(
-            owner: &T::AccountId,
-            group_id: T::EntityId,
-        ) -> Result<Vec<Entity<T::EntityId>>, RbacError> {
-            // Generate key for integrity check
+        /// drop all operations of the caller's draft
+        #[pallet::call_index(41)]
+        #[pallet::weight(T::WeightInfo::discard_draft())]
+        pub fn discard_draft(origin: OriginFor<T>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
 
-            let mut permissions: Vec<Entity<T::EntityId>> = vec![];
+            ensure!(
+                <PolicyDraftStore<T>>::contains_key(&sender),
+                Error::<T>::DraftDoesNotExist
+            );
+            <PolicyDraftStore<T>>::remove(&sender);
 
-            let key = Self::generate_key(owner, &group_id, Tag::Role2Group);
+            Self::deposit_event(Event::DraftDiscarded(sender));
+            Ok(())
+        }
+    }
 
-            if <Role2GroupStore<T>>::contains_key(key) {
-                let val = <Role2GroupStore<T>>::get(key);
+    impl<T: Config> Pallet<T> {
+        /// Checks an access proof against the owner's published policy root
+        pub fn check_access_proof(
+            owner: &T::AccountId,
+            user_id: &T::EntityId,
+            permission_id: &T::EntityId,
+            proof: &AccessProof<T::EntityId>,
+        ) -> Result<(), Error<T>> {
+            let root = Self::published_root_of(owner).ok_or(Error::<T>::PolicyRootNotPublished)?;
+
+            ensure!(
+                commitment::verify_access_proof(&root, user_id, permission_id, proof),
+                Error::<T>::InvalidAccessProof
+            );
+            Ok(())
+        }
 
-                let r2g_itr = val.iter();
+        /// Complete policy of an owner. Assignments to users are found through
+        /// `PolicyUsersStore`, so on chains which assigned users before its introduction
+        /// the export is complete once `migrations::v1::BackfillPolicyUsers` ran.
+        pub fn export_policy(owner: &T::AccountId) -> GenesisPolicy<T::EntityId> {
+            Self::export_policy_within(owner, usize::MAX).unwrap_or_default()
+        }
 
-                for r2g in r2g_itr {
-                    let p2r_option = Self::get_role_permissions(owner, r2g.role)?;
+        /// Like `export_policy`, but gives up as soon as the policy turns out to have more
+        /// than `max_items` entities and assignments, so that reads stay bounded
+        pub fn export_policy_within(
+            owner: &T::AccountId,
+            max_items: usize,
+        ) -> Option<GenesisPolicy<T::EntityId>> {
+            let roles = <RoleStore<T>>::get(owner);
+            let groups = <GroupStore<T>>::get(owner);
+            let users = <PolicyUsersStore<T>>::get(owner);
+
+            let mut policy = GenesisPolicy {
+                permissions: <PermissionStore<T>>::get(owner),
+                ..Default::default()
+            };
+            // Every user still to be read holds at least one assignment
+            let entities = roles.len().saturating_add(groups.len());
+            let within = |policy: &GenesisPolicy<T::EntityId>, pending: usize| {
+                policy
+                    .item_count()
+                    .saturating_add(entities)
+                    .saturating_add(pending)
+                    <= max_items
+            };
+            if !within(&policy, users.len()) {
+                return None;
+            }
 
-                    for p2r in p2r_option.iter() {
-                        let perm_option = Self::get_permission(owner, p2r.permission)?;
-                        permissions.push(perm_option);
-                    }
+            for (i, user_id) in users.iter().enumerate() {
+                let key = Self::generate_key(owner, user_id, Tag::Role2User);
+                policy.role2users.append(&mut <Role2UserStore<T>>::get(key));
+                let key = Self::generate_key(owner, user_id, Tag::User2Group);
+                policy
+                    .user2groups
+                    .append(&mut <User2GroupStore<T>>::get(key));
+                if !within(&policy, users.len() - i - 1) {
+                    return None;
+                }
+            }
+            for group in groups.iter() {
+                let key = Self::generate_key(owner, &group.id, Tag::Role2Group);
+                policy
+                    .role2groups
+                    .append(&mut <Role2GroupStore<T>>::get(key));
+                if !within(&policy, 0) {
+                    return None;
+                }
+            }
+            for role in roles.iter() {
+                let key = Self::generate_key(owner, &role.id, Tag::Permission2Role);
+                policy
+                    .permission2roles
+                    .append(&mut <Permission2RoleStore<T>>::get(key));
+                if !within(&policy, 0) {
+                    return None;
+                }
+            }
+            policy.roles = roles;
+            policy.groups = groups;
+            Some(policy)
+        }
+
+        /// Checks that the operations apply to the owner's live policy, without changing it
+        fn check_policy_ops(
+            owner: &T::AccountId,
+            ops: &[PolicyOp<T::EntityId>],
+        ) -> Result<(), RbacError> {
+            with_transaction_unchecked(|| {
+                TransactionOutcome::Rollback(Self::execute_policy_ops(owner, ops))
+            })
+        }
+
+        /// Owner's policy as it would be after applying the operations, without changing
+        /// the live policy
+        pub fn draft_policy(
+            owner: &T::AccountId,
+            ops: &[PolicyOp<T::EntityId>],
+        ) -> Result<GenesisPolicy<T::EntityId>, RbacError> {
+            with_transaction_unchecked(|| {
+                let policy =
+                    Self::execute_policy_ops(owner, ops).map(|_| Self::export_policy(owner));
+                TransactionOutcome::Rollback(policy)
+            })
+        }
+
+        /// Changes publishing the owner's draft would make to its live policy
+        pub fn diff_draft(owner: &T::AccountId) -> Result<PolicyDiff<T::EntityId>, RbacError> {
+            let draft = <PolicyDraftStore<T>>::get(owner);
+            let draft_policy = Self::draft_policy(owner, &draft)?;
+            Ok(Self::export_policy(owner).diff(&draft_policy))
+        }
+
+        /// Role assignments of each of the users, answered per user
+        pub fn users_roles(
+            owner: &T::AccountId,
+            user_ids: Vec<T::EntityId>,
+        ) -> Vec<(T::EntityId, Result<Vec<Role2User<T::EntityId>>, RbacError>)> {
+            user_ids
+                .into_iter()
+                .map(|user_id| (user_id, Self::get_user_roles(owner, user_id)))
+                .collect()
+        }
+
+        /// Effective permissions of each of the users, answered per user
+        pub fn users_permissions(
+            owner: &T::AccountId,
+            user_ids: Vec<T::EntityId>,
+        ) -> Vec<(T::EntityId, Result<Vec<Entity<T::EntityId>>, RbacError>)> {
+            user_ids
+                .into_iter()
+                .map(|user_id| (user_id, Self::get_user_permissions(owner, user_id)))
+                .collect()
+        }
+
+        /// Whether each user holds the permission paired with it
+        pub fn check_permissions(
+            owner: &T::AccountId,
+            queries: Vec<(T::EntityId, T::EntityId)>,
+        ) -> Vec<(T::EntityId, T::EntityId, bool)> {
+            queries
+                .into_iter()
+                .map(|(user_id, permission_id)| {
+                    let granted = Self::has_permission(owner, user_id, permission_id);
+                    (user_id, permission_id, granted)
+                })
+                .collect()
+        }
+
+        /// Summaries of the owner's stored snapshots
+        pub fn snapshots(owner: &T::AccountId) -> Vec<SnapshotInfo> {
+            <SnapshotIdsStore<T>>::get(owner)
+                .into_iter()
+                .filter_map(|id| {
+                    <PolicySnapshotStore<T>>::get(owner, id).map(|snapshot| SnapshotInfo {
+                        id,
+                        label: snapshot.label,
+                        block_number: snapshot.block_number,
+                        items: snapshot.policy.item_count() as u32,
+                    })
+                })
+                .collect()
+        }
+
+        /// Operations rolling the owner's live policy back to the snapshot
+        fn rollback_ops(
+            owner: &T::AccountId,
+            snapshot_id: u32,
+        ) -> Result<Vec<PolicyOp<T::EntityId>>, DispatchError> {
+            let snapshot = <PolicySnapshotStore<T>>::get(owner, snapshot_id)
+                .ok_or(Error::<T>::SnapshotDoesNotExist)?;
+            let current = Self::export_policy_within(owner, T::MaxSnapshotItems::get() as usize)
+                .ok_or(Error::<T>::PolicyTooLarge)?;
+            Ok(current.ops_to(&snapshot.policy))
+        }
+
+        /// Applies the next chunk of the first pending rollback, as many of its next
+        /// `RollbackChunkSize` operations as the remaining weight allows. The rollback is
+        /// aborted at the first operation which fails.
+        fn continue_rollback(remaining_weight: Weight) -> Weight {
+            let db_weight = T::DbWeight::get();
+            let mut used_weight = db_weight.reads(1);
+
+            let (owner, mut pending) = match <PendingRollbackStore<T>>::iter().next() {
+                Some(entry) => entry,
+                None => return used_weight,
+            };
+
+            // Computing the operations still needed costs as much as scheduling the rollback
+            let plan_weight = T::WeightInfo::rollback_policy(T::MaxSnapshotItems::get())
+                .saturating_add(db_weight.reads_writes(1, 2));
+            if used_weight
+                .saturating_add(plan_weight)
+                .any_gt(remaining_weight)
+            {
+                return used_weight;
+            }
+            used_weight = used_weight.saturating_add(plan_weight);
+
+            let result = Self::rollback_ops(&owner, pending.snapshot_id).and_then(|ops| {
+                let mut end = 0;
+                let limit = ops.len().min(T::RollbackChunkSize::get() as usize);
+                while end < limit {
+                    let op_weight = Self::policy_ops_weight(&ops[end..end + 1]);
+                    if used_weight
+                        .saturating_add(op_weight)
+                        .any_gt(remaining_weight)
+                    {
+                        break;
+                    }
+                    used_weight = used_weight.saturating_add(op_weight);
+                    end += 1;
+                }
+                let done = end == ops.len();
+                let claims = <RollbackNameClaimsStore<T>>::take(&owner);
+                with_transaction_unchecked(|| {
+                    // Names are checked against the rollback's end state, as a later chunk
+                    // may release the ones claimed by this one
+                    <NameClaimsStore<T>>::put(claims);
+                    // Applied on behalf of the owner who requested the rollback
+                    let res =
+                        Self::with_actor(&owner, || Self::execute_policy_ops(&owner, &ops[..end]));
+                    let claims = <NameClaimsStore<T>>::take().unwrap_or_default();
+                    let res = res.and_then(|_| {
+                        if done {
+                            Self::check_name_claims(claims)
+                        } else {
+                            if !claims.is_empty() {
+                                <RollbackNameClaimsStore<T>>::insert(&owner, claims);
+                            }
+                            Ok(())
+                        }
+                    });
+                    match res {
+                        Ok(()) => TransactionOutcome::Commit(Ok(())),
+                        Err(e) => TransactionOutcome::Rollback(Err(e)),
+                    }
+                })
+                .or_else(Error::<T>::dispatch_error)?;
+                pending.applied = pending.applied.saturating_add(end as u32);
+                Ok(done)
+            });
+
+            match result {
+                Ok(false) => <PendingRollbackStore<T>>::insert(&owner, pending),
+                Ok(true) => {
+                    <PendingRollbackStore<T>>::remove(&owner);
+                    Self::deposit_event(Event::PolicyRolledBack(
+                        owner,
+                        pending.snapshot_id,
+                        pending.applied,
+                    ));
+                }
+                Err(e) => {
+                    <PendingRollbackStore<T>>::remove(&owner);
+                    Self::deposit_event(Event::RollbackAborted(
+                        owner,
+                        pending.snapshot_id,
+                        pending.applied,
+                        e,
+                    ));
+                }
+            }
+            used_weight
+        }
+
+        /// Checks like `has_permission` whether the user holds the permission, examining
+        /// at most `limit` of its roles and groups. Returns the answer and the number of
+        /// roles and groups examined.
+        pub fn permission_lookups(
+            owner: &T::AccountId,
+            user_id: T::EntityId,
+            permission_id: T::EntityId,
+            limit: u32,
+        ) -> Result<(bool, u32), RbacError> {
+            if Self::get_permission(owner, permission_id).is_err() {
+                return Ok((false, 0));
+            }
+
+            let mut lookups: u32 = 0;
+            let lookup = |lookups: &mut u32| {
+                if *lookups >= limit {
+                    return RbacError::err(AssignmentLimitExceeded, &user_id);
+                }
+                *lookups += 1;
+                Ok(())
+            };
+            let grants = |role_id: &T::EntityId| {
+                let key = Self::generate_key(owner, role_id, Tag::Permission2Role);
+                Self::get_role(owner, *role_id).is_ok()
+                    && <Permission2RoleStore<T>>::get(key)
+                        .iter()
+                        .any(|p2r| p2r.permission == permission_id)
+            };
+
+            let role_2_user_key = Self::generate_key(owner, &user_id, Tag::Role2User);
+            for r2u in <Role2UserStore<T>>::get(role_2_user_key).iter() {
+                lookup(&mut lookups)?;
+                if grants(&r2u.role) {
+                    return Ok((true, lookups));
+                }
+            }
+
+            let user_2_group_key = Self::generate_key(owner, &user_id, Tag::User2Group);
+            for u2g in <User2GroupStore<T>>::get(user_2_group_key).iter() {
+                lookup(&mut lookups)?;
+                if Self::get_group(owner, u2g.group).is_err() {
+                    continue;
+                }
+                let key = Self::generate_key(owner, &u2g.group, Tag::Role2Group);
+                for r2g in <Role2GroupStore<T>>::get(key).iter() {
+                    lookup(&mut lookups)?;
+                    if grants(&r2g.role) {
+                        return Ok((true, lookups));
+                    }
+                }
+            }
+
+            Ok((false, lookups))
+        }
+
+        /// Adds the enabled permissions of the role to `permissions`, unless the role
+        /// itself is disabled
+        fn collect_role_permissions(
+            owner: &T::AccountId,
+            role_id: T::EntityId,
+            permissions: &mut Vec<Entity<T::EntityId>>,
+        ) {
+            if Self::get_role(owner, role_id).is_err() {
+                return;
+            }
+            let key = Self::generate_key(owner, &role_id, Tag::Permission2Role);
+            permissions.extend(
+                <Permission2RoleStore<T>>::get(key)
+                    .iter()
+                    .filter_map(|p2r| Self::get_permission(owner, p2r.permission).ok()),
+            );
+        }
+
+        /// Keeps the user in the owner's user index as long as it has any assignment
+        fn update_user_index(owner: &T::AccountId, user_id: T::EntityId) {
+            let assigned = <Role2UserStore<T>>::contains_key(Self::generate_key(
+                owner,
+                &user_id,
+                Tag::Role2User,
+            )) || <User2GroupStore<T>>::contains_key(Self::generate_key(
+                owner,
+                &user_id,
+                Tag::User2Group,
+            ));
+
+            <PolicyUsersStore<T>>::mutate(owner, |users| match users.binary_search(&user_id) {
+                Ok(i) if !assigned => {
+                    users.remove(i);
+                }
+                Err(i) if assigned => users.insert(i, user_id),
+                _ => {}
+            });
+        }
+
+        /// Changes of the owner's policy made in the current block so far, decoded from its
+        /// `PolicyChanged` events. Reads all events of the block, so only for off-chain use.
+        pub fn policy_changes(
+            owner: &T::AccountId,
+        ) -> Vec<PolicyChange<T::AccountId, T::EntityId>> {
+            // Runtime events are encoded as the index of their pallet followed by its event
+            let index = <Self as PalletInfoAccess>::index() as u8;
+            <frame_system::Pallet<T>>::read_events_no_consensus()
+                .filter_map(|record| {
+                    let encoded = record.event.encode();
+                    match encoded.split_first() {
+                        Some((pallet, mut event)) if *pallet == index => {
+                            match Event::<T>::decode_all(&mut event) {
+                                Ok(Event::PolicyChanged(changed, who, op)) if changed == *owner => {
+                                    Some(PolicyChange { op, who })
+                                }
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    }
+                })
+                .collect()
+        }
+
+        /// Records of the owner's audit log, oldest first, starting at sequence number `from`
+        /// or at the oldest record still kept
+        pub fn audit_log(
+            owner: &T::AccountId,
+            from: u64,
+            limit: u32,
+        ) -> Vec<AuditRecord<T::AccountId, T::EntityId>> {
+            let capacity = T::MaxAuditLogEntries::get() as u64;
+            let count = <AuditLogCountStore<T>>::get(owner);
+            let start = from.max(count.saturating_sub(capacity));
+            let end = count.min(start.saturating_add(limit.min(capacity as u32) as u64));
+
+            (start..end)
+                .filter_map(|seq| <AuditLogStore<T>>::get(owner, (seq % capacity) as u32))
+                .collect()
+        }
+
+        /// Checks the name of the entity against `MaxNameLength` and `NameRules`. Only new
+        /// names are checked: updates keeping the name and re-enabled entities aren't, while
+        /// rollbacks restoring a name the rules no longer accept are aborted.
+        fn check_name(entity_id: &T::EntityId, name: &[u8]) -> Result<(), RbacError> {
+            if name.is_empty() {
+                return RbacError::err(NameEmpty, entity_id);
+            }
+            if BoundedSlice::<u8, T::MaxNameLength>::try_from(name).is_err() {
+                return RbacError::err(NameExceedMaxChar, entity_id);
+            }
+            match T::NameRules::check(name) {
+                Ok(()) => Ok(()),
+                Err(typ) => RbacError::err(typ, entity_id),
+            }
+        }
+
+        /// Reserves the name for the entity, failing if another entity of the kind holds
+        /// it. Within a batch of policy operations the check is deferred to the end of the
+        /// batch, so names may be swapped. Does nothing unless names are unique.
+        fn index_name(
+            owner: &T::AccountId,
+            entity_id: &T::EntityId,
+            name: &[u8],
+            tag: Tag,
+        ) -> Result<(), RbacError> {
+            if !T::UniqueNames::get() {
+                return Ok(());
+            }
+
+            let key = rbac::generate_key(owner, &name, tag);
+            let holder = <NameIndexStore<T>>::get(key).filter(|holder| holder != entity_id);
+            if let Some(mut claims) = <NameClaimsStore<T>>::get() {
+                claims.push(NameClaim {
+                    name: name.to_vec(),
+                    name_key: key,
+                    claimant: *entity_id,
+                    claimant_key: Self::generate_key(owner, entity_id, tag),
+                    displaced_key: holder.map(|holder| Self::generate_key(owner, &holder, tag)),
+                });
+                <NameClaimsStore<T>>::put(claims);
+            } else if holder.is_some() {
+                return RbacError::err(NameAlreadyExist, &name.to_vec());
+            }
+            <NameIndexStore<T>>::insert(key, entity_id);
+            Ok(())
+        }
+
+        /// Releases a name no longer held by the entity, unless another entity claimed it
+        /// in the meantime
+        fn unindex_name(owner: &T::AccountId, entity_id: &T::EntityId, name: &[u8], tag: Tag) {
+            if T::UniqueNames::get() {
+                let key = rbac::generate_key(owner, &name, tag);
+                if <NameIndexStore<T>>::get(key).as_ref() == Some(entity_id) {
+                    <NameIndexStore<T>>::remove(key);
+                }
+            }
+        }
+
+        /// Checks the names claimed by a batch of policy operations once it is applied.
+        /// Each claimant still holding its name must be the one indexed for it, and the
+        /// entity it displaced must have released the name.
+        fn check_name_claims(claims: Vec<NameClaim<T::EntityId>>) -> Result<(), RbacError> {
+            let holds = |key: &RbacKeyType, name: &[u8]| {
+                let entity = <KeysLookUpStore<T>>::get(key);
+                entity.enabled && entity.name == name
+            };
+            for claim in claims {
+                if !holds(&claim.claimant_key, &claim.name) {
+                    continue;
+                }
+                let indexed = <NameIndexStore<T>>::get(claim.name_key) == Some(claim.claimant);
+                let displaced = claim
+                    .displaced_key
+                    .map_or(false, |key| holds(&key, &claim.name));
+                if !indexed || displaced {
+                    return RbacError::err(NameAlreadyExist, &claim.name);
+                }
+            }
+            Ok(())
+        }
+
+        /// Id of the owner's entity of the kind holding the name, from the index if names
+        /// are unique and else the first enabled one of `entities` with the name. Without an
+        /// enabled one the first with the name is returned, as the lookup then fails on it.
+        fn entity_id_by_name(
+            owner: &T::AccountId,
+            name: &[u8],
+            tag: Tag,
+            entities: impl FnOnce(&T::AccountId) -> Vec<Entity<T::EntityId>>,
+        ) -> Result<T::EntityId, RbacError> {
+            let entity_id = if T::UniqueNames::get() {
+                <NameIndexStore<T>>::get(rbac::generate_key(owner, &name, tag))
+            } else {
+                let entities = entities(owner);
+                let mut named = entities.iter().filter(|entity| entity.name == name);
+                let first = named.clone().next();
+                named
+                    .find(|entity| entity.enabled)
+                    .or(first)
+                    .map(|entity| entity.id)
+            };
+            match entity_id {
+                Some(entity_id) => Ok(entity_id),
+                None => RbacError::err(EntityDoesNotExist, &name.to_vec()),
+            }
+        }
+
+        /// Runs `f` with the policy changes it makes recorded in the audit log as made by
+        /// `actor`. Pallets changing policies through the `rbac` traits on behalf of an
+        /// account other than the owner should wrap their calls, otherwise the owner is
+        /// recorded.
+        pub fn with_actor<R>(actor: &T::AccountId, f: impl FnOnce() -> R) -> R {
+            let outer = <ActorStore<T>>::get();
+            <ActorStore<T>>::put(actor);
+            let res = f();
+            match outer {
+                Some(outer) => <ActorStore<T>>::put(outer),
+                None => <ActorStore<T>>::kill(),
+            }
+            res
+        }
+
+        /// Appends a change of the owner's policy to its audit log, overwriting the oldest
+        /// record once the log is full
+        fn record_change(owner: &T::AccountId, op: PolicyOp<T::EntityId>) {
+            let who = <ActorStore<T>>::get().unwrap_or_else(|| owner.clone());
+            Self::deposit_event(Event::PolicyChanged(owner.clone(), who.clone(), op.clone()));
+
+            let capacity = T::MaxAuditLogEntries::get();
+            if capacity == 0 {
+                return;
+            }
+
+            let seq = <AuditLogCountStore<T>>::get(owner);
+            let record = AuditRecord {
+                seq,
+                op,
+                who,
+                block_number: <frame_system::Pallet<T>>::block_number().unique_saturated_into(),
+                timestamp: <pallet_timestamp::Pallet<T>>::get().unique_saturated_into(),
+            };
+
+            <AuditLogStore<T>>::insert(owner, (seq % capacity as u64) as u32, record);
+            <AuditLogCountStore<T>>::insert(owner, seq.saturating_add(1));
+        }
+
+        /// Runs a change of the policy in a storage transaction, nothing is written if it
+        /// fails. Other pallets call the traits without the storage layer of a dispatchable.
+        fn transactional<R>(change: impl FnOnce() -> Result<R, RbacError>) -> Result<R, RbacError> {
+            with_transaction_unchecked(|| match change() {
+                Ok(r) => TransactionOutcome::Commit(Ok(r)),
+                Err(e) => TransactionOutcome::Rollback(Err(e)),
+            })
+        }
+
+        /// Replaces the leaf `old` by `new` in the owner's policy commitment and updates
+        /// its root
+        fn update_commitment(
+            owner: &T::AccountId,
+            old: Option<PolicyLeaf<T::EntityId>>,
+            new: Option<PolicyLeaf<T::EntityId>>,
+        ) -> Result<(), RbacError> {
+            Self::update_policy_tree(owner, old.map(|l| l.hash()), new.map(|l| l.hash()))
+        }
+
+        /// Replaces the leaf hash `old` by `new` in the owner's policy tree, nothing is
+        /// written on error. Trees may only grow beyond `MaxPolicyTreeDepth` by a migration,
+        /// then they still can shrink.
+        pub(crate) fn update_policy_tree(
+            owner: &T::AccountId,
+            old: Option<H256>,
+            new: Option<H256>,
+        ) -> Result<(), RbacError> {
+            let mut nodes = OwnerTreeNodes::<T>::new(owner);
+            let before = <PolicyTreeRootStore<T>>::get(owner);
+            let mut root = before;
+            if let Some(leaf) = old {
+                root = commitment::remove_leaf(&mut nodes, root, &leaf);
+            }
+            if let Some(leaf) = new {
+                root = commitment::insert_leaf(&mut nodes, root, leaf);
+            }
+
+            let height = root.map_or(0, |root| root.height());
+            if height > T::MaxPolicyTreeDepth::get() && height > before.map_or(0, |b| b.height()) {
+                return RbacError::err(PolicyTreeTooDeep, owner);
+            }
+
+            nodes.commit();
+            match root {
+                Some(root) => {
+                    <PolicyCommitmentStore<T>>::insert(owner, root.hash());
+                    <PolicyTreeRootStore<T>>::insert(owner, root);
+                }
+                None => {
+                    <PolicyCommitmentStore<T>>::remove(owner);
+                    <PolicyTreeRootStore<T>>::remove(owner);
+                }
+            }
+            Ok(())
+        }
+
+        /// Weight of `count` policy changes on top of their extrinsics: updating the
+        /// commitment, charged for a path of `MaxPolicyTreeDepth` branches, announcing it,
+        /// appending to the audit log and maintaining the name index
+        pub fn policy_change_weight(count: u32) -> Weight {
+            // ActorStore read and the PolicyChanged event deposited
+            let event_weight = T::DbWeight::get().reads_writes(1, 1);
+            let audit_weight = if T::MaxAuditLogEntries::get() == 0 {
+                Weight::zero()
+            } else {
+                // AuditLogCountStore and Timestamp read, AuditLogStore and
+                // AuditLogCountStore written
+                T::DbWeight::get().reads_writes(2, 2)
+            };
+            let names_weight = if T::UniqueNames::get() {
+                // NameIndexStore read and written for the new and the old name,
+                // NameClaimsStore read and written, and within batches the claimant and
+                // the displaced entity read from KeysLookUpStore once it is applied
+                T::DbWeight::get().reads_writes(6, 3)
+            } else {
+                Weight::zero()
+            };
+            T::WeightInfo::update_policy_tree(T::MaxPolicyTreeDepth::get())
+                .saturating_add(event_weight)
+                .saturating_add(audit_weight)
+                .saturating_add(names_weight)
+                .saturating_mul(count as u64)
+        }
+
+        /// Applies all operations for an owner within one storage transaction. Each operation
+        /// is validated against the state left by the ones before it, so the combined end
+        /// state is checked before anything is committed. Unique names are checked against
+        /// the end state, so operations may swap them. Nothing is written if any fails.
+        pub fn execute_policy_ops(
+            owner: &T::AccountId,
+            ops: &[PolicyOp<T::EntityId>],
+        ) -> Result<(), RbacError> {
+            with_transaction_unchecked(|| {
+                let outer_claims = <NameClaimsStore<T>>::get();
+                <NameClaimsStore<T>>::put(Vec::<NameClaim<T::EntityId>>::new());
+                let res = ops
+                    .iter()
+                    .try_for_each(|op| Self::execute_policy_op(owner, op));
+                let claims = <NameClaimsStore<T>>::take().unwrap_or_default();
+                let res = res.and_then(|_| match outer_claims {
+                    // Nested batches are checked along with the outer one
+                    Some(mut outer_claims) => {
+                        outer_claims.extend(claims);
+                        <NameClaimsStore<T>>::put(outer_claims);
+                        Ok(())
+                    }
+                    None => Self::check_name_claims(claims),
+                });
+                match res {
+                    Ok(()) => TransactionOutcome::Commit(Ok(())),
+                    Err(e) => TransactionOutcome::Rollback(Err(e)),
+                }
+            })
+        }
+
+        fn execute_policy_op(
+            owner: &T::AccountId,
+            op: &PolicyOp<T::EntityId>,
+        ) -> Result<(), RbacError> {
+            match op {
+                PolicyOp::AddRole(role_id, name) => Self::create_role(owner, *role_id, name),
+                PolicyOp::UpdateRole(role_id, name) => {
+                    Self::update_existing_role(owner, *role_id, name)
+                }
+                PolicyOp::DisableRole(role_id) => Self::disable_existing_role(owner, *role_id),
+                PolicyOp::AddPermission(permission_id, name) => {
+                    Self::create_permission(owner, *permission_id, name)
+                }
+                PolicyOp::UpdatePermission(permission_id, name) => {
+                    Self::update_existing_permission(owner, *permission_id, name)
+                }
+                PolicyOp::DisablePermission(permission_id) => {
+                    Self::disable_existing_permission(owner, *permission_id)
+                }
+                PolicyOp::AddGroup(group_id, name) => Self::create_group(owner, *group_id, name),
+                PolicyOp::UpdateGroup(group_id, name) => {
+                    Self::update_existing_group(owner, *group_id, name)
+                }
+                PolicyOp::DisableGroup(group_id) => Self::disable_existing_group(owner, *group_id),
+                PolicyOp::AssignRoleToUser(role_id, user_id) => {
+                    Self::create_role_to_user(owner, *role_id, *user_id)
+                }
+                PolicyOp::UnassignRoleToUser(role_id, user_id) => {
+                    Self::revoke_role_to_user(owner, *role_id, *user_id)
+                }
+                PolicyOp::AssignRoleToGroup(role_id, group_id) => {
+                    Self::create_role_to_group(owner, *role_id, *group_id)
+                }
+                PolicyOp::UnassignRoleToGroup(role_id, group_id) => {
+                    Self::revoke_role_to_group(owner, *role_id, *group_id)
+                }
+                PolicyOp::AssignUserToGroup(user_id, group_id) => {
+                    Self::create_user_to_group(owner, *user_id, *group_id)
+                }
+                PolicyOp::UnassignUserToGroup(user_id, group_id) => {
+                    Self::revoke_user_to_group(owner, *user_id, *group_id)
+                }
+                PolicyOp::AssignPermissionToRole(permission_id, role_id) => {
+                    Self::create_permission_to_role(owner, *permission_id, *role_id)
+                }
+                PolicyOp::UnassignPermissionToRole(permission_id, role_id) => {
+                    Self::revoke_permission_to_role(owner, *permission_id, *role_id)
+                }
+                PolicyOp::EnableRole(role_id) => Self::enable_existing_role(owner, *role_id),
+                PolicyOp::EnablePermission(permission_id) => {
+                    Self::enable_existing_permission(owner, *permission_id)
+                }
+                PolicyOp::EnableGroup(group_id) => Self::enable_existing_group(owner, *group_id),
+            }
+        }
+
+        /// Maps an applied operation to the event its single extrinsic would have emitted
+        fn policy_op_event(who: T::AccountId, op: PolicyOp<T::EntityId>) -> Event<T> {
+            match op {
+                PolicyOp::AddRole(id, name) => Event::RoleAdded(who, id, name),
+                PolicyOp::UpdateRole(id, name) => Event::RoleUpdated(who, id, name),
+                PolicyOp::DisableRole(id) => Event::RoleRemoved(who, id),
+                PolicyOp::AddPermission(id, name) => Event::PermissionAdded(who, id, name),
+                PolicyOp::UpdatePermission(id, name) => Event::PermissionUpdated(who, id, name),
+                PolicyOp::DisablePermission(id) => Event::PermissionDisabled(who, id),
+                PolicyOp::AddGroup(id, name) => Event::GroupAdded(who, id, name),
+                PolicyOp::UpdateGroup(id, name) => Event::GroupUpdated(who, id, name),
+                PolicyOp::DisableGroup(id) => Event::GroupDisabled(who, id),
+                PolicyOp::AssignRoleToUser(role, user) => {
+                    Event::RoleAssignedToUser(who, role, user)
+                }
+                PolicyOp::UnassignRoleToUser(role, user) => {
+                    Event::RoleUnassignedToUser(who, role, user)
+                }
+                PolicyOp::AssignRoleToGroup(role, group) => {
+                    Event::RoleAssignedToGroup(who, role, group)
+                }
+                PolicyOp::UnassignRoleToGroup(role, group) => {
+                    Event::RoleUnassignedToGroup(who, role, group)
+                }
+                PolicyOp::AssignUserToGroup(user, group) => {
+                    Event::UserAssignedToGroup(who, user, group)
+                }
+                PolicyOp::UnassignUserToGroup(user, group) => {
+                    Event::UserUnAssignedToGroup(who, user, group)
+                }
+                PolicyOp::AssignPermissionToRole(permission, role) => {
+                    Event::PermissionAssigned(who, permission, role)
+                }
+                PolicyOp::UnassignPermissionToRole(permission, role) => {
+                    Event::PermissionUnassignedToRole(who, permission, role)
+                }
+                PolicyOp::EnableRole(id) => Event::RoleEnabled(who, id),
+                PolicyOp::EnablePermission(id) => Event::PermissionEnabled(who, id),
+                PolicyOp::EnableGroup(id) => Event::GroupEnabled(who, id),
+            }
+        }
+
+        /// Sums up the benchmarked weights of the single extrinsics behind each operation
+        pub fn policy_ops_weight(ops: &[PolicyOp<T::EntityId>]) -> Weight {
+            let ops_weight = ops.iter().fold(Weight::zero(), |acc, op| {
+                acc.saturating_add(match op {
+                    PolicyOp::AddRole(..) => T::WeightInfo::add_role(),
+                    PolicyOp::UpdateRole(..) => T::WeightInfo::update_role(),
+                    PolicyOp::DisableRole(..) => T::WeightInfo::disable_role(),
+                    PolicyOp::AddPermission(..) => T::WeightInfo::add_permission(),
+                    PolicyOp::UpdatePermission(..) => T::WeightInfo::update_permission(),
+                    PolicyOp::DisablePermission(..) => T::WeightInfo::disable_permission(),
+                    PolicyOp::AddGroup(..) => T::WeightInfo::add_group(),
+                    PolicyOp::UpdateGroup(..) => T::WeightInfo::update_group(),
+                    PolicyOp::DisableGroup(..) => T::WeightInfo::disable_group(),
+                    PolicyOp::AssignRoleToUser(..) => T::WeightInfo::assign_role_to_user(),
+                    PolicyOp::UnassignRoleToUser(..) => T::WeightInfo::unassign_role_to_user(),
+                    PolicyOp::AssignRoleToGroup(..) => T::WeightInfo::assign_role_to_group(),
+                    PolicyOp::UnassignRoleToGroup(..) => T::WeightInfo::unassign_role_to_group(),
+                    PolicyOp::AssignUserToGroup(..) => T::WeightInfo::assign_user_to_group(),
+                    PolicyOp::UnassignUserToGroup(..) => T::WeightInfo::unassign_user_to_group(),
+                    PolicyOp::AssignPermissionToRole(..) => {
+                        T::WeightInfo::assign_permission_to_role()
+                    }
+                    PolicyOp::UnassignPermissionToRole(..) => {
+                        T::WeightInfo::unassign_permission_to_role()
+                    }
+                    // Enabling touches the same storage as disabling
+                    PolicyOp::EnableRole(..) => T::WeightInfo::disable_role(),
+                    PolicyOp::EnablePermission(..) => T::WeightInfo::disable_permission(),
+                    PolicyOp::EnableGroup(..) => T::WeightInfo::disable_group(),
+                })
+            });
+            ops_weight.saturating_add(Self::policy_change_weight(ops.len() as u32))
+        }
+    }
+
+    // implement the Rbac trait to satify the methods
+    impl<T: Config> Rbac<T::AccountId, T::EntityId> for Pallet<T> {
+        fn get_entity(
+            owner: &T::AccountId,
+            entity_id: &T::EntityId,
+            tag: Tag,
+        ) -> Result<Entity<T::EntityId>, RbacError> {
+            let key = Self::generate_key(owner, entity_id, tag);
+
+            if !<KeysLookUpStore<T>>::contains_key(key) {
+                return RbacError::err(EntityDoesNotExist, entity_id);
+            }
+
+            let entity = <KeysLookUpStore<T>>::get(key);
+
+            if !entity.enabled {
+                return RbacError::err(EntityDisabled, entity_id);
+            }
+
+            Ok(entity)
+        }
+
+        fn check_entity_get_key(
+            owner: &T::AccountId,
+            entity_id: &T::EntityId,
+            tag: Tag,
+        ) -> Result<RbacKeyType, RbacError> {
+            let key = Self::generate_key(owner, entity_id, tag);
+
+            if !<KeysLookUpStore<T>>::contains_key(key) {
+                return RbacError::err(EntityDoesNotExist, entity_id);
+            }
+
+            let entity = <KeysLookUpStore<T>>::get(key);
+
+            if !entity.enabled {
+                return RbacError::err(EntityDisabled, entity_id);
+            }
+
+            Ok(key)
+        }
+
+        fn get_user_roles(
+            owner: &T::AccountId,
+            user_id: T::EntityId,
+        ) -> Result<Vec<Role2User<T::EntityId>>, RbacError> {
+            // Generate key for integrity check
+            let key = Self::generate_key(owner, &user_id, Tag::Role2User);
+
+            if <Role2UserStore<T>>::contains_key(key) {
+                Ok(Self::role_to_user_of(key))
+            } else {
+                RbacError::err(AssignmentDoesNotExist, &user_id)
+            }
+        }
+
+        fn get_user_groups(
+            owner: &T::AccountId,
+            user_id: T::EntityId,
+        ) -> Result<Vec<User2Group<T::EntityId>>, RbacError> {
+            // Generate key for integrity check
+            let key = Self::generate_key(owner, &user_id, Tag::User2Group);
+
+            if <User2GroupStore<T>>::contains_key(key) {
+                Ok(Self::user_to_group_of(key))
+            } else {
+                RbacError::err(AssignmentDoesNotExist, &user_id)
+            }
+        }
+
+        fn get_group_roles(
+            owner: &T::AccountId,
+            group_id: T::EntityId,
+        ) -> Result<Vec<Role2Group<T::EntityId>>, RbacError> {
+            // Generate key for integrity check
+            let key = Self::generate_key(owner, &group_id, Tag::Role2Group);
+
+            if <Role2GroupStore<T>>::contains_key(key) {
+                Ok(Self::role_to_group_of(key))
+            } else {
+                RbacError::err(AssignmentDoesNotExist, &group_id)
+            }
+        }
+
+        fn get_role_permissions(
+            owner: &T::AccountId,
+            role_id: T::EntityId,
+        ) -> Result<Vec<Permission2Role<T::EntityId>>, RbacError> {
+            // Generate key for integrity check
+            let key = Self::generate_key(owner, &role_id, Tag::Permission2Role);
+
+            if <Permission2RoleStore<T>>::contains_key(key) {
+                Ok(Self::permission_to_role_of(key))
+            } else {
+                RbacError::err(AssignmentDoesNotExist, &role_id)
+            }
+        }
+
+        fn get_user_permissions(
+            owner: &T::AccountId,
+            user_id: T::EntityId,
+        ) -> Result<Vec<Entity<T::EntityId>>, RbacError> {
+            // Generate key for integrity check
+            let role_2_user_key = Self::generate_key(owner, &user_id, Tag::Role2User);
+            let user_2_group_key = Self::generate_key(owner, &user_id, Tag::User2Group);
+
+            let mut permissions: Vec<Entity<T::EntityId>> = vec![];
+            // use to avoid duplicate transverval
+            let mut processed_roles: Vec<T::EntityId> = vec![];
+
+            for r2u in <Role2UserStore<T>>::get(role_2_user_key).iter() {
+                // use to avoid duplicate transversal
+                processed_roles.push(r2u.role);
+                Self::collect_role_permissions(owner, r2u.role, &mut permissions);
+            }
+
+            for u2g in <User2GroupStore<T>>::get(user_2_group_key).iter() {
+                if Self::get_group(owner, u2g.group).is_err() {
+                    continue;
+                }
+                let key = Self::generate_key(owner, &u2g.group, Tag::Role2Group);
+                for r2g in <Role2GroupStore<T>>::get(key).iter() {
+                    // use to avoid duplicate transversal
+                    if !processed_roles.contains(&r2g.role) {
+                        processed_roles.push(r2g.role);
+                        Self::collect_role_permissions(owner, r2g.role, &mut permissions);
+                    }
                 }
             }
 
             Ok(permissions)
         }
 
+        fn get_group_permissions(
+            owner: &T::AccountId,
+            group_id: T::EntityId,
+        ) -> Result<Vec<Entity<T::EntityId>>, RbacError> {
+            let mut permissions: Vec<Entity<T::EntityId>> = vec![];
+            if Self::get_group(owner, group_id).is_err() {
+                return Ok(permissions);
+            }
+
+            let key = Self::generate_key(owner, &group_id, Tag::Role2Group);
+            for r2g in <Role2GroupStore<T>>::get(key).iter() {
+                Self::collect_role_permissions(owner, r2g.role, &mut permissions);
+            }
+
+            Ok(permissions)
+        }
+
+        fn has_permission(
+            owner: &T::AccountId,
+            user_id: T::EntityId,
+            permission_id: T::EntityId,
+        ) -> bool {
+            matches!(
+                Self::permission_lookups(owner, user_id, permission_id, u32::MAX),
+                Ok((true, _))
+            )
+        }
+
+        fn has_role(owner: &T::AccountId, user_id: T::EntityId, role_id: T::EntityId) -> bool {
+            if Self::get_role(owner, role_id).is_err() {
+                return false;
+            }
+
+            let role_2_user_key = Self::generate_key(owner, &user_id, Tag::Role2User);
+            if <Role2UserStore<T>>::get(role_2_user_key)
+                .iter()
+                .any(|r2u| r2u.role == role_id)
+            {
+                return true;
+            }
+
+            let user_2_group_key = Self::generate_key(owner, &user_id, Tag::User2Group);
+            <User2GroupStore<T>>::get(user_2_group_key)
+                .iter()
+                .filter(|u2g| Self::get_group(owner, u2g.group).is_ok())
+                .any(|u2g| {
+                    let key = Self::generate_key(owner, &u2g.group, Tag::Role2Group);
+                    <Role2GroupStore<T>>::get(key)
+                        .iter()
+                        .any(|r2g| r2g.role == role_id)
+                })
+        }
+
         fn create_role_to_user(
             owner: &T::AccountId,
             role_id: T::EntityId,
             user_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let role_key = Self::generate_key(owner, &role_id, Tag::Role);
-            let role_2_user_key = Self::generate_key(owner, &user_id, Tag::Role2User);
+            Self::transactional(|| {
+                // Generate key for integrity check
+                let role_key = Self::generate_key(owner, &role_id, Tag::Role);
+                let role_2_user_key = Self::generate_key(owner, &user_id, Tag::Role2User);
+
+                // Check if role exists
+                if !<KeysLookUpStore<T>>::contains_key(role_key) {
+                    return RbacError::err(EntityDoesNotExist, &role_id);
+                }
 
-            // Check if role exists
-            if !<KeysLookUpStore<T>>::contains_key(role_key) {
-                return RbacError::err(EntityDoesNotExist, &role_id);
-            }
+                let mut roles: Vec<Role2User<T::EntityId>> = vec![];
 
-            let mut roles: Vec<Role2User<T::EntityId>> = vec![];
+                let new_assign = Role2User {
+                    role: role_id,
+                    user: user_id,
+                };
 
-            let new_assign = Role2User {
-                role: role_id,
-                user: user_id,
-            };
+                // Check if role has already been assigned to user
+                if <Role2UserStore<T>>::contains_key(role_2_user_key) {
+                    let mut val = <Role2UserStore<T>>::get(role_2_user_key);
 
-            // Check if role has already been assigned to user
-            if <Role2UserStore<T>>::contains_key(role_2_user_key) {
-                let mut val = <Role2UserStore<T>>::get(role_2_user_key);
+                    if val.contains(&new_assign) {
+                        return RbacError::err(AssignmentAlreadyExist, &user_id);
+                    }
 
-                if val.contains(&new_assign) {
-                    return RbacError::err(AssignmentAlreadyExist, &user_id);
+                    roles.append(&mut val);
                 }
+                roles.push(new_assign.clone());
 
-                roles.append(&mut val);
-            }
-            roles.push(new_assign);
+                <Role2UserStore<T>>::insert(role_2_user_key, roles);
 
-            <Role2UserStore<T>>::insert(role_2_user_key, roles);
+                Self::update_commitment(owner, None, Some(PolicyLeaf::Role2User(new_assign)))?;
+                Self::record_change(owner, PolicyOp::AssignRoleToUser(role_id, user_id));
+                Self::update_user_index(owner, user_id);
 
-            Ok(())
+                Ok(())
+            })
         }
 
         fn revoke_role_to_user(
@@ -896,39 +2534,45 @@ pub mod pallet {
             role_id: T::EntityId,
             user_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let role_2_user_key = Self::generate_key(owner, &user_id, Tag::Role2User);
+            Self::transactional(|| {
+                // Generate key for integrity check
+                let role_2_user_key = Self::generate_key(owner, &user_id, Tag::Role2User);
 
-            // Check if role exists
-            if !<Role2UserStore<T>>::contains_key(role_2_user_key) {
-                return RbacError::err(AssignmentDoesNotExist, &user_id);
-            }
+                // Check if role exists
+                if !<Role2UserStore<T>>::contains_key(role_2_user_key) {
+                    return RbacError::err(AssignmentDoesNotExist, &user_id);
+                }
 
-            let new_assign = Role2User {
-                role: role_id,
-                user: user_id,
-            };
+                let new_assign = Role2User {
+                    role: role_id,
+                    user: user_id,
+                };
 
-            let mut val = <Role2UserStore<T>>::get(role_2_user_key);
+                let mut val = <Role2UserStore<T>>::get(role_2_user_key);
 
-            if !val.contains(&new_assign) {
-                return RbacError::err(AssignmentDoesNotExist, &user_id);
-            }
+                if !val.contains(&new_assign) {
+                    return RbacError::err(AssignmentDoesNotExist, &user_id);
+                }
 
-            match val.binary_search(&new_assign) {
-                Ok(i) => val.remove(i),
-                Err(_) => return RbacError::err(AssignmentDoesNotExist, &user_id),
-            };
+                match val.binary_search(&new_assign) {
+                    Ok(i) => val.remove(i),
+                    Err(_) => return RbacError::err(AssignmentDoesNotExist, &user_id),
+                };
 
-            if val.is_empty() {
-                <Role2UserStore<T>>::remove(role_2_user_key);
-            }
+                if val.is_empty() {
+                    <Role2UserStore<T>>::remove(role_2_user_key);
+                }
 
-            if !val.is_empty() {
-                <Role2UserStore<T>>::mutate(role_2_user_key, |a| *a = val);
-            }
+                if !val.is_empty() {
+                    <Role2UserStore<T>>::mutate(role_2_user_key, |a| *a = val);
+                }
 
-            Ok(())
+                Self::update_commitment(owner, Some(PolicyLeaf::Role2User(new_assign)), None)?;
+                Self::record_change(owner, PolicyOp::UnassignRoleToUser(role_id, user_id));
+                Self::update_user_index(owner, user_id);
+
+                Ok(())
+            })
         }
 
         fn create_role_to_group(
@@ -936,43 +2580,48 @@ pub mod pallet {
             role_id: T::EntityId,
             group_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let group_key = Self::generate_key(owner, &group_id, Tag::Group);
-            let role_key = Self::generate_key(owner, &role_id, Tag::Role);
-            let role_2_group_key = Self::generate_key(owner, &group_id, Tag::Role2Group);
+            Self::transactional(|| {
+                // Generate key for integrity check
+                let group_key = Self::generate_key(owner, &group_id, Tag::Group);
+                let role_key = Self::generate_key(owner, &role_id, Tag::Role);
+                let role_2_group_key = Self::generate_key(owner, &group_id, Tag::Role2Group);
+
+                // Check if role exists
+                if !<KeysLookUpStore<T>>::contains_key(role_key) {
+                    return RbacError::err(EntityDoesNotExist, &role_id);
+                }
 
-            // Check if role exists
-            if !<KeysLookUpStore<T>>::contains_key(role_key) {
-                return RbacError::err(EntityDoesNotExist, &role_id);
-            }
+                // Check if group exists
+                if !<KeysLookUpStore<T>>::contains_key(group_key) {
+                    return RbacError::err(EntityDoesNotExist, &group_id);
+                }
 
-            // Check if group exists
-            if !<KeysLookUpStore<T>>::contains_key(group_key) {
-                return RbacError::err(EntityDoesNotExist, &group_id);
-            }
+                let mut roles: Vec<Role2Group<T::EntityId>> = vec![];
 
-            let mut roles: Vec<Role2Group<T::EntityId>> = vec![];
+                let new_assign = Role2Group {
+                    role: role_id,
+                    group: group_id,
+                };
 
-            let new_assign = Role2Group {
-                role: role_id,
-                group: group_id,
-            };
+                // Check if role has already been assigned to group
+                if <Role2GroupStore<T>>::contains_key(role_2_group_key) {
+                    let mut val = <Role2GroupStore<T>>::get(role_2_group_key);
 
-            // Check if role has already been assigned to group
-            if <Role2GroupStore<T>>::contains_key(role_2_group_key) {
-                let mut val = <Role2GroupStore<T>>::get(role_2_group_key);
+                    if val.contains(&new_assign) {
+                        return RbacError::err(AssignmentAlreadyExist, &group_id);
+                    }
 
-                if val.contains(&new_assign) {
-                    return RbacError::err(AssignmentAlreadyExist, &group_id);
+                    roles.append(&mut val);
                 }
+                roles.push(new_assign.clone());
 
-                roles.append(&mut val);
-            }
-            roles.push(new_assign);
+                <Role2GroupStore<T>>::insert(role_2_group_key, roles);
 
-            <Role2GroupStore<T>>::insert(role_2_group_key, roles);
+                Self::update_commitment(owner, None, Some(PolicyLeaf::Role2Group(new_assign)))?;
+                Self::record_change(owner, PolicyOp::AssignRoleToGroup(role_id, group_id));
 
-            Ok(())
+                Ok(())
+            })
         }
 
         fn revoke_role_to_group(
@@ -980,37 +2629,42 @@ pub mod pallet {
             role_id: T::EntityId,
             group_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let role_2_group_key = Self::generate_key(owner, &group_id, Tag::Role2Group);
+            Self::transactional(|| {
+                // Generate key for integrity check
+                let role_2_group_key = Self::generate_key(owner, &group_id, Tag::Role2Group);
 
-            // Check if role exists
-            if !<Role2GroupStore<T>>::contains_key(role_2_group_key) {
-                return RbacError::err(AssignmentDoesNotExist, &group_id);
-            }
+                // Check if role exists
+                if !<Role2GroupStore<T>>::contains_key(role_2_group_key) {
+                    return RbacError::err(AssignmentDoesNotExist, &group_id);
+                }
 
-            let new_assign = Role2Group {
-                role: role_id,
-                group: group_id,
-            };
+                let new_assign = Role2Group {
+                    role: role_id,
+                    group: group_id,
+                };
 
-            let mut val = <Role2GroupStore<T>>::get(role_2_group_key);
+                let mut val = <Role2GroupStore<T>>::get(role_2_group_key);
 
-            if !val.contains(&new_assign) {
-                return RbacError::err(AssignmentDoesNotExist, &group_id);
-            }
+                if !val.contains(&new_assign) {
+                    return RbacError::err(AssignmentDoesNotExist, &group_id);
+                }
 
-            match val.binary_search(&new_assign) {
-                Ok(i) => val.remove(i),
-                Err(_) => return RbacError::err(AssignmentDoesNotExist, &group_id),
-            };
+                match val.binary_search(&new_assign) {
+                    Ok(i) => val.remove(i),
+                    Err(_) => return RbacError::err(AssignmentDoesNotExist, &group_id),
+                };
 
-            if val.is_empty() {
-                <Role2GroupStore<T>>::remove(role_2_group_key);
-            } else {
-                <Role2GroupStore<T>>::mutate(role_2_group_key, |a| *a = val);
-            }
+                if val.is_empty() {
+                    <Role2GroupStore<T>>::remove(role_2_group_key);
+                } else {
+                    <Role2GroupStore<T>>::mutate(role_2_group_key, |a| *a = val);
+                }
 
-            Ok(())
+                Self::update_commitment(owner, Some(PolicyLeaf::Role2Group(new_assign)), None)?;
+                Self::record_change(owner, PolicyOp::UnassignRoleToGroup(role_id, group_id));
+
+                Ok(())
+            })
         }
 
         fn create_user_to_group(
@@ -1018,37 +2672,43 @@ pub mod pallet {
             user_id: T::EntityId,
             group_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let group_key = Self::generate_key(owner, &group_id, Tag::Group);
-            let user_2_group_key = Self::generate_key(owner, &user_id, Tag::User2Group);
+            Self::transactional(|| {
+                // Generate key for integrity check
+                let group_key = Self::generate_key(owner, &group_id, Tag::Group);
+                let user_2_group_key = Self::generate_key(owner, &user_id, Tag::User2Group);
+
+                // Check if group exists
+                if !<KeysLookUpStore<T>>::contains_key(group_key) {
+                    return RbacError::err(EntityDoesNotExist, &group_id);
+                }
 
-            // Check if group exists
-            if !<KeysLookUpStore<T>>::contains_key(group_key) {
-                return RbacError::err(EntityDoesNotExist, &group_id);
-            }
+                let mut groups: Vec<User2Group<T::EntityId>> = vec![];
 
-            let mut groups: Vec<User2Group<T::EntityId>> = vec![];
+                let new_assign = User2Group {
+                    user: user_id,
+                    group: group_id,
+                };
 
-            let new_assign = User2Group {
-                user: user_id,
-                group: group_id,
-            };
+                // Check if role has already been assigned to group
+                if <User2GroupStore<T>>::contains_key(user_2_group_key) {
+                    let mut val = <User2GroupStore<T>>::get(user_2_group_key);
 
-            // Check if role has already been assigned to group
-            if <User2GroupStore<T>>::contains_key(user_2_group_key) {
-                let mut val = <User2GroupStore<T>>::get(user_2_group_key);
+                    if val.contains(&new_assign) {
+                        return RbacError::err(AssignmentAlreadyExist, &group_id);
+                    }
 
-                if val.contains(&new_assign) {
-                    return RbacError::err(AssignmentAlreadyExist, &group_id);
+                    groups.append(&mut val);
                 }
+                groups.push(new_assign.clone());
 
-                groups.append(&mut val);
-            }
-            groups.push(new_assign);
+                <User2GroupStore<T>>::insert(user_2_group_key, groups);
 
-            <User2GroupStore<T>>::insert(user_2_group_key, groups);
+                Self::update_commitment(owner, None, Some(PolicyLeaf::User2Group(new_assign)))?;
+                Self::record_change(owner, PolicyOp::AssignUserToGroup(user_id, group_id));
+                Self::update_user_index(owner, user_id);
 
-            Ok(())
+                Ok(())
+            })
         }
 
         fn revoke_user_to_group(
@@ -1056,39 +2716,45 @@ pub mod pallet {
             user_id: T::EntityId,
             group_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let user_2_group_key = Self::generate_key(owner, &user_id, Tag::User2Group);
+            Self::transactional(|| {
+                // Generate key for integrity check
+                let user_2_group_key = Self::generate_key(owner, &user_id, Tag::User2Group);
 
-            // Check if user exists
-            if !<User2GroupStore<T>>::contains_key(user_2_group_key) {
-                return RbacError::err(AssignmentDoesNotExist, &group_id);
-            }
+                // Check if user exists
+                if !<User2GroupStore<T>>::contains_key(user_2_group_key) {
+                    return RbacError::err(AssignmentDoesNotExist, &group_id);
+                }
 
-            let new_assign = User2Group {
-                user: user_id,
-                group: group_id,
-            };
+                let new_assign = User2Group {
+                    user: user_id,
+                    group: group_id,
+                };
 
-            let mut val = <User2GroupStore<T>>::get(user_2_group_key);
+                let mut val = <User2GroupStore<T>>::get(user_2_group_key);
 
-            if !val.contains(&new_assign) {
-                return RbacError::err(AssignmentDoesNotExist, &group_id);
-            }
+                if !val.contains(&new_assign) {
+                    return RbacError::err(AssignmentDoesNotExist, &group_id);
+                }
 
-            match val.binary_search(&new_assign) {
-                Ok(i) => val.remove(i),
-                Err(_) => return RbacError::err(AssignmentDoesNotExist, &group_id),
-            };
+                match val.binary_search(&new_assign) {
+                    Ok(i) => val.remove(i),
+                    Err(_) => return RbacError::err(AssignmentDoesNotExist, &group_id),
+                };
 
-            if val.is_empty() {
-                <User2GroupStore<T>>::remove(user_2_group_key);
-            }
+                if val.is_empty() {
+                    <User2GroupStore<T>>::remove(user_2_group_key);
+                }
 
-            if !val.is_empty() {
-                <User2GroupStore<T>>::mutate(user_2_group_key, |a| *a = val);
-            }
+                if !val.is_empty() {
+                    <User2GroupStore<T>>::mutate(user_2_group_key, |a| *a = val);
+                }
 
-            Ok(())
+                Self::update_commitment(owner, Some(PolicyLeaf::User2Group(new_assign)), None)?;
+                Self::record_change(owner, PolicyOp::UnassignUserToGroup(user_id, group_id));
+                Self::update_user_index(owner, user_id);
+
+                Ok(())
+            })
         }
 
         fn create_permission_to_role(
@@ -1096,43 +2762,56 @@ pub mod pallet {
             permission_id: T::EntityId,
             role_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let role_key = Self::generate_key(owner, &role_id, Tag::Role);
-            let permission_key = Self::generate_key(owner, &permission_id, Tag::Permission);
-            let permission_2_role_key = Self::generate_key(owner, &role_id, Tag::Permission2Role);
+            Self::transactional(|| {
+                // Generate key for integrity check
+                let role_key = Self::generate_key(owner, &role_id, Tag::Role);
+                let permission_key = Self::generate_key(owner, &permission_id, Tag::Permission);
+                let permission_2_role_key =
+                    Self::generate_key(owner, &role_id, Tag::Permission2Role);
+
+                // Check if role exists
+                if !<KeysLookUpStore<T>>::contains_key(role_key) {
+                    return RbacError::err(EntityDoesNotExist, &role_id);
+                }
 
-            // Check if role exists
-            if !<KeysLookUpStore<T>>::contains_key(role_key) {
-                return RbacError::err(EntityDoesNotExist, &role_id);
-            }
+                // Check if permission exists
+                if !<KeysLookUpStore<T>>::contains_key(permission_key) {
+                    return RbacError::err(EntityDoesNotExist, &permission_id);
+                }
 
-            // Check if permission exists
-            if !<KeysLookUpStore<T>>::contains_key(permission_key) {
-                return RbacError::err(EntityDoesNotExist, &permission_id);
-            }
+                let mut permissions: Vec<Permission2Role<T::EntityId>> = vec![];
 
-            let mut permissions: Vec<Permission2Role<T::EntityId>> = vec![];
+                let new_assign = Permission2Role {
+                    permission: permission_id,
+                    role: role_id,
+                };
 
-            let new_assign = Permission2Role {
-                permission: permission_id,
-                role: role_id,
-            };
+                // Check if permission has already been assigned to role
+                if <Permission2RoleStore<T>>::contains_key(permission_2_role_key) {
+                    let mut val = <Permission2RoleStore<T>>::get(permission_2_role_key);
 
-            // Check if permission has already been assigned to role
-            if <Permission2RoleStore<T>>::contains_key(permission_2_role_key) {
-                let mut val = <Permission2RoleStore<T>>::get(permission_2_role_key);
+                    if val.contains(&new_assign) {
+                        return RbacError::err(AssignmentAlreadyExist, &role_id);
+                    }
 
-                if val.contains(&new_assign) {
-                    return RbacError::err(AssignmentAlreadyExist, &role_id);
+                    permissions.append(&mut val);
                 }
-
-                permissions.append(&mut val);
-            }
-            permissions.push(new_assign);
-
-            <Permission2RoleStore<T>>::insert(permission_2_role_key, permissions);
-
-            Ok(())
+                permissions.push(new_assign.clone());
+
+                <Permission2RoleStore<T>>::insert(permission_2_role_key, permissions);
+
+                Self::update_commitment(
+                    owner,
+                    None,
+                    Some(PolicyLeaf::Permission2Role(new_assign)),
+                )?;
+                Self::record_change(
+                    owner,
+                    PolicyOp::AssignPermissionToRole(permission_id, role_id),
+                );
+
+                Ok(())
+            })
         }
 
         fn revoke_permission_to_role(
@@ -1140,48 +2819,212 @@ pub mod pallet {
             permission_id: T::EntityId,
             role_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let permission_2_role_key = Self::generate_key(owner, &role_id, Tag::Permission2Role);
+            Self::transactional(|| {
+                // Generate key for integrity check
+                let permission_2_role_key =
+                    Self::generate_key(owner, &role_id, Tag::Permission2Role);
+
+                // Check if permission exists
+                if !<Permission2RoleStore<T>>::contains_key(permission_2_role_key) {
+                    return RbacError::err(AssignmentDoesNotExist, &role_id);
+                }
 
-            // Check if permission exists
-            if !<Permission2RoleStore<T>>::contains_key(permission_2_role_key) {
-                return RbacError::err(AssignmentDoesNotExist, &role_id);
-            }
+                let new_assign = Permission2Role {
+                    permission: permission_id,
+                    role: role_id,
+                };
 
-            let new_assign = Permission2Role {
-                permission: permission_id,
-                role: role_id,
-            };
+                let mut val = <Permission2RoleStore<T>>::get(permission_2_role_key);
 
-            let mut val = <Permission2RoleStore<T>>::get(permission_2_role_key);
+                if !val.contains(&new_assign) {
+                    return RbacError::err(AssignmentDoesNotExist, &role_id);
+                }
 
-            if !val.contains(&new_assign) {
-                return RbacError::err(AssignmentDoesNotExist, &role_id);
-            }
+                match val.binary_search(&new_assign) {
+                    Ok(i) => val.remove(i),
+                    Err(_) => return RbacError::err(AssignmentDoesNotExist, &role_id),
+                };
 
-            match val.binary_search(&new_assign) {
-                Ok(i) => val.remove(i),
-                Err(_) => return RbacError::err(AssignmentDoesNotExist, &role_id),
-            };
+                if val.is_empty() {
+                    <Permission2RoleStore<T>>::remove(permission_2_role_key);
+                }
+
+                if !val.is_empty() {
+                    <Permission2RoleStore<T>>::mutate(permission_2_role_key, |a| *a = val);
+                }
+
+                Self::update_commitment(
+                    owner,
+                    Some(PolicyLeaf::Permission2Role(new_assign)),
+                    None,
+                )?;
+                Self::record_change(
+                    owner,
+                    PolicyOp::UnassignPermissionToRole(permission_id, role_id),
+                );
+
+                Ok(())
+            })
+        }
+
+        fn generate_key(owner: &T::AccountId, entity: &T::EntityId, tag: Tag) -> RbacKeyType {
+            rbac::generate_key(owner, entity, tag)
+        }
+    }
+
+    /// Branches of an owner's policy tree, kept in `PolicyTreeStore`. Changes are buffered
+    /// until they are committed, so each branch is written once.
+    struct OwnerTreeNodes<'a, T: Config> {
+        owner: &'a T::AccountId,
+        changes: BTreeMap<NodeId, Option<TreeNode>>,
+    }
 
-            if val.is_empty() {
-                <Permission2RoleStore<T>>::remove(permission_2_role_key);
+    impl<'a, T: Config> OwnerTreeNodes<'a, T> {
+        fn new(owner: &'a T::AccountId) -> Self {
+            OwnerTreeNodes {
+                owner,
+                changes: BTreeMap::new(),
             }
+        }
 
-            if !val.is_empty() {
-                <Permission2RoleStore<T>>::mutate(permission_2_role_key, |a| *a = val);
+        fn commit(self) {
+            for (id, node) in self.changes {
+                match node {
+                    Some(node) => <PolicyTreeStore<T>>::insert(self.owner, id, node),
+                    None => <PolicyTreeStore<T>>::remove(self.owner, id),
+                }
             }
+        }
+    }
 
-            Ok(())
+    impl<'a, T: Config> TreeNodes for OwnerTreeNodes<'a, T> {
+        fn node(&self, id: &NodeId) -> Option<TreeNode> {
+            match self.changes.get(id) {
+                Some(change) => *change,
+                None => <PolicyTreeStore<T>>::get(self.owner, id),
+            }
         }
 
-        fn generate_key(owner: &T::AccountId, entity: &T::EntityId, tag: Tag) -> RbacKeyType {
-            let mut bytes_in_tag: Vec<u8> = tag.to_string().as_bytes().to_vec();
-            let mut entity_bytes_to_hash: Vec<u8> = entity.encode().as_slice().to_vec();
-            let mut owner_bytes_to_hash: Vec<u8> = owner.encode().as_slice().to_vec();
-            owner_bytes_to_hash.append(&mut entity_bytes_to_hash);
-            owner_bytes_to_hash.append(&mut bytes_in_tag);
-            blake2_256(&owner_bytes_to_hash[..])
+        fn insert_node(&mut self, id: NodeId, node: TreeNode) {
+            self.changes.insert(id, Some(node));
+        }
+
+        fn remove_node(&mut self, id: &NodeId) {
+            self.changes.insert(*id, None);
+        }
+    }
+
+    // Operations only rollbacks may apply
+    impl<T: Config> Pallet<T> {
+        /// Re-enables a disabled role, does nothing if it is enabled. Only used by
+        /// rollbacks, as disabling stands for removing.
+        fn enable_existing_role(
+            owner: &T::AccountId,
+            role_id: T::EntityId,
+        ) -> Result<(), RbacError> {
+            Self::transactional(|| {
+                // Check if role exists, it may be disabled
+                let key = Self::generate_key(owner, &role_id, Tag::Role);
+                if !<KeysLookUpStore<T>>::contains_key(key) {
+                    return RbacError::err(EntityDoesNotExist, &role_id);
+                }
+                let mut val = <RoleStore<T>>::get(owner);
+
+                for entity in val.iter_mut() {
+                    if entity.id == role_id && !entity.enabled {
+                        let old = PolicyLeaf::Role(entity.clone());
+                        Self::index_name(owner, &role_id, &entity.name, Tag::Role)?;
+                        entity.enabled = true;
+                        <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                        Self::update_commitment(
+                            owner,
+                            Some(old),
+                            Some(PolicyLeaf::Role(entity.clone())),
+                        )?;
+                        Self::record_change(owner, PolicyOp::EnableRole(role_id));
+                        break;
+                    }
+                }
+
+                if !val.is_empty() {
+                    <RoleStore<T>>::mutate(owner, |v| *v = val);
+                }
+                Ok(())
+            })
+        }
+
+        /// Re-enables a disabled permission, does nothing if it is enabled. Only used by
+        /// rollbacks, as disabling stands for removing.
+        fn enable_existing_permission(
+            owner: &T::AccountId,
+            permission_id: T::EntityId,
+        ) -> Result<(), RbacError> {
+            Self::transactional(|| {
+                // Check if permission exists, it may be disabled
+                let key = Self::generate_key(owner, &permission_id, Tag::Permission);
+                if !<KeysLookUpStore<T>>::contains_key(key) {
+                    return RbacError::err(EntityDoesNotExist, &permission_id);
+                }
+                let mut val = <PermissionStore<T>>::get(owner);
+
+                for entity in val.iter_mut() {
+                    if entity.id == permission_id && !entity.enabled {
+                        let old = PolicyLeaf::Permission(entity.clone());
+                        Self::index_name(owner, &permission_id, &entity.name, Tag::Permission)?;
+                        entity.enabled = true;
+                        <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                        Self::update_commitment(
+                            owner,
+                            Some(old),
+                            Some(PolicyLeaf::Permission(entity.clone())),
+                        )?;
+                        Self::record_change(owner, PolicyOp::EnablePermission(permission_id));
+                        break;
+                    }
+                }
+
+                if !val.is_empty() {
+                    <PermissionStore<T>>::mutate(owner, |v| *v = val);
+                }
+                Ok(())
+            })
+        }
+
+        /// Re-enables a disabled group, does nothing if it is enabled. Only used by
+        /// rollbacks, as disabling stands for removing.
+        fn enable_existing_group(
+            owner: &T::AccountId,
+            group_id: T::EntityId,
+        ) -> Result<(), RbacError> {
+            Self::transactional(|| {
+                // Check if group exists, it may be disabled
+                let key = Self::generate_key(owner, &group_id, Tag::Group);
+                if !<KeysLookUpStore<T>>::contains_key(key) {
+                    return RbacError::err(EntityDoesNotExist, &group_id);
+                }
+                let mut val = <GroupStore<T>>::get(owner);
+
+                for entity in val.iter_mut() {
+                    if entity.id == group_id && !entity.enabled {
+                        let old = PolicyLeaf::Group(entity.clone());
+                        Self::index_name(owner, &group_id, &entity.name, Tag::Group)?;
+                        entity.enabled = true;
+                        <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                        Self::update_commitment(
+                            owner,
+                            Some(old),
+                            Some(PolicyLeaf::Group(entity.clone())),
+                        )?;
+                        Self::record_change(owner, PolicyOp::EnableGroup(group_id));
+                        break;
+                    }
+                }
+
+                if !val.is_empty() {
+                    <GroupStore<T>>::mutate(owner, |v| *v = val);
+                }
+                Ok(())
+            })
         }
     }
 
@@ -1198,38 +3041,56 @@ pub mod pallet {
             Ok(<RoleStore<T>>::get(owner))
         }
 
+        fn get_role_by_name(
+            owner: &T::AccountId,
+            name: &[u8],
+        ) -> Result<Entity<T::EntityId>, RbacError> {
+            let role_id = Self::entity_id_by_name(owner, name, Tag::Role, |owner| {
+                <RoleStore<T>>::get(owner)
+            })?;
+            Self::get_role(owner, role_id)
+        }
+
         fn create_role(
             owner: &T::AccountId,
             role_id: T::EntityId,
             name: &[u8],
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let key = Self::generate_key(owner, &role_id, Tag::Role);
+            Self::transactional(|| {
+                Self::check_name(&role_id, name)?;
 
-            // Check if role already exists
-            if <KeysLookUpStore<T>>::contains_key(key) {
-                return RbacError::err(EntityAlreadyExist, &role_id);
-            }
+                // Generate key for integrity check
+                let key = Self::generate_key(owner, &role_id, Tag::Role);
 
-            let mut roles: Vec<Entity<T::EntityId>> = vec![];
+                // Check if role already exists
+                if <KeysLookUpStore<T>>::contains_key(key) {
+                    return RbacError::err(EntityAlreadyExist, &role_id);
+                }
+                Self::index_name(owner, &role_id, name, Tag::Role)?;
 
-            let new_role = Entity {
-                id: role_id,
-                name: name.to_vec(),
-                enabled: true,
-            };
+                let mut roles: Vec<Entity<T::EntityId>> = vec![];
 
-            // Check if this account already had roles
-            if <RoleStore<T>>::contains_key(owner) {
-                let mut val = <RoleStore<T>>::get(owner);
-                roles.append(&mut val);
-            }
-            roles.push(new_role.clone());
+                let new_role = Entity {
+                    id: role_id,
+                    name: name.to_vec(),
+                    enabled: true,
+                };
+
+                // Check if this account already had roles
+                if <RoleStore<T>>::contains_key(owner) {
+                    let mut val = <RoleStore<T>>::get(owner);
+                    roles.append(&mut val);
+                }
+                roles.push(new_role.clone());
 
-            <RoleStore<T>>::insert(owner, roles);
-            <KeysLookUpStore<T>>::insert(key, new_role);
+                <RoleStore<T>>::insert(owner, roles);
+                <KeysLookUpStore<T>>::insert(key, new_role.clone());
 
-            Ok(())
+                Self::update_commitment(owner, None, Some(PolicyLeaf::Role(new_role)))?;
+                Self::record_change(owner, PolicyOp::AddRole(role_id, name.to_vec()));
+
+                Ok(())
+            })
         }
 
         fn update_existing_role(
@@ -1237,52 +3098,112 @@ pub mod pallet {
             role_id: T::EntityId,
             name: &[u8],
         ) -> Result<(), RbacError> {
-            // Check if role exists and it's enabled
-            let key = Self::check_entity_get_key(owner, &role_id, Tag::Role)?;
-
-            let mut val = <RoleStore<T>>::get(owner);
+            Self::transactional(|| {
+                // Check if role exists and it's enabled
+                let key = Self::check_entity_get_key(owner, &role_id, Tag::Role)?;
+                // A kept name isn't checked again, it may predate stricter rules
+                if <KeysLookUpStore<T>>::get(key).name != name {
+                    Self::check_name(&role_id, name)?;
+                }
+                Self::index_name(owner, &role_id, name, Tag::Role)?;
 
-            let iterator = val.iter_mut();
+                let mut val = <RoleStore<T>>::get(owner);
 
-            for entity in iterator {
-                if entity.id == role_id {
-                    entity.name = name.to_vec();
-                    <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                let iterator = val.iter_mut();
 
-                    break;
+                for entity in iterator {
+                    if entity.id == role_id {
+                        let old = PolicyLeaf::Role(entity.clone());
+                        if entity.name != name {
+                            Self::unindex_name(owner, &role_id, &entity.name, Tag::Role);
+                        }
+                        entity.name = name.to_vec();
+                        <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                        Self::update_commitment(
+                            owner,
+                            Some(old),
+                            Some(PolicyLeaf::Role(entity.clone())),
+                        )?;
+                        Self::record_change(owner, PolicyOp::UpdateRole(role_id, name.to_vec()));
+
+                        break;
+                    }
                 }
-            }
 
-            if !val.is_empty() {
-                <RoleStore<T>>::mutate(owner, |a| *a = val);
-            }
+                if !val.is_empty() {
+                    <RoleStore<T>>::mutate(owner, |a| *a = val);
+                }
 
-            Ok(())
+                Ok(())
+            })
         }
 
         fn disable_existing_role(
             owner: &T::AccountId,
             role_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Check if role exists and it's enabled and get key for integrity check
-            let key = Self::check_entity_get_key(owner, &role_id, Tag::Role)?;
+            Self::transactional(|| {
+                // Check if role exists and it's enabled and get key for integrity check
+                let key = Self::check_entity_get_key(owner, &role_id, Tag::Role)?;
 
-            let mut val = <RoleStore<T>>::get(owner);
+                let mut val = <RoleStore<T>>::get(owner);
 
-            let iterator = val.iter_mut();
+                let iterator = val.iter_mut();
+
+                for entity in iterator {
+                    if entity.id == role_id {
+                        let old = PolicyLeaf::Role(entity.clone());
+                        Self::unindex_name(owner, &role_id, &entity.name, Tag::Role);
+                        entity.enabled = false;
+                        <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                        Self::update_commitment(
+                            owner,
+                            Some(old),
+                            Some(PolicyLeaf::Role(entity.clone())),
+                        )?;
+                        Self::record_change(owner, PolicyOp::DisableRole(role_id));
+                        break;
+                    }
+                }
 
-            for entity in iterator {
-                if entity.id == role_id {
-                    entity.enabled = false;
-                    <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
-                    break;
+                if !val.is_empty() {
+                    <RoleStore<T>>::mutate(owner, |v| *v = val);
                 }
-            }
+                Ok(())
+            })
+        }
 
-            if !val.is_empty() {
-                <RoleStore<T>>::mutate(owner, |v| *v = val);
+        fn clone_existing_role(
+            owner: &T::AccountId,
+            source_role_id: T::EntityId,
+            role_id: T::EntityId,
+            name: &[u8],
+        ) -> Result<Vec<T::EntityId>, RbacError> {
+            // Check if source role exists and it's enabled
+            Self::check_entity_get_key(owner, &source_role_id, Tag::Role)?;
+
+            let key = Self::generate_key(owner, &source_role_id, Tag::Permission2Role);
+            let permissions: Vec<T::EntityId> = <Permission2RoleStore<T>>::get(key)
+                .iter()
+                .map(|p2r| p2r.permission)
+                .collect();
+
+            if permissions.len() > T::MaxCloneAssignments::get() as usize {
+                return RbacError::err(AssignmentLimitExceeded, &source_role_id);
             }
-            Ok(())
+
+            // Either the role and all of its permissions are copied or nothing is
+            with_transaction_unchecked(|| {
+                let res = Self::create_role(owner, role_id, name).and_then(|_| {
+                    permissions.iter().try_for_each(|permission_id| {
+                        Self::create_permission_to_role(owner, *permission_id, role_id)
+                    })
+                });
+                match res {
+                    Ok(()) => TransactionOutcome::Commit(Ok(permissions)),
+                    Err(e) => TransactionOutcome::Rollback(Err(e)),
+                }
+            })
         }
     }
 
@@ -1298,38 +3219,56 @@ pub mod pallet {
             Ok(<PermissionStore<T>>::get(owner))
         }
 
+        fn get_permission_by_name(
+            owner: &T::AccountId,
+            name: &[u8],
+        ) -> Result<Entity<T::EntityId>, RbacError> {
+            let permission_id = Self::entity_id_by_name(owner, name, Tag::Permission, |owner| {
+                <PermissionStore<T>>::get(owner)
+            })?;
+            Self::get_permission(owner, permission_id)
+        }
+
         fn create_permission(
             owner: &T::AccountId,
             permission_id: T::EntityId,
             name: &[u8],
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let key = Self::generate_key(owner, &permission_id, Tag::Permission);
+            Self::transactional(|| {
+                Self::check_name(&permission_id, name)?;
 
-            // Check if permission already exists
-            if <KeysLookUpStore<T>>::contains_key(key) {
-                return RbacError::err(EntityAlreadyExist, &permission_id);
-            }
+                // Generate key for integrity check
+                let key = Self::generate_key(owner, &permission_id, Tag::Permission);
 
-            let new_permission = Entity {
-                id: permission_id,
-                name: name.to_vec(),
-                enabled: true,
-            };
+                // Check if permission already exists
+                if <KeysLookUpStore<T>>::contains_key(key) {
+                    return RbacError::err(EntityAlreadyExist, &permission_id);
+                }
+                Self::index_name(owner, &permission_id, name, Tag::Permission)?;
 
-            let mut permissions: Vec<Entity<T::EntityId>> = vec![];
+                let new_permission = Entity {
+                    id: permission_id,
+                    name: name.to_vec(),
+                    enabled: true,
+                };
 
-            // Check if this account already had permissions
-            if <PermissionStore<T>>::contains_key(owner) {
-                let mut val = <PermissionStore<T>>::get(owner);
-                permissions.append(&mut val);
-            }
-            permissions.push(new_permission.clone());
+                let mut permissions: Vec<Entity<T::EntityId>> = vec![];
+
+                // Check if this account already had permissions
+                if <PermissionStore<T>>::contains_key(owner) {
+                    let mut val = <PermissionStore<T>>::get(owner);
+                    permissions.append(&mut val);
+                }
+                permissions.push(new_permission.clone());
 
-            <PermissionStore<T>>::insert(owner, permissions);
-            <KeysLookUpStore<T>>::insert(key, new_permission);
+                <PermissionStore<T>>::insert(owner, permissions);
+                <KeysLookUpStore<T>>::insert(key, new_permission.clone());
 
-            Ok(())
+                Self::update_commitment(owner, None, Some(PolicyLeaf::Permission(new_permission)))?;
+                Self::record_change(owner, PolicyOp::AddPermission(permission_id, name.to_vec()));
+
+                Ok(())
+            })
         }
 
         fn update_existing_permission(
@@ -1337,52 +3276,87 @@ pub mod pallet {
             permission_id: T::EntityId,
             name: &[u8],
         ) -> Result<(), RbacError> {
-            // Check if permission exists and it's enabled and get key for integrity check
-            let key = Self::check_entity_get_key(owner, &permission_id, Tag::Permission)?;
-
-            let mut val = <PermissionStore<T>>::get(owner);
+            Self::transactional(|| {
+                // Check if permission exists and it's enabled and get key for integrity check
+                let key = Self::check_entity_get_key(owner, &permission_id, Tag::Permission)?;
+                // A kept name isn't checked again, it may predate stricter rules
+                if <KeysLookUpStore<T>>::get(key).name != name {
+                    Self::check_name(&permission_id, name)?;
+                }
+                Self::index_name(owner, &permission_id, name, Tag::Permission)?;
 
-            let iterator = val.iter_mut();
+                let mut val = <PermissionStore<T>>::get(owner);
 
-            for entity in iterator {
-                if entity.id == permission_id {
-                    entity.name = name.to_vec();
-                    <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
-                    break;
+                let iterator = val.iter_mut();
+
+                for entity in iterator {
+                    if entity.id == permission_id {
+                        let old = PolicyLeaf::Permission(entity.clone());
+                        if entity.name != name {
+                            Self::unindex_name(
+                                owner,
+                                &permission_id,
+                                &entity.name,
+                                Tag::Permission,
+                            );
+                        }
+                        entity.name = name.to_vec();
+                        <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                        Self::update_commitment(
+                            owner,
+                            Some(old),
+                            Some(PolicyLeaf::Permission(entity.clone())),
+                        )?;
+                        Self::record_change(
+                            owner,
+                            PolicyOp::UpdatePermission(permission_id, name.to_vec()),
+                        );
+                        break;
+                    }
                 }
-            }
 
-            if !val.is_empty() {
-                <PermissionStore<T>>::mutate(owner, |v| *v = val);
-            }
+                if !val.is_empty() {
+                    <PermissionStore<T>>::mutate(owner, |v| *v = val);
+                }
 
-            Ok(())
+                Ok(())
+            })
         }
 
         fn disable_existing_permission(
             owner: &T::AccountId,
             permission_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Check if permission exists and it's enabled and get key for integrity check
-            let key = Self::check_entity_get_key(owner, &permission_id, Tag::Permission)?;
+            Self::transactional(|| {
+                // Check if permission exists and it's enabled and get key for integrity check
+                let key = Self::check_entity_get_key(owner, &permission_id, Tag::Permission)?;
 
-            let mut val = <PermissionStore<T>>::get(owner);
-
-            let iterator = val.iter_mut();
+                let mut val = <PermissionStore<T>>::get(owner);
 
-            for entity in iterator {
-                if entity.id == permission_id {
-                    entity.enabled = false;
-                    <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
-                    break;
+                let iterator = val.iter_mut();
+
+                for entity in iterator {
+                    if entity.id == permission_id {
+                        let old = PolicyLeaf::Permission(entity.clone());
+                        Self::unindex_name(owner, &permission_id, &entity.name, Tag::Permission);
+                        entity.enabled = false;
+                        <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                        Self::update_commitment(
+                            owner,
+                            Some(old),
+                            Some(PolicyLeaf::Permission(entity.clone())),
+                        )?;
+                        Self::record_change(owner, PolicyOp::DisablePermission(permission_id));
+                        break;
+                    }
                 }
-            }
 
-            if !val.is_empty() {
-                <PermissionStore<T>>::mutate(owner, |v| *v = val);
-            }
+                if !val.is_empty() {
+                    <PermissionStore<T>>::mutate(owner, |v| *v = val);
+                }
 
-            Ok(())
+                Ok(())
+            })
         }
     }
 
@@ -1398,38 +3372,56 @@ pub mod pallet {
             Ok(<GroupStore<T>>::get(owner))
         }
 
+        fn get_group_by_name(
+            owner: &T::AccountId,
+            name: &[u8],
+        ) -> Result<Entity<T::EntityId>, RbacError> {
+            let group_id = Self::entity_id_by_name(owner, name, Tag::Group, |owner| {
+                <GroupStore<T>>::get(owner)
+            })?;
+            Self::get_group(owner, group_id)
+        }
+
         fn create_group(
             owner: &T::AccountId,
             group_id: T::EntityId,
             name: &[u8],
         ) -> Result<(), RbacError> {
-            // Generate key for integrity check
-            let key = Self::generate_key(owner, &group_id, Tag::Group);
+            Self::transactional(|| {
+                Self::check_name(&group_id, name)?;
 
-            // Check if group already exists
-            if <KeysLookUpStore<T>>::contains_key(key) {
-                return RbacError::err(EntityAlreadyExist, &group_id);
-            }
+                // Generate key for integrity check
+                let key = Self::generate_key(owner, &group_id, Tag::Group);
 
-            let new_group = Entity {
-                id: group_id,
-                name: name.to_vec(),
-                enabled: true,
-            };
+                // Check if group already exists
+                if <KeysLookUpStore<T>>::contains_key(key) {
+                    return RbacError::err(EntityAlreadyExist, &group_id);
+                }
+                Self::index_name(owner, &group_id, name, Tag::Group)?;
 
-            let mut groups: Vec<Entity<T::EntityId>> = vec![];
+                let new_group = Entity {
+                    id: group_id,
+                    name: name.to_vec(),
+                    enabled: true,
+                };
 
-            // Check if this account already had groups
-            if <GroupStore<T>>::contains_key(owner) {
-                let mut val = <GroupStore<T>>::get(owner);
-                groups.append(&mut val);
-            }
-            groups.push(new_group.clone());
+                let mut groups: Vec<Entity<T::EntityId>> = vec![];
 
-            <GroupStore<T>>::insert(owner, groups);
-            <KeysLookUpStore<T>>::insert(key, new_group);
+                // Check if this account already had groups
+                if <GroupStore<T>>::contains_key(owner) {
+                    let mut val = <GroupStore<T>>::get(owner);
+                    groups.append(&mut val);
+                }
+                groups.push(new_group.clone());
 
-            Ok(())
+                <GroupStore<T>>::insert(owner, groups);
+                <KeysLookUpStore<T>>::insert(key, new_group.clone());
+
+                Self::update_commitment(owner, None, Some(PolicyLeaf::Group(new_group)))?;
+                Self::record_change(owner, PolicyOp::AddGroup(group_id, name.to_vec()));
+
+                Ok(())
+            })
         }
 
         fn update_existing_group(
@@ -1437,45 +3429,105 @@ pub mod pallet {
             group_id: T::EntityId,
             name: &[u8],
         ) -> Result<(), RbacError> {
-            // Check if group exists and it's enabled and get key for integrity check
-            let key = Self::check_entity_get_key(owner, &group_id, Tag::Group)?;
+            Self::transactional(|| {
+                // Check if group exists and it's enabled and get key for integrity check
+                let key = Self::check_entity_get_key(owner, &group_id, Tag::Group)?;
+                // A kept name isn't checked again, it may predate stricter rules
+                if <KeysLookUpStore<T>>::get(key).name != name {
+                    Self::check_name(&group_id, name)?;
+                }
+                Self::index_name(owner, &group_id, name, Tag::Group)?;
 
-            let mut val = <GroupStore<T>>::get(owner);
+                let mut val = <GroupStore<T>>::get(owner);
 
-            for entity in val.iter_mut() {
-                if entity.id == group_id {
-                    entity.name = name.to_vec();
-                    <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
-                    break;
+                for entity in val.iter_mut() {
+                    if entity.id == group_id {
+                        let old = PolicyLeaf::Group(entity.clone());
+                        if entity.name != name {
+                            Self::unindex_name(owner, &group_id, &entity.name, Tag::Group);
+                        }
+                        entity.name = name.to_vec();
+                        <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                        Self::update_commitment(
+                            owner,
+                            Some(old),
+                            Some(PolicyLeaf::Group(entity.clone())),
+                        )?;
+                        Self::record_change(owner, PolicyOp::UpdateGroup(group_id, name.to_vec()));
+                        break;
+                    }
                 }
-            }
 
-            if !val.is_empty() {
-                <GroupStore<T>>::mutate(owner, |v| *v = val);
-            }
-            Ok(())
+                if !val.is_empty() {
+                    <GroupStore<T>>::mutate(owner, |v| *v = val);
+                }
+                Ok(())
+            })
         }
 
         fn disable_existing_group(
             owner: &T::AccountId,
             group_id: T::EntityId,
         ) -> Result<(), RbacError> {
-            // Check if group exists and it's enabled and get key for integrity check
-            let key = Self::check_entity_get_key(owner, &group_id, Tag::Group)?;
-            let mut val = <GroupStore<T>>::get(owner);
+            Self::transactional(|| {
+                // Check if group exists and it's enabled and get key for integrity check
+                let key = Self::check_entity_get_key(owner, &group_id, Tag::Group)?;
+                let mut val = <GroupStore<T>>::get(owner);
 
-            for entity in val.iter_mut() {
-                if entity.id == group_id {
-                    entity.enabled = false;
-                    <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
-                    break;
+                for entity in val.iter_mut() {
+                    if entity.id == group_id {
+                        let old = PolicyLeaf::Group(entity.clone());
+                        Self::unindex_name(owner, &group_id, &entity.name, Tag::Group);
+                        entity.enabled = false;
+                        <KeysLookUpStore<T>>::mutate(key, |e| *e = entity.clone());
+                        Self::update_commitment(
+                            owner,
+                            Some(old),
+                            Some(PolicyLeaf::Group(entity.clone())),
+                        )?;
+                        Self::record_change(owner, PolicyOp::DisableGroup(group_id));
+                        break;
+                    }
                 }
-            }
 
-            if !val.is_empty() {
-                <GroupStore<T>>::mutate(owner, |v| *v = val);
+                if !val.is_empty() {
+                    <GroupStore<T>>::mutate(owner, |v| *v = val);
+                }
+                Ok(())
+            })
+        }
+
+        fn clone_existing_group(
+            owner: &T::AccountId,
+            source_group_id: T::EntityId,
+            group_id: T::EntityId,
+            name: &[u8],
+        ) -> Result<Vec<T::EntityId>, RbacError> {
+            // Check if source group exists and it's enabled
+            Self::check_entity_get_key(owner, &source_group_id, Tag::Group)?;
+
+            let key = Self::generate_key(owner, &source_group_id, Tag::Role2Group);
+            let roles: Vec<T::EntityId> = <Role2GroupStore<T>>::get(key)
+                .iter()
+                .map(|r2g| r2g.role)
+                .collect();
+
+            if roles.len() > T::MaxCloneAssignments::get() as usize {
+                return RbacError::err(AssignmentLimitExceeded, &source_group_id);
             }
-            Ok(())
+
+            // Either the group and all of its roles are copied or nothing is
+            with_transaction_unchecked(|| {
+                let res = Self::create_group(owner, group_id, name).and_then(|_| {
+                    roles.iter().try_for_each(|role_id| {
+                        Self::create_role_to_group(owner, *role_id, group_id)
+                    })
+                });
+                match res {
+                    Ok(()) => TransactionOutcome::Commit(Ok(roles)),
+                    Err(e) => TransactionOutcome::Rollback(Err(e)),
+                }
+            })
         }
     }
 }
@@ -0,0 +1,244 @@
+//! Storage migrations, to be listed in the runtime's `Executive` migrations in order.
+
+use frame_support::{
+    storage::migration::clear_storage_prefix,
+    traits::{Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+    weights::Weight,
+};
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    marker::PhantomData,
+    vec::Vec,
+};
+
+use crate::{
+    commitment::{self, NodeId, TreeNode},
+    rbac::{self, RbacKeyType, Tag},
+    structs::Entity,
+    Config, GroupStore, NameIndexStore, Pallet, PermissionStore, PolicyCommitmentStore,
+    PolicyTreeRootStore, PolicyTreeStore, PolicyUsersStore, Role2UserStore, RoleStore,
+    User2GroupStore,
+};
+
+pub mod v1 {
+    use super::*;
+
+    /// Builds `PolicyUsersStore` from the user assignments made before its introduction.
+    /// Their storage keys are hashes, so each one is matched against the owners of the
+    /// role or group it references.
+    pub struct BackfillPolicyUsers<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for BackfillPolicyUsers<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() >= 1 {
+                return T::DbWeight::get().reads(1);
+            }
+            let mut reads: u64 = 1;
+            let mut writes: u64 = 1;
+
+            // Owners of each role and group id, ids aren't unique across owners
+            let mut role_owners: BTreeMap<T::EntityId, Vec<T::AccountId>> = BTreeMap::new();
+            for (owner, roles) in <RoleStore<T>>::iter() {
+                reads += 1;
+                for role in roles {
+                    role_owners.entry(role.id).or_default().push(owner.clone());
+                }
+            }
+            let mut group_owners: BTreeMap<T::EntityId, Vec<T::AccountId>> = BTreeMap::new();
+            for (owner, groups) in <GroupStore<T>>::iter() {
+                reads += 1;
+                for group in groups {
+                    group_owners
+                        .entry(group.id)
+                        .or_default()
+                        .push(owner.clone());
+                }
+            }
+
+            let mut users: BTreeMap<T::AccountId, Vec<T::EntityId>> = BTreeMap::new();
+            let mut add = |candidates: Option<&Vec<T::AccountId>>,
+                           user: T::EntityId,
+                           key: RbacKeyType,
+                           tag: Tag| {
+                let owner = candidates
+                    .into_iter()
+                    .flatten()
+                    .find(|owner| rbac::generate_key(*owner, &user, tag) == key);
+                if let Some(owner) = owner {
+                    users.entry(owner.clone()).or_default().push(user);
+                }
+            };
+            for (key, r2us) in <Role2UserStore<T>>::iter() {
+                reads += 1;
+                if let Some(r2u) = r2us.first() {
+                    add(role_owners.get(&r2u.role), r2u.user, key, Tag::Role2User);
+                }
+            }
+            for (key, u2gs) in <User2GroupStore<T>>::iter() {
+                reads += 1;
+                if let Some(u2g) = u2gs.first() {
+                    add(group_owners.get(&u2g.group), u2g.user, key, Tag::User2Group);
+                }
+            }
+
+            for (owner, user_ids) in users {
+                reads += 1;
+                writes += 1;
+                <PolicyUsersStore<T>>::mutate(owner, |stored| {
+                    stored.extend(user_ids);
+                    stored.sort();
+                    stored.dedup();
+                });
+            }
+
+            StorageVersion::new(1).put::<Pallet<T>>();
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+    }
+}
+
+pub mod v2 {
+    use super::*;
+
+    /// Builds each owner's policy tree from the live policy, replacing the sorted leaf
+    /// hashes of `PolicyLeavesStore`, which only covered changes made after its
+    /// introduction. `MaxPolicyTreeDepth` is not applied. Requires
+    /// `v1::BackfillPolicyUsers` to have run before.
+    pub struct BuildPolicyTrees<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for BuildPolicyTrees<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() >= 2 {
+                return T::DbWeight::get().reads(1);
+            }
+            let mut reads: u64 = 1;
+            let mut writes: u64 = 1;
+
+            // Every owner holding assignments holds the roles or groups they reference
+            let mut owners: BTreeSet<T::AccountId> = <RoleStore<T>>::iter_keys().collect();
+            owners.extend(<PermissionStore<T>>::iter_keys());
+            owners.extend(<GroupStore<T>>::iter_keys());
+            reads += owners.len() as u64;
+
+            for owner in owners {
+                let policy = Pallet::<T>::export_policy(&owner);
+                reads += 4
+                    + 2 * Pallet::<T>::users_of(&owner).len() as u64
+                    + (policy.roles.len() + policy.groups.len()) as u64;
+
+                let mut nodes: BTreeMap<NodeId, TreeNode> = BTreeMap::new();
+                let root = commitment::policy_leaves(&policy)
+                    .into_iter()
+                    .fold(None, |root, leaf| {
+                        commitment::insert_leaf(&mut nodes, root, leaf)
+                    });
+                if let Some(root) = root {
+                    writes += 2 + nodes.len() as u64;
+                    for (id, node) in nodes {
+                        <PolicyTreeStore<T>>::insert(&owner, id, node);
+                    }
+                    <PolicyCommitmentStore<T>>::insert(&owner, root.hash());
+                    <PolicyTreeRootStore<T>>::insert(&owner, root);
+                }
+            }
+
+            let removed = clear_storage_prefix(
+                <Pallet<T> as PalletInfoAccess>::name().as_bytes(),
+                b"PolicyLeavesStore",
+                b"",
+                None,
+                None,
+            );
+            writes += removed.backend as u64;
+
+            StorageVersion::new(2).put::<Pallet<T>>();
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+    }
+}
+
+pub mod v3 {
+    use super::*;
+
+    /// Builds `NameIndexStore` from the enabled entities, list it in the runtime upgrade
+    /// enabling `UniqueNames`. While it is disabled nothing is done and the storage version
+    /// is kept, so that the upgrade enabling it later still builds the index. Where enabled
+    /// entities of an owner and kind already share a name, only the first one is indexed
+    /// for it, renaming or disabling the others keeps the index consistent.
+    pub struct BuildNameIndex<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for BuildNameIndex<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() >= 3 || !T::UniqueNames::get() {
+                return T::DbWeight::get().reads(1);
+            }
+            let removed = clear_storage_prefix(
+                <Pallet<T> as PalletInfoAccess>::name().as_bytes(),
+                b"NameIndexStore",
+                b"",
+                None,
+                None,
+            );
+            let mut reads: u64 = 1;
+            let mut writes: u64 = 1 + removed.backend as u64;
+
+            let mut index = |owner: T::AccountId, entities: Vec<Entity<T::EntityId>>, tag: Tag| {
+                reads += 1;
+                for entity in entities.into_iter().filter(|entity| entity.enabled) {
+                    let key = rbac::generate_key(&owner, &entity.name, tag);
+                    reads += 1;
+                    if !<NameIndexStore<T>>::contains_key(key) {
+                        writes += 1;
+                        <NameIndexStore<T>>::insert(key, entity.id);
+                    }
+                }
+            };
+            for (owner, roles) in <RoleStore<T>>::iter() {
+                index(owner, roles, Tag::Role);
+            }
+            for (owner, permissions) in <PermissionStore<T>>::iter() {
+                index(owner, permissions, Tag::Permission);
+            }
+            for (owner, groups) in <GroupStore<T>>::iter() {
+                index(owner, groups, Tag::Group);
+            }
+
+            StorageVersion::new(3).put::<Pallet<T>>();
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+
+        /// Every enabled entity's name is indexed for an enabled entity holding it
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            if !T::UniqueNames::get() {
+                return Ok(());
+            }
+            frame_support::ensure!(
+                Pallet::<T>::on_chain_storage_version() >= 3,
+                "NameIndexStore was not built"
+            );
+
+            let check = |owner: &T::AccountId, entities: &[Entity<T::EntityId>], tag: Tag| {
+                entities
+                    .iter()
+                    .filter(|entity| entity.enabled)
+                    .all(|entity| {
+                        let key = rbac::generate_key(owner, &entity.name, tag);
+                        <NameIndexStore<T>>::get(key).map_or(false, |id| {
+                            entities
+                                .iter()
+                                .any(|e| e.id == id && e.enabled && e.name == entity.name)
+                        })
+                    })
+            };
+            let indexed = <RoleStore<T>>::iter()
+                .all(|(owner, roles)| check(&owner, &roles, Tag::Role))
+                && <PermissionStore<T>>::iter()
+                    .all(|(owner, permissions)| check(&owner, &permissions, Tag::Permission))
+                && <GroupStore<T>>::iter()
+                    .all(|(owner, groups)| check(&owner, &groups, Tag::Group));
+            frame_support::ensure!(indexed, "enabled entity missing from NameIndexStore");
+            Ok(())
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use crate as peaq_rbac;
-use frame_support::parameter_types;
+use frame_support::{
+    parameter_types,
+    traits::{Contains, GenesisBuild},
+    BoundedVec,
+};
 use frame_system as system;
 use pallet_timestamp;
 use sp_core::{sr25519, Pair, H256};
 use sp_runtime::{
     testing::Header,
-    traits::{BlakeTwo256, IdentityLookup},
+    traits::{BlakeTwo256, Convert, IdentityLookup},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -20,7 +24,9 @@ frame_support::construct_runtime!(
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
         Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
-        PeaqRBAC: peaq_rbac::{Pallet, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        PeaqRBAC: peaq_rbac::{Pallet, Call, Storage, Event<T>, Config<T>},
+        Dummy: pallet_dummy::{Pallet, Call},
     }
 );
 
@@ -47,7 +53,7 @@ impl system::Config for Test {
     type BlockHashCount = BlockHashCount;
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<u64>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
@@ -67,10 +73,128 @@ impl pallet_timestamp::Config for Test {
     type WeightInfo = ();
 }
 
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type HoldIdentifier = ();
+    type FreezeIdentifier = ();
+    type MaxHolds = ();
+    type MaxFreezes = ();
+}
+
 impl peaq_rbac::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type EntityId = [u8; 32];
     type WeightInfo = peaq_rbac::weights::WeightInfo<Test>;
+    type MaxCloneAssignments = frame_support::traits::ConstU32<4>;
+    type MaxPolicyOps = frame_support::traits::ConstU32<16>;
+    type DispatchUserMapper = AccountToUser;
+    type RuntimeCall = RuntimeCall;
+    type MaxAllowedCalls = frame_support::traits::ConstU32<8>;
+    type DispatchFilter = DispatchableCalls;
+    type MaxDispatchLookups = frame_support::traits::ConstU32<4>;
+    type MaxAuditLogEntries = frame_support::traits::ConstU32<4>;
+    type MaxPolicyTreeDepth = frame_support::traits::ConstU32<32>;
+    type Currency = Balances;
+    type SnapshotDepositBase = frame_support::traits::ConstU64<10>;
+    type SnapshotDepositPerItem = frame_support::traits::ConstU64<1>;
+    type MaxSnapshots = frame_support::traits::ConstU32<2>;
+    type MaxSnapshotItems = frame_support::traits::ConstU32<16>;
+    type RollbackChunkSize = frame_support::traits::ConstU32<2>;
+    type MaxDraftOps = frame_support::traits::ConstU32<8>;
+    type MaxNameLength = MaxNameLength;
+    type MaxSnapshotLabelLength = MaxSnapshotLabelLength;
+    type NameRules = ();
+    type UniqueNames = UniqueNames;
+}
+
+/// Keeps `dispatch_with_permission` from moving the owner's funds.
+pub struct DispatchableCalls;
+impl Contains<RuntimeCall> for DispatchableCalls {
+    fn contains(call: &RuntimeCall) -> bool {
+        !matches!(call, RuntimeCall::Balances(_))
+    }
+}
+
+pub type MaxNameLength = frame_support::traits::ConstU32<64>;
+pub type MaxSnapshotLabelLength = frame_support::traits::ConstU32<64>;
+
+parameter_types! {
+    /// Off by default, as most tests reuse names, and enabled by the tests of the index
+    pub static UniqueNames: bool = false;
+}
+
+/// A pallet whose call is guarded by an RBAC permission.
+#[frame_support::pallet]
+pub mod pallet_dummy {
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type GuardOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(Weight::zero())]
+        pub fn guarded(origin: OriginFor<T>) -> DispatchResult {
+            T::GuardOrigin::ensure_origin(origin)?;
+            Ok(())
+        }
+    }
+}
+
+parameter_types! {
+    pub DeviceOwner: sr25519::Public = account_key("Iredia");
+    pub const DevicePermission: [u8; 32] = *b"52464667364637663721676474666576";
+}
+
+/// Uses the raw public key of an account as its user id.
+pub struct AccountToUser;
+impl Convert<sr25519::Public, [u8; 32]> for AccountToUser {
+    fn convert(account: sr25519::Public) -> [u8; 32] {
+        account.0
+    }
+}
+
+impl pallet_dummy::Config for Test {
+    type GuardOrigin =
+        peaq_rbac::origin::EnsureRbacPermission<Test, DeviceOwner, DevicePermission, AccountToUser>;
+}
+
+/// Gates every call of the dummy pallet behind the device permission.
+pub struct DummyCallPermissions;
+impl peaq_rbac::extension::CallPermissions<sr25519::Public, [u8; 32], RuntimeCall>
+    for DummyCallPermissions
+{
+    fn required_permission(call: &RuntimeCall) -> Option<(sr25519::Public, [u8; 32])> {
+        match call {
+            RuntimeCall::Dummy(_) => Some((DeviceOwner::get(), DevicePermission::get())),
+            _ => None,
+        }
+    }
+}
+
+impl peaq_rbac::extension::Config for Test {
+    type UserMapper = AccountToUser;
+    type CallPermissions = DummyCallPermissions;
+    type MaxPermissionLookups = frame_support::traits::ConstU32<4>;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -81,8 +205,29 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
         .into()
 }
 
+// Build genesis storage seeded with the given RBAC policies.
+pub fn new_test_ext_with_genesis(
+    config: peaq_rbac::GenesisConfig<Test>,
+) -> sp_io::TestExternalities {
+    let mut t = system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    config.assimilate_storage(&mut t).unwrap();
+    t.into()
+}
+
 pub fn account_key(s: &str) -> sr25519::Public {
     sr25519::Pair::from_string(&format!("//{}", s), None)
         .expect("static values are valid; qed")
         .public()
 }
+
+/// Name as taken by the dispatchables
+pub fn bounded_name(name: &[u8]) -> BoundedVec<u8, MaxNameLength> {
+    name.to_vec().try_into().expect("test names are short")
+}
+
+/// Snapshot label as taken by the dispatchables
+pub fn bounded_label(label: &[u8]) -> BoundedVec<u8, MaxSnapshotLabelLength> {
+    label.to_vec().try_into().expect("test labels are short")
+}
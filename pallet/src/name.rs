@@ -0,0 +1,46 @@
+//! Rules entity names must follow. Every name must be non-empty and at most
+//! `Config::MaxNameLength` bytes long, `Config::NameRules` may restrict them further.
+
+use crate::error::RbacErrorType;
+
+/// Checks of entity names beyond their length.
+pub trait NameRules {
+    /// Returns the reason why the name is rejected, if it is.
+    fn check(name: &[u8]) -> Result<(), RbacErrorType>;
+}
+
+/// Accepts any bytes as name.
+impl NameRules for () {
+    fn check(_name: &[u8]) -> Result<(), RbacErrorType> {
+        Ok(())
+    }
+}
+
+/// Requires names to be valid UTF-8 without control characters.
+pub struct Utf8Names;
+
+impl NameRules for Utf8Names {
+    fn check(name: &[u8]) -> Result<(), RbacErrorType> {
+        let name = core::str::from_utf8(name).map_err(|_| RbacErrorType::NameInvalidUtf8)?;
+        if name.chars().any(char::is_control) {
+            return Err(RbacErrorType::NameInvalidChar);
+        }
+        Ok(())
+    }
+}
+
+/// Restricts names to ASCII letters, digits, `_`, `-` and `.`, e.g. `FLEET_ADMIN`.
+pub struct IdentifierNames;
+
+impl NameRules for IdentifierNames {
+    fn check(name: &[u8]) -> Result<(), RbacErrorType> {
+        if name
+            .iter()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b'.'))
+        {
+            Ok(())
+        } else {
+            Err(RbacErrorType::NameInvalidChar)
+        }
+    }
+}
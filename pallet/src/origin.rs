@@ -0,0 +1,70 @@
+//! Origin checks backed by RBAC permissions, so other pallets can restrict their calls to
+//! users holding a given permission.
+
+use crate::{rbac::Rbac, Config, Pallet};
+use frame_support::traits::{EnsureOrigin, Get};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Convert;
+use sp_std::marker::PhantomData;
+
+/// Ensures that the origin is signed by an account whose user entity holds the permission
+/// given by `PermissionGetter` within the policy of `OwnerGetter`. The signing account is
+/// resolved to its user entity by `UserMapper`. Succeeds with the signing account.
+pub struct EnsureRbacPermission<T, OwnerGetter, PermissionGetter, UserMapper>(
+    PhantomData<(T, OwnerGetter, PermissionGetter, UserMapper)>,
+);
+
+impl<T, O, OwnerGetter, PermissionGetter, UserMapper> EnsureOrigin<O>
+    for EnsureRbacPermission<T, OwnerGetter, PermissionGetter, UserMapper>
+where
+    T: Config,
+    O: Into<Result<RawOrigin<T::AccountId>, O>> + From<RawOrigin<T::AccountId>>,
+    OwnerGetter: Get<T::AccountId>,
+    PermissionGetter: Get<T::EntityId>,
+    UserMapper: Convert<T::AccountId, T::EntityId>,
+{
+    type Success = T::AccountId;
+
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        o.into().and_then(|o| match o {
+            RawOrigin::Signed(who) => {
+                let user_id = UserMapper::convert(who.clone());
+                let owner = OwnerGetter::get();
+
+                if Pallet::<T>::has_permission(&owner, user_id, PermissionGetter::get()) {
+                    Ok(who)
+                } else {
+                    Err(O::from(RawOrigin::Signed(who)))
+                }
+            }
+            r => Err(O::from(r)),
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn try_successful_origin() -> Result<O, ()> {
+        use crate::rbac::{Permission, Role};
+
+        let who: T::AccountId = frame_benchmarking::account("rbac_origin", 0, 0);
+        let owner = OwnerGetter::get();
+        let permission_id = PermissionGetter::get();
+        let user_id = UserMapper::convert(who.clone());
+        // The permission is granted through a role sharing its id
+        let role_id = permission_id;
+
+        if Pallet::<T>::get_permission(&owner, permission_id).is_err() {
+            Pallet::<T>::create_permission(&owner, permission_id, b"BENCHMARK").map_err(|_| ())?;
+        }
+        if Pallet::<T>::get_role(&owner, role_id).is_err() {
+            Pallet::<T>::create_role(&owner, role_id, b"BENCHMARK").map_err(|_| ())?;
+        }
+        // Either freshly assigned or already in place
+        let _ = Pallet::<T>::create_permission_to_role(&owner, permission_id, role_id);
+        let _ = Pallet::<T>::create_role_to_user(&owner, role_id, user_id);
+
+        if !Pallet::<T>::has_permission(&owner, user_id, permission_id) {
+            return Err(());
+        }
+        Ok(O::from(RawOrigin::Signed(who)))
+    }
+}
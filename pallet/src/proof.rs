@@ -0,0 +1,158 @@
+//! Verification of storage read proofs over the RBAC stores.
+//!
+//! Off-chain services and light clients, which only trust a block's state root, can use
+//! [`RbacProofVerifier`] to recompute the answer of an RBAC query from a read proof served
+//! by an untrusted RPC node (see `peaqrbac_proveUserRoles` and friends).
+
+use codec::{Decode, Encode};
+use frame_support::pallet_prelude::Parameter;
+use sp_core::H256;
+use sp_io::hashing::{blake2_128, twox_128};
+use sp_runtime::traits::BlakeTwo256;
+use sp_std::vec::Vec;
+use sp_trie::{read_trie_value, LayoutV1, MemoryDB, StorageProof};
+
+use crate::{
+    error::{RbacError, RbacErrorType::AssignmentDoesNotExist},
+    rbac::{generate_key, RbacKeyType, Result, Tag},
+    structs::{Entity, Permission2Role, Role2User},
+};
+
+/// Storage name of `KeysLookUpStore`
+pub const KEYS_LOOKUP_STORE: &[u8] = b"KeysLookUpStore";
+/// Storage name of `Role2UserStore`
+pub const ROLE_2_USER_STORE: &[u8] = b"Role2UserStore";
+/// Storage name of `Permission2RoleStore`
+pub const PERMISSION_2_ROLE_STORE: &[u8] = b"Permission2RoleStore";
+
+/// Errors which can occur while verifying a read proof
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProofError {
+    /// The proof does not contain the requested key or does not match the state root
+    InvalidProof,
+    /// The proven value could not be decoded
+    DecodeFailed,
+}
+
+/// Full storage key of an RBAC store entry. All RBAC stores are `Blake2_128Concat`
+/// maps, so the key is `twox128(pallet) ++ twox128(storage) ++ blake2_128(key) ++ key`.
+pub fn storage_key(pallet_prefix: &[u8], storage_name: &[u8], key: &RbacKeyType) -> Vec<u8> {
+    let mut final_key = Vec::with_capacity(32 + 16 + key.len());
+    final_key.extend_from_slice(&twox_128(pallet_prefix));
+    final_key.extend_from_slice(&twox_128(storage_name));
+    final_key.extend_from_slice(&blake2_128(key));
+    final_key.extend_from_slice(key);
+    final_key
+}
+
+/// Storage key of an entity in `KeysLookUpStore`
+pub fn entity_storage_key<AccountId: Encode, EntityId: Encode>(
+    pallet_prefix: &[u8],
+    owner: &AccountId,
+    entity_id: &EntityId,
+    tag: Tag,
+) -> Vec<u8> {
+    let key = generate_key(owner, entity_id, tag);
+    storage_key(pallet_prefix, KEYS_LOOKUP_STORE, &key)
+}
+
+/// Storage key of a user's role assignments in `Role2UserStore`
+pub fn user_roles_storage_key<AccountId: Encode, EntityId: Encode>(
+    pallet_prefix: &[u8],
+    owner: &AccountId,
+    user_id: &EntityId,
+) -> Vec<u8> {
+    let key = generate_key(owner, user_id, Tag::Role2User);
+    storage_key(pallet_prefix, ROLE_2_USER_STORE, &key)
+}
+
+/// Storage key of a role's permission assignments in `Permission2RoleStore`
+pub fn role_permissions_storage_key<AccountId: Encode, EntityId: Encode>(
+    pallet_prefix: &[u8],
+    owner: &AccountId,
+    role_id: &EntityId,
+) -> Vec<u8> {
+    let key = generate_key(owner, role_id, Tag::Permission2Role);
+    storage_key(pallet_prefix, PERMISSION_2_ROLE_STORE, &key)
+}
+
+/// Recomputes answers of RBAC queries from a read proof against a trusted state root
+pub struct RbacProofVerifier<'a> {
+    pallet_prefix: &'a [u8],
+    state_root: H256,
+    db: MemoryDB<BlakeTwo256>,
+}
+
+impl<'a> RbacProofVerifier<'a> {
+    /// Creates a verifier for the pallet instance named `pallet_prefix` in
+    /// `construct_runtime!`, from the trie nodes of a read proof
+    pub fn new(pallet_prefix: &'a [u8], state_root: H256, proof: Vec<Vec<u8>>) -> Self {
+        let db = StorageProof::new(proof).into_memory_db::<BlakeTwo256>();
+        RbacProofVerifier {
+            pallet_prefix,
+            state_root,
+            db,
+        }
+    }
+
+    /// Reads and decodes the value at the given storage key, `None` if the proof
+    /// shows the key is absent
+    pub fn read_value<V: Decode>(&self, key: &[u8]) -> Result<Option<V>, ProofError> {
+        let value = read_trie_value::<LayoutV1<BlakeTwo256>, _>(
+            &self.db,
+            &self.state_root,
+            key,
+            None,
+            None,
+        )
+        .map_err(|_| ProofError::InvalidProof)?;
+
+        match value {
+            Some(bytes) => V::decode(&mut &bytes[..])
+                .map(Some)
+                .map_err(|_| ProofError::DecodeFailed),
+            None => Ok(None),
+        }
+    }
+
+    /// Proven entity including its enabled flag, `None` if it does not exist
+    pub fn entity<AccountId: Encode, EntityId: Parameter>(
+        &self,
+        owner: &AccountId,
+        entity_id: &EntityId,
+        tag: Tag,
+    ) -> Result<Option<Entity<EntityId>>, ProofError> {
+        self.read_value(&entity_storage_key(
+            self.pallet_prefix,
+            owner,
+            entity_id,
+            tag,
+        ))
+    }
+
+    /// Recomputes the answer of `fetch_user_roles`
+    pub fn user_roles<AccountId: Encode, EntityId: Parameter>(
+        &self,
+        owner: &AccountId,
+        user_id: &EntityId,
+    ) -> Result<Result<Vec<Role2User<EntityId>>, RbacError>, ProofError> {
+        let key = user_roles_storage_key(self.pallet_prefix, owner, user_id);
+        Ok(match self.read_value(&key)? {
+            Some(roles) => Ok(roles),
+            None => RbacError::err(AssignmentDoesNotExist, user_id),
+        })
+    }
+
+    /// Recomputes the answer of `fetch_role_permissions`
+    pub fn role_permissions<AccountId: Encode, EntityId: Parameter>(
+        &self,
+        owner: &AccountId,
+        role_id: &EntityId,
+    ) -> Result<Result<Vec<Permission2Role<EntityId>>, RbacError>, ProofError> {
+        let key = role_permissions_storage_key(self.pallet_prefix, owner, role_id);
+        Ok(match self.read_value(&key)? {
+            Some(permissions) => Ok(permissions),
+            None => RbacError::err(AssignmentDoesNotExist, role_id),
+        })
+    }
+}
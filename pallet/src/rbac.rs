@@ -1,4 +1,6 @@
 use crate::structs::*;
+use codec::Encode;
+use sp_io::hashing::blake2_256;
 use sp_std::vec::Vec;
 
 use crate::error::RbacError;
@@ -6,6 +8,21 @@ pub use crate::error::Result;
 
 pub type RbacKeyType = [u8; 32];
 
+/// Derives the storage key of an entity or assignment, as used by all RBAC stores.
+/// Also usable outside of the runtime, e.g. to request storage proofs.
+pub fn generate_key<AccountId: Encode, EntityId: Encode>(
+    owner: &AccountId,
+    entity: &EntityId,
+    tag: Tag,
+) -> RbacKeyType {
+    let mut bytes_in_tag: Vec<u8> = tag.to_string().as_bytes().to_vec();
+    let mut entity_bytes_to_hash: Vec<u8> = entity.encode().as_slice().to_vec();
+    let mut owner_bytes_to_hash: Vec<u8> = owner.encode().as_slice().to_vec();
+    owner_bytes_to_hash.append(&mut entity_bytes_to_hash);
+    owner_bytes_to_hash.append(&mut bytes_in_tag);
+    blake2_256(&owner_bytes_to_hash[..])
+}
+
 pub trait Rbac<AccountId, EntityId> {
     fn generate_key(owner: &AccountId, entity: &EntityId, tag: Tag) -> RbacKeyType;
 
@@ -41,16 +58,28 @@ pub trait Rbac<AccountId, EntityId> {
         role_id: EntityId,
     ) -> Result<Vec<Permission2Role<EntityId>>, RbacError>;
 
+    /// Permissions the user holds, through its roles and the roles of its groups. Like
+    /// for `has_permission`, disabled entities are skipped and never grant anything.
     fn get_user_permissions(
         owner: &AccountId,
         user_id: EntityId,
     ) -> Result<Vec<Entity<EntityId>>, RbacError>;
 
+    /// Permissions granted through the roles of the group, none if the group is
+    /// disabled. Disabled roles and permissions are skipped.
     fn get_group_permissions(
         owner: &AccountId,
         group_id: EntityId,
     ) -> Result<Vec<Entity<EntityId>>, RbacError>;
 
+    /// Checks whether the user holds the permission, either directly through one of its
+    /// roles or through one of its groups. Disabled entities never grant anything.
+    fn has_permission(owner: &AccountId, user_id: EntityId, permission_id: EntityId) -> bool;
+
+    /// Checks whether the user holds the role, either directly or through one of its
+    /// groups. Disabled entities never grant anything.
+    fn has_role(owner: &AccountId, user_id: EntityId, role_id: EntityId) -> bool;
+
     fn create_role_to_user(
         owner: &AccountId,
         role_id: EntityId,
@@ -105,6 +134,9 @@ pub trait Role<AccountId, EntityId> {
 
     fn get_roles(owner: &AccountId) -> Result<Vec<Entity<EntityId>>, RbacError>;
 
+    /// Looks an entity up by its name, the first one holding it unless names are unique
+    fn get_role_by_name(owner: &AccountId, name: &[u8]) -> Result<Entity<EntityId>, RbacError>;
+
     fn create_role(owner: &AccountId, role_id: EntityId, name: &[u8]) -> Result<(), RbacError>;
 
     fn update_existing_role(
@@ -114,6 +146,13 @@ pub trait Role<AccountId, EntityId> {
     ) -> Result<(), RbacError>;
 
     fn disable_existing_role(owner: &AccountId, role_id: EntityId) -> Result<(), RbacError>;
+
+    fn clone_existing_role(
+        owner: &AccountId,
+        source_role_id: EntityId,
+        role_id: EntityId,
+        name: &[u8],
+    ) -> Result<Vec<EntityId>, RbacError>;
 }
 
 pub trait Permission<AccountId, EntityId> {
@@ -124,6 +163,12 @@ pub trait Permission<AccountId, EntityId> {
 
     fn get_permissions(owner: &AccountId) -> Result<Vec<Entity<EntityId>>, RbacError>;
 
+    /// Looks an entity up by its name, the first one holding it unless names are unique
+    fn get_permission_by_name(
+        owner: &AccountId,
+        name: &[u8],
+    ) -> Result<Entity<EntityId>, RbacError>;
+
     fn create_permission(
         owner: &AccountId,
         permission_id: EntityId,
@@ -147,6 +192,9 @@ pub trait Group<AccountId, EntityId> {
 
     fn get_groups(owner: &AccountId) -> Result<Vec<Entity<EntityId>>, RbacError>;
 
+    /// Looks an entity up by its name, the first one holding it unless names are unique
+    fn get_group_by_name(owner: &AccountId, name: &[u8]) -> Result<Entity<EntityId>, RbacError>;
+
     fn create_group(owner: &AccountId, group_id: EntityId, name: &[u8]) -> Result<(), RbacError>;
 
     fn update_existing_group(
@@ -156,8 +204,16 @@ pub trait Group<AccountId, EntityId> {
     ) -> Result<(), RbacError>;
 
     fn disable_existing_group(owner: &AccountId, group_id: EntityId) -> Result<(), RbacError>;
+
+    fn clone_existing_group(
+        owner: &AccountId,
+        source_group_id: EntityId,
+        group_id: EntityId,
+        name: &[u8],
+    ) -> Result<Vec<EntityId>, RbacError>;
 }
 
+#[derive(Clone, Copy)]
 pub enum Tag {
     Role,
     Group,
@@ -166,6 +222,7 @@ pub enum Tag {
     User2Group,
     Permission,
     Permission2Role,
+    CallFilter,
 }
 
 impl Tag {
@@ -178,6 +235,7 @@ impl Tag {
             Self::User2Group => "U2G",
             Self::Permission => "Permission",
             Self::Permission2Role => "P2R",
+            Self::CallFilter => "CF",
         }
     }
 }
@@ -1,9 +1,13 @@
+use crate::rbac::RbacKeyType;
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sp_core::RuntimeDebug;
-use sp_std::vec::Vec;
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    vec::Vec,
+};
 
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(
@@ -50,3 +54,381 @@ pub struct Permission2Role<EntityId> {
     pub permission: EntityId,
     pub role: EntityId,
 }
+
+/// A call which holders of a permission may dispatch on behalf of its owner, identified by
+/// the names of its pallet and function. An empty function name allows every call of the
+/// pallet.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(
+    Clone, PartialEq, Eq, PartialOrd, Ord, Default, TypeInfo, Decode, Encode, RuntimeDebug,
+)]
+pub struct AllowedCall {
+    pub pallet_name: Vec<u8>,
+    pub function_name: Vec<u8>,
+}
+
+impl AllowedCall {
+    /// Checks whether a call with the given names is covered by this entry
+    pub fn matches(&self, pallet_name: &str, function_name: &str) -> bool {
+        self.pallet_name == pallet_name.as_bytes()
+            && (self.function_name.is_empty() || self.function_name == function_name.as_bytes())
+    }
+}
+
+/// Policy of a single owner, used to seed the pallet at genesis. Users have no entity of
+/// their own and are only referenced by the assignments.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Default, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct GenesisPolicy<EntityId> {
+    pub roles: Vec<Entity<EntityId>>,
+    pub permissions: Vec<Entity<EntityId>>,
+    pub groups: Vec<Entity<EntityId>>,
+    pub role2users: Vec<Role2User<EntityId>>,
+    pub role2groups: Vec<Role2Group<EntityId>>,
+    pub user2groups: Vec<User2Group<EntityId>>,
+    pub permission2roles: Vec<Permission2Role<EntityId>>,
+}
+
+impl<EntityId: Ord + Clone> GenesisPolicy<EntityId> {
+    /// Number of entities and assignments of the policy
+    pub fn item_count(&self) -> usize {
+        self.roles.len()
+            + self.permissions.len()
+            + self.groups.len()
+            + self.role2users.len()
+            + self.role2groups.len()
+            + self.user2groups.len()
+            + self.permission2roles.len()
+    }
+
+    /// Operations turning this policy into `target`. Entities can't be removed, so the ones
+    /// missing in `target` get disabled. Entities are enabled before and disabled after the
+    /// assignments change, so the operations apply in the returned order.
+    pub fn ops_to(&self, target: &Self) -> Vec<PolicyOp<EntityId>> {
+        let mut ops = Vec::new();
+        let mut unassigns = Vec::new();
+        let mut assigns = Vec::new();
+        let mut disables = Vec::new();
+
+        let roles = EntityOps {
+            add: PolicyOp::AddRole,
+            update: PolicyOp::UpdateRole,
+            enable: PolicyOp::EnableRole,
+            disable: PolicyOp::DisableRole,
+        };
+        let permissions = EntityOps {
+            add: PolicyOp::AddPermission,
+            update: PolicyOp::UpdatePermission,
+            enable: PolicyOp::EnablePermission,
+            disable: PolicyOp::DisablePermission,
+        };
+        let groups = EntityOps {
+            add: PolicyOp::AddGroup,
+            update: PolicyOp::UpdateGroup,
+            enable: PolicyOp::EnableGroup,
+            disable: PolicyOp::DisableGroup,
+        };
+        roles.diff(&self.roles, &target.roles, &mut ops, &mut disables);
+        permissions.diff(
+            &self.permissions,
+            &target.permissions,
+            &mut ops,
+            &mut disables,
+        );
+        groups.diff(&self.groups, &target.groups, &mut ops, &mut disables);
+
+        assignment_ops(
+            &self.role2users,
+            &target.role2users,
+            |a| PolicyOp::UnassignRoleToUser(a.role.clone(), a.user.clone()),
+            |a| PolicyOp::AssignRoleToUser(a.role.clone(), a.user.clone()),
+            &mut unassigns,
+            &mut assigns,
+        );
+        assignment_ops(
+            &self.role2groups,
+            &target.role2groups,
+            |a| PolicyOp::UnassignRoleToGroup(a.role.clone(), a.group.clone()),
+            |a| PolicyOp::AssignRoleToGroup(a.role.clone(), a.group.clone()),
+            &mut unassigns,
+            &mut assigns,
+        );
+        assignment_ops(
+            &self.user2groups,
+            &target.user2groups,
+            |a| PolicyOp::UnassignUserToGroup(a.user.clone(), a.group.clone()),
+            |a| PolicyOp::AssignUserToGroup(a.user.clone(), a.group.clone()),
+            &mut unassigns,
+            &mut assigns,
+        );
+        assignment_ops(
+            &self.permission2roles,
+            &target.permission2roles,
+            |a| PolicyOp::UnassignPermissionToRole(a.permission.clone(), a.role.clone()),
+            |a| PolicyOp::AssignPermissionToRole(a.permission.clone(), a.role.clone()),
+            &mut unassigns,
+            &mut assigns,
+        );
+
+        ops.append(&mut unassigns);
+        ops.append(&mut assigns);
+        ops.append(&mut disables);
+        ops
+    }
+
+    /// Items of `target` which are not in this policy and the other way round. A renamed,
+    /// enabled or disabled entity shows up as removed in its old and added in its new state.
+    pub fn diff(&self, target: &Self) -> PolicyDiff<EntityId> {
+        let missing = |from: &Self, other: &Self| GenesisPolicy {
+            roles: missing_items(&from.roles, &other.roles),
+            permissions: missing_items(&from.permissions, &other.permissions),
+            groups: missing_items(&from.groups, &other.groups),
+            role2users: missing_items(&from.role2users, &other.role2users),
+            role2groups: missing_items(&from.role2groups, &other.role2groups),
+            user2groups: missing_items(&from.user2groups, &other.user2groups),
+            permission2roles: missing_items(&from.permission2roles, &other.permission2roles),
+        };
+
+        PolicyDiff {
+            added: missing(target, self),
+            removed: missing(self, target),
+        }
+    }
+}
+
+/// Items of `from` which are not in `other`, in their original order
+fn missing_items<T: Ord + Clone>(from: &[T], other: &[T]) -> Vec<T> {
+    let other: BTreeSet<&T> = other.iter().collect();
+    from.iter()
+        .filter(|item| !other.contains(item))
+        .cloned()
+        .collect()
+}
+
+/// Difference between two states of an owner's policy
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, Default, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct PolicyDiff<EntityId> {
+    /// Entities and assignments only found in the new state
+    pub added: GenesisPolicy<EntityId>,
+    /// Entities and assignments only found in the old state
+    pub removed: GenesisPolicy<EntityId>,
+}
+
+impl<EntityId: Ord + Clone> PolicyDiff<EntityId> {
+    /// Whether both states are the same
+    pub fn is_empty(&self) -> bool {
+        self.added.item_count() == 0 && self.removed.item_count() == 0
+    }
+}
+
+/// Operations changing one kind of entity
+struct EntityOps<EntityId> {
+    add: fn(EntityId, Vec<u8>) -> PolicyOp<EntityId>,
+    update: fn(EntityId, Vec<u8>) -> PolicyOp<EntityId>,
+    enable: fn(EntityId) -> PolicyOp<EntityId>,
+    disable: fn(EntityId) -> PolicyOp<EntityId>,
+}
+
+impl<EntityId: Ord + Clone> EntityOps<EntityId> {
+    fn diff(
+        &self,
+        from: &[Entity<EntityId>],
+        to: &[Entity<EntityId>],
+        ops: &mut Vec<PolicyOp<EntityId>>,
+        disables: &mut Vec<PolicyOp<EntityId>>,
+    ) {
+        let current: BTreeMap<&EntityId, &Entity<EntityId>> =
+            from.iter().map(|e| (&e.id, e)).collect();
+
+        for target in to.iter() {
+            let id = target.id.clone();
+            let enabled = match current.get(&target.id) {
+                None => {
+                    ops.push((self.add)(id.clone(), target.name.clone()));
+                    true
+                }
+                Some(entity) if entity.name != target.name => {
+                    // Only enabled entities can be renamed
+                    if !entity.enabled {
+                        ops.push((self.enable)(id.clone()));
+                    }
+                    ops.push((self.update)(id.clone(), target.name.clone()));
+                    true
+                }
+                Some(entity) => entity.enabled,
+            };
+
+            if target.enabled && !enabled {
+                ops.push((self.enable)(id));
+            } else if !target.enabled && enabled {
+                disables.push((self.disable)(id));
+            }
+        }
+
+        let targets: BTreeSet<&EntityId> = to.iter().map(|e| &e.id).collect();
+        for entity in from
+            .iter()
+            .filter(|e| e.enabled && !targets.contains(&e.id))
+        {
+            disables.push((self.disable)(entity.id.clone()));
+        }
+    }
+}
+
+/// Collects the operations turning one kind of assignments `from` into `to`
+fn assignment_ops<A: Ord, EntityId>(
+    from: &[A],
+    to: &[A],
+    unassign: impl Fn(&A) -> PolicyOp<EntityId>,
+    assign: impl Fn(&A) -> PolicyOp<EntityId>,
+    unassigns: &mut Vec<PolicyOp<EntityId>>,
+    assigns: &mut Vec<PolicyOp<EntityId>>,
+) {
+    let current: BTreeSet<&A> = from.iter().collect();
+    let target: BTreeSet<&A> = to.iter().collect();
+    unassigns.extend(current.difference(&target).map(|a| unassign(a)));
+    assigns.extend(target.difference(&current).map(|a| assign(a)));
+}
+
+/// A single change to an owner's policy. A list of these is applied by `apply_policy_ops`
+/// within one storage transaction. Names aren't bounded here, as the bound is a pallet
+/// constant, but are checked against `MaxNameLength` when the operation is applied.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub enum PolicyOp<EntityId> {
+    /// [roleId, roleName]
+    AddRole(EntityId, Vec<u8>),
+    /// [roleId, roleName]
+    UpdateRole(EntityId, Vec<u8>),
+    /// [roleId]
+    DisableRole(EntityId),
+    /// [permissionId, permissionName]
+    AddPermission(EntityId, Vec<u8>),
+    /// [permissionId, permissionName]
+    UpdatePermission(EntityId, Vec<u8>),
+    /// [permissionId]
+    DisablePermission(EntityId),
+    /// [groupId, groupName]
+    AddGroup(EntityId, Vec<u8>),
+    /// [groupId, groupName]
+    UpdateGroup(EntityId, Vec<u8>),
+    /// [groupId]
+    DisableGroup(EntityId),
+    /// [roleId, userId]
+    AssignRoleToUser(EntityId, EntityId),
+    /// [roleId, userId]
+    UnassignRoleToUser(EntityId, EntityId),
+    /// [roleId, groupId]
+    AssignRoleToGroup(EntityId, EntityId),
+    /// [roleId, groupId]
+    UnassignRoleToGroup(EntityId, EntityId),
+    /// [userId, groupId]
+    AssignUserToGroup(EntityId, EntityId),
+    /// [userId, groupId]
+    UnassignUserToGroup(EntityId, EntityId),
+    /// [permissionId, roleId]
+    AssignPermissionToRole(EntityId, EntityId),
+    /// [permissionId, roleId]
+    UnassignPermissionToRole(EntityId, EntityId),
+    /// [roleId] Only applied by rollbacks, rejected by `apply_policy_ops` and drafts
+    EnableRole(EntityId),
+    /// [permissionId] Only applied by rollbacks, rejected by `apply_policy_ops` and drafts
+    EnablePermission(EntityId),
+    /// [groupId] Only applied by rollbacks, rejected by `apply_policy_ops` and drafts
+    EnableGroup(EntityId),
+}
+
+impl<EntityId> PolicyOp<EntityId> {
+    /// Returns the entity name carried by this operation, if any
+    pub fn name(&self) -> Option<&[u8]> {
+        match self {
+            Self::AddRole(_, name)
+            | Self::UpdateRole(_, name)
+            | Self::AddPermission(_, name)
+            | Self::UpdatePermission(_, name)
+            | Self::AddGroup(_, name)
+            | Self::UpdateGroup(_, name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Whether only rollbacks may apply this operation, as disabling stands for removing
+    pub fn is_internal(&self) -> bool {
+        matches!(
+            self,
+            Self::EnableRole(_) | Self::EnablePermission(_) | Self::EnableGroup(_)
+        )
+    }
+}
+
+/// A change of an owner's policy, as recorded in its audit log
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct AuditRecord<AccountId, EntityId> {
+    /// Sequence number of the change within the owner's log, starting at zero
+    pub seq: u64,
+    /// The change, including the ids of the affected entity or assignment
+    pub op: PolicyOp<EntityId>,
+    /// Account which made the change: the owner, the caller of `dispatch_with_permission`
+    /// or the account another pallet acts for, see `Pallet::with_actor`
+    pub who: AccountId,
+    pub block_number: u64,
+    /// Milliseconds since the Unix epoch, as set by `pallet_timestamp`
+    pub timestamp: u64,
+}
+
+/// A change of an owner's policy made in the current block, as announced by its
+/// `PolicyChanged` event
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct PolicyChange<AccountId, EntityId> {
+    pub op: PolicyOp<EntityId>,
+    /// Account which made the change, like `AuditRecord::who`
+    pub who: AccountId,
+}
+
+/// Versioned copy of an owner's policy, restorable by `rollback_policy`
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct PolicySnapshot<EntityId, Balance> {
+    pub label: Vec<u8>,
+    pub block_number: u64,
+    pub policy: GenesisPolicy<EntityId>,
+    /// Deposit reserved from the owner while the snapshot is stored
+    pub deposit: Balance,
+}
+
+/// Summary of a stored snapshot
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct SnapshotInfo {
+    pub id: u32,
+    pub label: Vec<u8>,
+    pub block_number: u64,
+    /// Number of entities and assignments in the snapshot
+    pub items: u32,
+}
+
+/// Rollback to a snapshot, applied in chunks across blocks. The operations still needed
+/// are computed against the live policy for each chunk, so changes made in the meantime
+/// are rolled back as well.
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct PendingRollback {
+    pub snapshot_id: u32,
+    /// Number of operations applied so far
+    pub applied: u32,
+}
+
+/// Name claimed while applying a batch of policy operations. Only checked once the whole
+/// batch is applied, as later operations may release it.
+#[derive(Clone, PartialEq, Eq, TypeInfo, Decode, Encode, RuntimeDebug)]
+pub struct NameClaim<EntityId> {
+    pub name: Vec<u8>,
+    /// Key of the name in `NameIndexStore`
+    pub name_key: RbacKeyType,
+    pub claimant: EntityId,
+    /// Key of the claimant in `KeysLookUpStore`
+    pub claimant_key: RbacKeyType,
+    /// Key of the entity indexed for the name before, which must have released it
+    pub displaced_key: Option<RbacKeyType>,
+}
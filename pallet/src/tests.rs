@@ -1,5 +1,33 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+    commitment,
+    error::RbacErrorType,
+    extension::CheckRbacPermission,
+    migrations,
+    mock::*,
+    name::{IdentifierNames, NameRules, Utf8Names},
+    proof::{self, ProofError, RbacProofVerifier},
+    rbac::{Rbac, Role, Tag},
+    structs::{
+        AllowedCall, Entity, GenesisPolicy, Permission2Role, PolicyOp, Role2Group, Role2User,
+        User2Group,
+    },
+    Error, Event, GenesisConfig, KeysLookUpStore, NameIndexStore, PolicyCommitmentStore,
+    PolicyTreeRootStore, PolicyTreeStore, PolicyUsersStore, WeightInfo,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::migration::{have_storage_value, put_storage_value},
+    traits::{Get, GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+    weights::Weight,
+    BoundedVec,
+};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, Convert, Hash, SignedExtension},
+    transaction_validity::InvalidTransaction,
+    DispatchError,
+};
 
 #[test]
 fn add_role_test() {
@@ -12,20 +40,22 @@ fn add_role_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for duplicate entry
         assert_noop!(
-            PeaqRBAC::add_role(RuntimeOrigin::signed(origin), role_id, name.to_vec(),),
+            PeaqRBAC::add_role(RuntimeOrigin::signed(origin), role_id, bounded_name(name),),
             Error::<Test>::EntityAlreadyExist
         );
 
         // Test name more than 64 chars
         let name = b"ADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMIN";
+        assert!(BoundedVec::<u8, MaxNameLength>::try_from(name.to_vec()).is_err());
+        let ops = vec![PolicyOp::AddRole(role_id, name.to_vec())];
         assert_noop!(
-            PeaqRBAC::add_role(RuntimeOrigin::signed(origin), role_id, name.to_vec(),),
-            Error::<Test>::EntityNameExceedMax64
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::EntityNameTooLong
         );
     });
 }
@@ -43,26 +73,26 @@ fn update_role_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for updating role not owned by origin
         let name = b"CAN_UPDATE";
         assert_noop!(
-            PeaqRBAC::update_role(RuntimeOrigin::signed(origin2), role_id, name.to_vec()),
+            PeaqRBAC::update_role(RuntimeOrigin::signed(origin2), role_id, bounded_name(name)),
             Error::<Test>::EntityDoesNotExist
         );
 
         assert_ok!(PeaqRBAC::update_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec()
+            bounded_name(name)
         ));
 
         // Test for removal of non-existing role
         let role_id = *b"22676474666576474646673646376638";
         assert_noop!(
-            PeaqRBAC::update_role(RuntimeOrigin::signed(origin), role_id, name.to_vec()),
+            PeaqRBAC::update_role(RuntimeOrigin::signed(origin), role_id, bounded_name(name)),
             Error::<Test>::EntityDoesNotExist
         );
     });
@@ -81,7 +111,7 @@ fn disable_role_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for removal of role not owned by origin
@@ -114,7 +144,7 @@ fn fetch_role_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::fetch_role(
@@ -144,12 +174,12 @@ fn fetch_roles_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id2,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::fetch_roles(RuntimeOrigin::signed(origin), origin));
@@ -170,7 +200,7 @@ fn assign_role_to_user_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for assigning role not owned by origin
@@ -214,7 +244,7 @@ fn unassign_role_to_user_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::assign_role_to_user(
@@ -258,19 +288,19 @@ fn assign_role_to_group_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin2),
             group_id2,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for assigning role not owned by origin
@@ -327,13 +357,13 @@ fn unassign_role_to_group_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::assign_role_to_group(
@@ -374,7 +404,7 @@ fn fetch_user_roles_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::assign_role_to_user(
@@ -409,20 +439,22 @@ fn add_permission_test() {
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for duplicate entry
         assert_noop!(
-            PeaqRBAC::add_permission(RuntimeOrigin::signed(origin), permission_id, name.to_vec(),),
+            PeaqRBAC::add_permission(RuntimeOrigin::signed(origin), permission_id, bounded_name(name),),
             Error::<Test>::EntityAlreadyExist
         );
 
         // Test name more than 64 chars
         let name = b"CAN_DELETECAN_DELETECAN_DELETECAN_DELETECAN_DELETECAN_DELETECAN_DELETECAN_DELETECAN_DELETECAN_DELETE";
+        assert!(BoundedVec::<u8, MaxNameLength>::try_from(name.to_vec()).is_err());
+        let ops = vec![PolicyOp::AddPermission(permission_id, name.to_vec())];
         assert_noop!(
-            PeaqRBAC::add_permission(RuntimeOrigin::signed(origin), permission_id, name.to_vec(),),
-            Error::<Test>::EntityNameExceedMax64
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::EntityNameTooLong
         );
     });
 }
@@ -440,7 +472,7 @@ fn update_permission_test() {
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for updating permission not owned by origin
@@ -449,7 +481,7 @@ fn update_permission_test() {
             PeaqRBAC::update_permission(
                 RuntimeOrigin::signed(origin2),
                 permission_id,
-                name.to_vec()
+                bounded_name(name)
             ),
             Error::<Test>::EntityDoesNotExist
         );
@@ -457,7 +489,7 @@ fn update_permission_test() {
         assert_ok!(PeaqRBAC::update_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec()
+            bounded_name(name)
         ));
 
         // Test for removal of non-existing permission
@@ -466,7 +498,7 @@ fn update_permission_test() {
             PeaqRBAC::update_permission(
                 RuntimeOrigin::signed(origin),
                 permission_id,
-                name.to_vec()
+                bounded_name(name)
             ),
             Error::<Test>::EntityDoesNotExist
         );
@@ -486,7 +518,7 @@ fn disable_permission_test() {
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for removal of permission not owned by origin
@@ -519,7 +551,7 @@ fn fetch_permission_test() {
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::fetch_permission(
@@ -549,12 +581,12 @@ fn fetch_permissions_test() {
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id2,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::fetch_permissions(
@@ -579,13 +611,13 @@ fn assign_permission_to_role_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            role_name.to_vec(),
+            bounded_name(role_name),
         ));
 
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for assigning permission not owned by origin
@@ -642,13 +674,13 @@ fn unassign_permission_to_role_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            role_name.to_vec(),
+            bounded_name(role_name),
         ));
 
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::assign_permission_to_role(
@@ -698,13 +730,13 @@ fn fetch_role_permissions_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            role_name.to_vec(),
+            bounded_name(role_name),
         ));
 
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::assign_permission_to_role(
@@ -739,20 +771,22 @@ fn add_group_test() {
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for duplicate entry
         assert_noop!(
-            PeaqRBAC::add_group(RuntimeOrigin::signed(origin), group_id, name.to_vec(),),
+            PeaqRBAC::add_group(RuntimeOrigin::signed(origin), group_id, bounded_name(name),),
             Error::<Test>::EntityAlreadyExist
         );
 
         // Test name more than 64 chars
         let name = b"UsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsersUsers";
+        assert!(BoundedVec::<u8, MaxNameLength>::try_from(name.to_vec()).is_err());
+        let ops = vec![PolicyOp::AddGroup(group_id, name.to_vec())];
         assert_noop!(
-            PeaqRBAC::add_group(RuntimeOrigin::signed(origin), group_id, name.to_vec(),),
-            Error::<Test>::EntityNameExceedMax64
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::EntityNameTooLong
         );
     });
 }
@@ -770,26 +804,26 @@ fn update_group_test() {
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for updating group not owned by origin
         let name = b"Admins";
         assert_noop!(
-            PeaqRBAC::update_group(RuntimeOrigin::signed(origin2), group_id, name.to_vec()),
+            PeaqRBAC::update_group(RuntimeOrigin::signed(origin2), group_id, bounded_name(name)),
             Error::<Test>::EntityDoesNotExist
         );
 
         assert_ok!(PeaqRBAC::update_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec()
+            bounded_name(name)
         ));
 
         // Test for removal of non-existing group
         let group_id = *b"12663776474646673646665421676477";
         assert_noop!(
-            PeaqRBAC::update_group(RuntimeOrigin::signed(origin), group_id, name.to_vec()),
+            PeaqRBAC::update_group(RuntimeOrigin::signed(origin), group_id, bounded_name(name)),
             Error::<Test>::EntityDoesNotExist
         );
     });
@@ -808,7 +842,7 @@ fn disable_group_test() {
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for removal of group not owned by origin
@@ -841,7 +875,7 @@ fn fetch_group_test() {
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::fetch_group(
@@ -871,13 +905,13 @@ fn fetch_groups_test() {
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id2,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::fetch_groups(
@@ -901,7 +935,7 @@ fn assign_user_to_group_test() {
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         // Test for assigning group not owned by origin
@@ -945,7 +979,7 @@ fn unassign_user_to_group_test() {
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::assign_user_to_group(
@@ -986,7 +1020,7 @@ fn fetch_user_groups_test() {
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::assign_user_to_group(
@@ -1024,19 +1058,19 @@ fn fetch_user_permissions_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::assign_user_to_group(
@@ -1078,19 +1112,19 @@ fn fetch_group_permissions_test() {
         assert_ok!(PeaqRBAC::add_role(
             RuntimeOrigin::signed(origin),
             role_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::add_permission(
             RuntimeOrigin::signed(origin),
             permission_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::add_group(
             RuntimeOrigin::signed(origin),
             group_id,
-            name.to_vec(),
+            bounded_name(name),
         ));
 
         assert_ok!(PeaqRBAC::assign_role_to_group(
@@ -1112,3 +1146,2107 @@ fn fetch_group_permissions_test() {
         ));
     });
 }
+
+#[test]
+fn clone_role_test() {
+    new_test_ext().execute_with(|| {
+        let acct = "Iredia";
+        let acct2 = "Iredia2";
+        let role_id = *b"16464667364637663721676474666576";
+        let new_role_id = *b"17464667364637663721676474666576";
+        let permission_id = *b"46464667364637663721676474666576";
+        let permission_id2 = *b"47464667364637663721676474666576";
+        let origin = account_key(acct);
+        let origin2 = account_key(acct2);
+        let name = b"CAN_DELETE";
+        let role_name = b"ADMIN";
+
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            role_id,
+            bounded_name(role_name),
+        ));
+
+        for permission in [permission_id, permission_id2] {
+            assert_ok!(PeaqRBAC::add_permission(
+                RuntimeOrigin::signed(origin),
+                permission,
+                bounded_name(name),
+            ));
+            assert_ok!(PeaqRBAC::assign_permission_to_role(
+                RuntimeOrigin::signed(origin),
+                permission,
+                role_id
+            ));
+        }
+
+        // Test for cloning role not owned by origin
+        assert_noop!(
+            PeaqRBAC::clone_role(
+                RuntimeOrigin::signed(origin2),
+                role_id,
+                new_role_id,
+                bounded_name(role_name)
+            ),
+            Error::<Test>::EntityDoesNotExist
+        );
+
+        assert_ok!(PeaqRBAC::clone_role(
+            RuntimeOrigin::signed(origin),
+            role_id,
+            new_role_id,
+            bounded_name(role_name)
+        ));
+
+        assert_eq!(
+            PeaqRBAC::get_role_permissions(&origin, new_role_id).unwrap(),
+            vec![
+                Permission2Role {
+                    permission: permission_id,
+                    role: new_role_id
+                },
+                Permission2Role {
+                    permission: permission_id2,
+                    role: new_role_id
+                },
+            ]
+        );
+
+        // Test for cloning into an existing role
+        assert_noop!(
+            PeaqRBAC::clone_role(
+                RuntimeOrigin::signed(origin),
+                role_id,
+                new_role_id,
+                bounded_name(role_name)
+            ),
+            Error::<Test>::EntityAlreadyExist
+        );
+
+        // Test for cloning a role holding more permissions than the limit
+        for i in 0..3u8 {
+            let mut permission = permission_id;
+            permission[0] = i;
+            assert_ok!(PeaqRBAC::add_permission(
+                RuntimeOrigin::signed(origin),
+                permission,
+                bounded_name(name),
+            ));
+            assert_ok!(PeaqRBAC::assign_permission_to_role(
+                RuntimeOrigin::signed(origin),
+                permission,
+                role_id
+            ));
+        }
+        let new_role_id = *b"18464667364637663721676474666576";
+        assert_noop!(
+            PeaqRBAC::clone_role(
+                RuntimeOrigin::signed(origin),
+                role_id,
+                new_role_id,
+                bounded_name(role_name)
+            ),
+            Error::<Test>::AssignmentLimitExceeded
+        );
+
+        // Test for cloning a disabled role
+        assert_ok!(PeaqRBAC::disable_role(
+            RuntimeOrigin::signed(origin),
+            role_id
+        ));
+        assert_noop!(
+            PeaqRBAC::clone_role(
+                RuntimeOrigin::signed(origin),
+                role_id,
+                new_role_id,
+                bounded_name(role_name)
+            ),
+            Error::<Test>::EntityDisabled
+        );
+    });
+}
+
+#[test]
+fn clone_group_test() {
+    new_test_ext().execute_with(|| {
+        let acct = "Iredia";
+        let acct2 = "Iredia2";
+        let group_id = *b"12663776474646673646665421676476";
+        let new_group_id = *b"13663776474646673646665421676476";
+        let role_id = *b"19464667364637663721676474666576";
+        let origin = account_key(acct);
+        let origin2 = account_key(acct2);
+        let name = b"Users";
+        let role_name = b"ADMIN";
+
+        assert_ok!(PeaqRBAC::add_group(
+            RuntimeOrigin::signed(origin),
+            group_id,
+            bounded_name(name),
+        ));
+
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            role_id,
+            bounded_name(role_name),
+        ));
+
+        assert_ok!(PeaqRBAC::assign_role_to_group(
+            RuntimeOrigin::signed(origin),
+            role_id,
+            group_id
+        ));
+
+        // Test for cloning group not owned by origin
+        assert_noop!(
+            PeaqRBAC::clone_group(
+                RuntimeOrigin::signed(origin2),
+                group_id,
+                new_group_id,
+                bounded_name(name)
+            ),
+            Error::<Test>::EntityDoesNotExist
+        );
+
+        assert_ok!(PeaqRBAC::clone_group(
+            RuntimeOrigin::signed(origin),
+            group_id,
+            new_group_id,
+            bounded_name(name)
+        ));
+
+        assert_eq!(
+            PeaqRBAC::get_group_roles(&origin, new_group_id).unwrap(),
+            vec![Role2Group {
+                role: role_id,
+                group: new_group_id
+            }]
+        );
+
+        // Test for cloning into an existing group
+        assert_noop!(
+            PeaqRBAC::clone_group(
+                RuntimeOrigin::signed(origin),
+                group_id,
+                new_group_id,
+                bounded_name(name)
+            ),
+            Error::<Test>::EntityAlreadyExist
+        );
+    });
+}
+
+#[test]
+fn apply_policy_ops_test() {
+    new_test_ext().execute_with(|| {
+        let acct = "Iredia";
+        let role_id = *b"25676474666576474646673646376637";
+        let permission_id = *b"48464667364637663721676474666576";
+        let group_id = *b"14663776474646673646665421676476";
+        let user_id = *b"13676474666576474646673646376637";
+        let origin = account_key(acct);
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, b"ADMIN".to_vec()),
+            PolicyOp::AddPermission(permission_id, b"CAN_DELETE".to_vec()),
+            PolicyOp::AddGroup(group_id, b"Users".to_vec()),
+            PolicyOp::AssignPermissionToRole(permission_id, role_id),
+            PolicyOp::AssignRoleToGroup(role_id, group_id),
+            PolicyOp::AssignUserToGroup(user_id, group_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+
+        assert_eq!(
+            PeaqRBAC::get_user_permissions(&origin, user_id)
+                .unwrap()
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>(),
+            vec![permission_id]
+        );
+
+        // Test that a failing operation rolls back the whole batch
+        let role_id2 = *b"26676474666576474646673646376637";
+        let ops = vec![
+            PolicyOp::AddRole(role_id2, b"OPERATOR".to_vec()),
+            PolicyOp::AssignRoleToUser(role_id2, user_id),
+            PolicyOp::AssignPermissionToRole(permission_id, role_id),
+        ];
+        assert_noop!(
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::AssignmentAlreadyExist
+        );
+
+        // Test that later operations are validated against earlier ones
+        let ops = vec![
+            PolicyOp::DisableRole(role_id),
+            PolicyOp::UpdateRole(role_id, b"ADMIN2".to_vec()),
+        ];
+        assert_noop!(
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::EntityDisabled
+        );
+
+        // Test name more than 64 chars
+        let name = b"ADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMINADMIN";
+        let ops = vec![PolicyOp::AddRole(role_id2, name.to_vec())];
+        assert_noop!(
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::EntityNameTooLong
+        );
+
+        // Test that disabled entities can't be enabled again, disabling stands for removing
+        assert_ok!(PeaqRBAC::disable_role(
+            RuntimeOrigin::signed(origin),
+            role_id
+        ));
+        let ops = vec![PolicyOp::EnableRole(role_id)];
+        assert_noop!(
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::PolicyOpNotAllowed
+        );
+    });
+}
+
+fn genesis_policy() -> GenesisPolicy<[u8; 32]> {
+    let role_id = *b"27676474666576474646673646376637";
+    let permission_id = *b"49464667364637663721676474666576";
+    let group_id = *b"15663776474646673646665421676476";
+    let user_id = *b"14676474666576474646673646376637";
+
+    GenesisPolicy {
+        roles: vec![Entity {
+            id: role_id,
+            name: b"ADMIN".to_vec(),
+            enabled: true,
+        }],
+        permissions: vec![Entity {
+            id: permission_id,
+            name: b"CAN_DELETE".to_vec(),
+            enabled: true,
+        }],
+        groups: vec![Entity {
+            id: group_id,
+            name: b"Users".to_vec(),
+            enabled: false,
+        }],
+        role2users: vec![],
+        role2groups: vec![Role2Group {
+            role: role_id,
+            group: group_id,
+        }],
+        user2groups: vec![User2Group {
+            user: user_id,
+            group: group_id,
+        }],
+        permission2roles: vec![Permission2Role {
+            permission: permission_id,
+            role: role_id,
+        }],
+    }
+}
+
+#[test]
+fn genesis_config_test() {
+    let origin = account_key("Iredia");
+    let policy = genesis_policy();
+    let config = GenesisConfig::<Test> {
+        policies: vec![(origin, policy.clone())],
+    };
+
+    new_test_ext_with_genesis(config).execute_with(|| {
+        assert_eq!(PeaqRBAC::role_of(origin), policy.roles);
+        assert_eq!(PeaqRBAC::permission_of(origin), policy.permissions);
+        assert_eq!(PeaqRBAC::group_of(origin), policy.groups);
+
+        // Disabled entities are seeded disabled
+        assert_noop!(
+            PeaqRBAC::fetch_group(RuntimeOrigin::signed(origin), origin, policy.groups[0].id),
+            Error::<Test>::EntityDisabled
+        );
+
+        assert_eq!(
+            PeaqRBAC::get_user_permissions(&origin, policy.user2groups[0].user).unwrap(),
+            policy.permissions
+        );
+    });
+}
+
+#[test]
+#[should_panic(expected = "cannot assign permission to role")]
+fn genesis_config_inconsistent_test() {
+    let origin = account_key("Iredia");
+    let mut policy = genesis_policy();
+    policy.permissions.clear();
+    let config = GenesisConfig::<Test> {
+        policies: vec![(origin, policy)],
+    };
+
+    new_test_ext_with_genesis(config);
+}
+
+#[test]
+fn has_permission_test() {
+    new_test_ext().execute_with(|| {
+        let acct = "Iredia";
+        let group_id = *b"16663776474646673646665421676476";
+        let role_id = *b"28676474666576474646673646376637";
+        let role_id2 = *b"29676474666576474646673646376637";
+        let permission_id = *b"50464667364637663721676474666576";
+        let permission_id2 = *b"51464667364637663721676474666576";
+        let user_id = *b"15676474666576474646673646376637";
+        let origin = account_key(acct);
+        let origin2 = account_key("Iredia2");
+        let name = b"Admin";
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, name.to_vec()),
+            PolicyOp::AddRole(role_id2, name.to_vec()),
+            PolicyOp::AddPermission(permission_id, name.to_vec()),
+            PolicyOp::AddPermission(permission_id2, name.to_vec()),
+            PolicyOp::AddGroup(group_id, name.to_vec()),
+            PolicyOp::AssignPermissionToRole(permission_id, role_id),
+            PolicyOp::AssignPermissionToRole(permission_id2, role_id2),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+            PolicyOp::AssignRoleToGroup(role_id2, group_id),
+            PolicyOp::AssignUserToGroup(user_id, group_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+
+        // Directly through a role and through a group
+        assert!(PeaqRBAC::has_permission(&origin, user_id, permission_id));
+        assert!(PeaqRBAC::has_permission(&origin, user_id, permission_id2));
+        assert!(PeaqRBAC::has_role(&origin, user_id, role_id));
+        assert!(PeaqRBAC::has_role(&origin, user_id, role_id2));
+        let permission_ids = |owner| -> Vec<[u8; 32]> {
+            PeaqRBAC::get_user_permissions(owner, user_id)
+                .unwrap()
+                .iter()
+                .map(|p| p.id)
+                .collect()
+        };
+        assert_eq!(permission_ids(&origin), vec![permission_id, permission_id2]);
+
+        // Another owner's policy does not grant anything
+        assert!(!PeaqRBAC::has_permission(&origin2, user_id, permission_id));
+        assert!(!PeaqRBAC::has_role(&origin2, user_id, role_id));
+
+        // A disabled group no longer grants its roles, while other grants stay intact
+        assert_ok!(PeaqRBAC::disable_group(
+            RuntimeOrigin::signed(origin),
+            group_id
+        ));
+        assert!(!PeaqRBAC::has_permission(&origin, user_id, permission_id2));
+        assert!(!PeaqRBAC::has_role(&origin, user_id, role_id2));
+        assert!(PeaqRBAC::has_permission(&origin, user_id, permission_id));
+        // The permissions listed agree with has_permission
+        assert_eq!(permission_ids(&origin), vec![permission_id]);
+        assert!(PeaqRBAC::get_group_permissions(&origin, group_id)
+            .unwrap()
+            .is_empty());
+
+        // A disabled permission is never held
+        assert_ok!(PeaqRBAC::disable_permission(
+            RuntimeOrigin::signed(origin),
+            permission_id
+        ));
+        assert!(!PeaqRBAC::has_permission(&origin, user_id, permission_id));
+        assert!(permission_ids(&origin).is_empty());
+    });
+}
+
+#[test]
+fn ensure_rbac_permission_test() {
+    new_test_ext().execute_with(|| {
+        let owner = DeviceOwner::get();
+        let device = account_key("Device");
+        let role_id = *b"30676474666576474646673646376637";
+        let user_id = AccountToUser::convert(device);
+
+        // Test for calling without holding the permission
+        assert_noop!(
+            Dummy::guarded(RuntimeOrigin::signed(device)),
+            DispatchError::BadOrigin
+        );
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, b"DEVICE".to_vec()),
+            PolicyOp::AddPermission(DevicePermission::get(), b"CAN_TRIGGER".to_vec()),
+            PolicyOp::AssignPermissionToRole(DevicePermission::get(), role_id),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(owner),
+            ops.try_into().unwrap()
+        ));
+
+        assert_ok!(Dummy::guarded(RuntimeOrigin::signed(device)));
+
+        // Unsigned origins are never accepted
+        assert_noop!(
+            Dummy::guarded(RuntimeOrigin::none()),
+            DispatchError::BadOrigin
+        );
+
+        // Test for calling after the role has been revoked
+        assert_ok!(PeaqRBAC::unassign_role_to_user(
+            RuntimeOrigin::signed(owner),
+            role_id,
+            user_id
+        ));
+        assert_noop!(
+            Dummy::guarded(RuntimeOrigin::signed(device)),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn check_rbac_permission_test() {
+    new_test_ext().execute_with(|| {
+        let owner = DeviceOwner::get();
+        let device = account_key("Device");
+        let role_id = *b"31676474666576474646673646376637";
+        let user_id = AccountToUser::convert(device);
+        let gated: RuntimeCall = pallet_dummy::Call::guarded {}.into();
+        let ungated: RuntimeCall = frame_system::Call::remark { remark: vec![] }.into();
+        let info = Default::default();
+        let ext = CheckRbacPermission::<Test>::new();
+
+        // Calls without a mapped permission are not affected
+        assert_ok!(ext.validate(&device, &ungated, &info, 0));
+
+        // Test for submitting a gated call without holding the permission
+        assert_eq!(
+            ext.validate(&device, &gated, &info, 0),
+            Err(InvalidTransaction::Custom(RbacErrorType::PermissionDenied.code()).into())
+        );
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, b"DEVICE".to_vec()),
+            PolicyOp::AddPermission(DevicePermission::get(), b"CAN_TRIGGER".to_vec()),
+            PolicyOp::AssignPermissionToRole(DevicePermission::get(), role_id),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(owner),
+            ops.try_into().unwrap()
+        ));
+
+        assert_ok!(ext.validate(&device, &gated, &info, 0));
+        let weight = System::block_weight().total();
+        assert_ok!(ext.clone().pre_dispatch(&device, &gated, &info, 0));
+
+        // The check is charged for the single role examined
+        let lookup = <Test as crate::Config>::WeightInfo::dispatch_with_permission(1)
+            .saturating_sub(<Test as crate::Config>::WeightInfo::dispatch_with_permission(0));
+        assert_eq!(
+            System::block_weight().total(),
+            weight
+                .saturating_add(<Test as crate::Config>::WeightInfo::fetch_user_permissions())
+                .saturating_add(lookup)
+        );
+
+        // Signers with more roles before the granting one than examined are rejected
+        let device2 = account_key("Device2");
+        let user_id2 = AccountToUser::convert(device2);
+        let mut ops = vec![];
+        for i in 0..4u8 {
+            let mut idle_role_id = *b"40676474666576474646673646376637";
+            idle_role_id[0] = b'a' + i;
+            ops.push(PolicyOp::AddRole(idle_role_id, b"IDLE".to_vec()));
+            ops.push(PolicyOp::AssignRoleToUser(idle_role_id, user_id2));
+        }
+        ops.push(PolicyOp::AssignRoleToUser(role_id, user_id2));
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(owner),
+            ops.try_into().unwrap()
+        ));
+        assert_eq!(
+            ext.validate(&device2, &gated, &info, 0),
+            Err(InvalidTransaction::Custom(RbacErrorType::AssignmentLimitExceeded.code()).into())
+        );
+
+        // Resolution errors are reported with the code of their error type
+        assert_ok!(PeaqRBAC::disable_permission(
+            RuntimeOrigin::signed(owner),
+            DevicePermission::get()
+        ));
+        assert_eq!(
+            ext.validate(&device, &gated, &info, 0),
+            Err(InvalidTransaction::Custom(RbacErrorType::EntityDisabled.code()).into())
+        );
+    });
+}
+
+#[test]
+fn dispatch_with_permission_test() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        let owner = account_key("Iredia");
+        let device = account_key("Device");
+        let role_id = *b"32676474666576474646673646376637";
+        let permission_id = *b"53464667364637663721676474666576";
+        let user_id = AccountToUser::convert(device);
+        let call: RuntimeCall = frame_system::Call::remark_with_event {
+            remark: b"triggered".to_vec(),
+        }
+        .into();
+
+        // Test for dispatching without holding the permission
+        assert_noop!(
+            PeaqRBAC::dispatch_with_permission(
+                RuntimeOrigin::signed(device),
+                owner,
+                permission_id,
+                Box::new(call.clone())
+            ),
+            Error::<Test>::PermissionDenied
+        );
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, b"DEVICE".to_vec()),
+            PolicyOp::AddPermission(permission_id, b"CAN_REMARK".to_vec()),
+            PolicyOp::AssignPermissionToRole(permission_id, role_id),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(owner),
+            ops.try_into().unwrap()
+        ));
+
+        // Test for dispatching a call not allowed by the permission
+        assert_noop!(
+            PeaqRBAC::dispatch_with_permission(
+                RuntimeOrigin::signed(device),
+                owner,
+                permission_id,
+                Box::new(call.clone())
+            ),
+            Error::<Test>::CallNotAllowed
+        );
+
+        // Test for setting the call filter of a permission not owned by origin
+        let calls = vec![AllowedCall {
+            pallet_name: b"System".to_vec(),
+            function_name: b"remark_with_event".to_vec(),
+        }];
+        assert_noop!(
+            PeaqRBAC::set_permission_calls(
+                RuntimeOrigin::signed(device),
+                permission_id,
+                calls.clone().try_into().unwrap()
+            ),
+            Error::<Test>::EntityDoesNotExist
+        );
+
+        assert_ok!(PeaqRBAC::set_permission_calls(
+            RuntimeOrigin::signed(owner),
+            permission_id,
+            calls.try_into().unwrap()
+        ));
+
+        assert_ok!(PeaqRBAC::dispatch_with_permission(
+            RuntimeOrigin::signed(device),
+            owner,
+            permission_id,
+            Box::new(call)
+        ));
+
+        // The call has been dispatched as the owner
+        System::assert_has_event(
+            frame_system::Event::Remarked {
+                sender: owner,
+                hash: BlakeTwo256::hash(b"triggered"),
+            }
+            .into(),
+        );
+        System::assert_last_event(
+            Event::<Test>::DispatchedWithPermission(device, owner, permission_id, Ok(())).into(),
+        );
+
+        // Test for dispatching calls of this pallet or excluded by DispatchFilter, even
+        // though the permission allows them
+        let calls = vec![
+            AllowedCall {
+                pallet_name: b"PeaqRBAC".to_vec(),
+                function_name: vec![],
+            },
+            AllowedCall {
+                pallet_name: b"Balances".to_vec(),
+                function_name: vec![],
+            },
+        ];
+        assert_ok!(PeaqRBAC::set_permission_calls(
+            RuntimeOrigin::signed(owner),
+            permission_id,
+            calls.try_into().unwrap()
+        ));
+        let rbac_call: RuntimeCall = crate::Call::<Test>::add_role {
+            role_id: *b"33676474666576474646673646376637",
+            name: b"ESCALATED".to_vec(),
+        }
+        .into();
+        let balances_call: RuntimeCall = pallet_balances::Call::transfer {
+            dest: device,
+            value: 1,
+        }
+        .into();
+        for call in [rbac_call, balances_call] {
+            assert_noop!(
+                PeaqRBAC::dispatch_with_permission(
+                    RuntimeOrigin::signed(device),
+                    owner,
+                    permission_id,
+                    Box::new(call)
+                ),
+                Error::<Test>::CallNotAllowed
+            );
+        }
+    });
+}
+
+#[test]
+fn dispatch_with_permission_lookup_limit_test() {
+    new_test_ext().execute_with(|| {
+        let owner = account_key("Iredia");
+        let device = account_key("Device");
+        let role_id = *b"32676474666576474646673646376637";
+        let permission_id = *b"53464667364637663721676474666576";
+        let user_id = AccountToUser::convert(device);
+        let call: RuntimeCall = frame_system::Call::remark_with_event {
+            remark: b"triggered".to_vec(),
+        }
+        .into();
+
+        // The granting role comes after MaxDispatchLookups roles which don't grant it
+        let mut ops: Vec<PolicyOp<[u8; 32]>> = (0..4u8)
+            .flat_map(|i| {
+                let mut other_role_id = role_id;
+                other_role_id[0] = b'a' + i;
+                [
+                    PolicyOp::AddRole(other_role_id, b"OTHER".to_vec()),
+                    PolicyOp::AssignRoleToUser(other_role_id, user_id),
+                ]
+            })
+            .collect();
+        ops.extend([
+            PolicyOp::AddRole(role_id, b"DEVICE".to_vec()),
+            PolicyOp::AddPermission(permission_id, b"CAN_REMARK".to_vec()),
+            PolicyOp::AssignPermissionToRole(permission_id, role_id),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+        ]);
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(owner),
+            ops.try_into().unwrap()
+        ));
+        let calls = vec![AllowedCall {
+            pallet_name: b"System".to_vec(),
+            function_name: vec![],
+        }];
+        assert_ok!(PeaqRBAC::set_permission_calls(
+            RuntimeOrigin::signed(owner),
+            permission_id,
+            calls.try_into().unwrap()
+        ));
+
+        assert_noop!(
+            PeaqRBAC::dispatch_with_permission(
+                RuntimeOrigin::signed(device),
+                owner,
+                permission_id,
+                Box::new(call.clone())
+            ),
+            Error::<Test>::AssignmentLimitExceeded
+        );
+
+        // The permission is found once the user holds fewer roles
+        let mut other_role_id = role_id;
+        other_role_id[0] = b'a';
+        assert_ok!(PeaqRBAC::unassign_role_to_user(
+            RuntimeOrigin::signed(owner),
+            other_role_id,
+            user_id
+        ));
+        assert_ok!(PeaqRBAC::dispatch_with_permission(
+            RuntimeOrigin::signed(device),
+            owner,
+            permission_id,
+            Box::new(call)
+        ));
+    });
+}
+
+#[test]
+fn verify_read_proof_test() {
+    let acct = "Iredia";
+    let role_id = *b"30676474666576474646673646376637";
+    let permission_id = *b"53464667364637663721676474666576";
+    let user_id = *b"16676474666576474646673646376637";
+    let unknown_user_id = *b"17676474666576474646673646376637";
+    let origin = account_key(acct);
+    let name = b"Admin";
+    let prefix = b"PeaqRBAC";
+
+    let mut ext = new_test_ext();
+    ext.execute_with(|| {
+        let ops = vec![
+            PolicyOp::AddRole(role_id, name.to_vec()),
+            PolicyOp::AddPermission(permission_id, name.to_vec()),
+            PolicyOp::AssignPermissionToRole(permission_id, role_id),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+    });
+    ext.commit_all().unwrap();
+
+    let keys = vec![
+        proof::user_roles_storage_key(prefix, &origin, &user_id),
+        proof::user_roles_storage_key(prefix, &origin, &unknown_user_id),
+        proof::role_permissions_storage_key(prefix, &origin, &role_id),
+        proof::entity_storage_key(prefix, &origin, &role_id, Tag::Role),
+    ];
+    let backend = ext.as_backend();
+    let root = *backend.root();
+    let read_proof = sp_state_machine::prove_read(backend, &keys).unwrap();
+    let nodes: Vec<Vec<u8>> = read_proof.into_iter_nodes().collect();
+
+    let verifier = RbacProofVerifier::new(prefix, root, nodes.clone());
+    let (expected_roles, expected_permissions) = ext.execute_with(|| {
+        (
+            PeaqRBAC::get_user_roles(&origin, user_id).unwrap(),
+            PeaqRBAC::get_role_permissions(&origin, role_id).unwrap(),
+        )
+    });
+    assert_eq!(
+        verifier.user_roles(&origin, &user_id).unwrap().unwrap(),
+        expected_roles
+    );
+    assert_eq!(
+        verifier
+            .role_permissions(&origin, &role_id)
+            .unwrap()
+            .unwrap(),
+        expected_permissions
+    );
+    assert!(
+        verifier
+            .entity(&origin, &role_id, Tag::Role)
+            .unwrap()
+            .unwrap()
+            .enabled
+    );
+
+    // Absence of an assignment is proven as well
+    let err = verifier
+        .user_roles(&origin, &unknown_user_id)
+        .unwrap()
+        .unwrap_err();
+    assert!(matches!(err.typ, RbacErrorType::AssignmentDoesNotExist));
+
+    // Keys not covered by the proof cannot be answered
+    assert_eq!(
+        verifier
+            .role_permissions(&origin, &permission_id)
+            .unwrap_err(),
+        ProofError::InvalidProof
+    );
+
+    // A proof does not verify against another state root
+    let verifier = RbacProofVerifier::new(prefix, Default::default(), nodes);
+    assert_eq!(
+        verifier.user_roles(&origin, &user_id).unwrap_err(),
+        ProofError::InvalidProof
+    );
+}
+
+#[test]
+fn policy_commitment_test() {
+    let origin = account_key("Iredia");
+    let mut policy = genesis_policy();
+    let config = GenesisConfig::<Test> {
+        policies: vec![(origin, policy.clone())],
+    };
+
+    new_test_ext_with_genesis(config).execute_with(|| {
+        let role_id = policy.roles[0].id;
+        let permission_id = policy.permissions[0].id;
+
+        // Genesis is committed to like any other change
+        let root = PeaqRBAC::policy_commitment_of(origin);
+        assert_ne!(root, H256::zero());
+        assert_eq!(root, commitment::policy_root(&policy));
+        assert_eq!(
+            PeaqRBAC::policy_commitment_of(account_key("Iredia2")),
+            H256::zero()
+        );
+
+        // Updates replace the committed entity
+        assert_ok!(PeaqRBAC::update_role(
+            RuntimeOrigin::signed(origin),
+            role_id,
+            bounded_name(b"OPERATOR"),
+        ));
+        policy.roles[0].name = b"OPERATOR".to_vec();
+        assert_eq!(
+            PeaqRBAC::policy_commitment_of(origin),
+            commitment::policy_root(&policy)
+        );
+
+        // Revoking an assignment removes it from the commitment
+        assert_ok!(PeaqRBAC::unassign_permission_to_role(
+            RuntimeOrigin::signed(origin),
+            permission_id,
+            role_id,
+        ));
+        policy.permission2roles.clear();
+        assert_eq!(
+            PeaqRBAC::policy_commitment_of(origin),
+            commitment::policy_root(&policy)
+        );
+
+        assert_ok!(PeaqRBAC::disable_permission(
+            RuntimeOrigin::signed(origin),
+            permission_id,
+        ));
+        policy.permissions[0].enabled = false;
+        assert_eq!(
+            PeaqRBAC::policy_commitment_of(origin),
+            commitment::policy_root(&policy)
+        );
+    });
+}
+
+#[test]
+fn verify_access_proof_test() {
+    new_test_ext().execute_with(|| {
+        let owner = account_key("Iredia");
+        let origin = account_key("Iredia2");
+        let role_id = *b"31676474666576474646673646376637";
+        let permission_id = *b"54464667364637663721676474666576";
+        let permission_id2 = *b"55464667364637663721676474666576";
+        let user_id = *b"18676474666576474646673646376637";
+        let user_id2 = *b"19676474666576474646673646376637";
+        let entity = |id| Entity {
+            id,
+            name: b"Admin".to_vec(),
+            enabled: true,
+        };
+
+        let mut policy = GenesisPolicy {
+            roles: vec![entity(role_id)],
+            permissions: vec![entity(permission_id), entity(permission_id2)],
+            role2users: vec![Role2User {
+                role: role_id,
+                user: user_id,
+            }],
+            permission2roles: vec![Permission2Role {
+                permission: permission_id,
+                role: role_id,
+            }],
+            ..Default::default()
+        };
+        let proof = commitment::access_proof(&policy, &user_id, &permission_id).unwrap();
+        assert!(commitment::access_proof(&policy, &user_id, &permission_id2).is_none());
+
+        // Test for verifying before a root has been published
+        assert_noop!(
+            PeaqRBAC::verify_access_proof(
+                RuntimeOrigin::signed(origin),
+                owner,
+                user_id,
+                permission_id,
+                proof.clone()
+            ),
+            Error::<Test>::PolicyRootNotPublished
+        );
+
+        let root = commitment::policy_root(&policy);
+        assert_ok!(PeaqRBAC::publish_policy_root(
+            RuntimeOrigin::signed(owner),
+            root
+        ));
+        System::assert_last_event(Event::<Test>::PolicyRootPublished(owner, root).into());
+
+        assert_ok!(PeaqRBAC::verify_access_proof(
+            RuntimeOrigin::signed(origin),
+            owner,
+            user_id,
+            permission_id,
+            proof.clone()
+        ));
+        System::assert_last_event(
+            Event::<Test>::AccessAttested(origin, owner, user_id, permission_id, role_id).into(),
+        );
+
+        // Test for a proof of another user or permission
+        assert_noop!(
+            PeaqRBAC::verify_access_proof(
+                RuntimeOrigin::signed(origin),
+                owner,
+                user_id2,
+                permission_id,
+                proof.clone()
+            ),
+            Error::<Test>::InvalidAccessProof
+        );
+        assert_noop!(
+            PeaqRBAC::verify_access_proof(
+                RuntimeOrigin::signed(origin),
+                owner,
+                user_id,
+                permission_id2,
+                proof.clone()
+            ),
+            Error::<Test>::InvalidAccessProof
+        );
+
+        // Test for a proof against an outdated root, after the permission got disabled
+        policy.permissions[0].enabled = false;
+        assert_ok!(PeaqRBAC::publish_policy_root(
+            RuntimeOrigin::signed(owner),
+            commitment::policy_root(&policy)
+        ));
+        assert_noop!(
+            PeaqRBAC::verify_access_proof(
+                RuntimeOrigin::signed(origin),
+                owner,
+                user_id,
+                permission_id,
+                proof
+            ),
+            Error::<Test>::InvalidAccessProof
+        );
+    });
+}
+
+#[test]
+fn merkle_proof_test() {
+    let leaves: Vec<H256> = (0u8..7).map(H256::repeat_byte).collect();
+    let mut tree = commitment::PolicyTree::from_leaves(leaves.clone());
+    let root = tree.root();
+
+    // The tree only depends on its leaves, not on their order
+    assert_eq!(
+        commitment::PolicyTree::from_leaves(leaves.iter().rev().copied()).root(),
+        root
+    );
+
+    for leaf in leaves.iter() {
+        let proof = tree.proof(leaf).unwrap();
+        assert!(commitment::verify_merkle_proof(&root, *leaf, &proof));
+        assert!(!commitment::verify_merkle_proof(
+            &root,
+            H256::repeat_byte(9),
+            &proof
+        ));
+    }
+    assert!(tree.proof(&H256::repeat_byte(9)).is_none());
+
+    // Removing a leaf gives the tree built without it
+    tree.remove(&leaves[3]);
+    let rest = leaves.iter().filter(|leaf| **leaf != leaves[3]).copied();
+    assert_eq!(
+        tree.root(),
+        commitment::PolicyTree::from_leaves(rest).root()
+    );
+    assert!(tree.proof(&leaves[3]).is_none());
+
+    // A branch is no leaf, even for a leaf encoding its children
+    let node = commitment::hash_node(0, &leaves[0], &leaves[1]);
+    assert_ne!(
+        node,
+        commitment::hash_leaf(&[&[0u8][..], leaves[0].as_bytes(), leaves[1].as_bytes()].concat())
+    );
+
+    // Proofs deeper than any tree fail to decode
+    let encoded = vec![(0u8, H256::zero()); 257].encode();
+    assert!(commitment::MerkleProof::decode(&mut &encoded[..]).is_err());
+}
+
+#[test]
+fn policy_tree_depth_test() {
+    new_test_ext().execute_with(|| {
+        let origin = account_key("Iredia");
+        let depth = <Test as crate::Config>::MaxPolicyTreeDepth::get();
+        // Key differing from the zero key at its `bit`th bit only
+        let key = |bit: u32| {
+            let mut key = H256::zero();
+            key.0[bit as usize / 8] = 0x80 >> (bit % 8);
+            key
+        };
+
+        // Each key splits from the zero key at a later bit
+        for bit in 0..depth {
+            assert_ok!(PeaqRBAC::update_policy_tree(&origin, None, Some(key(bit))));
+        }
+        assert_ok!(PeaqRBAC::update_policy_tree(
+            &origin,
+            None,
+            Some(H256::zero())
+        ));
+        let root = PeaqRBAC::policy_tree_root_of(origin).unwrap();
+        assert_eq!(root.height(), depth);
+        assert_eq!(PeaqRBAC::policy_commitment_of(origin), root.hash());
+
+        // Nothing is written for a change growing the tree too deep
+        let err = PeaqRBAC::update_policy_tree(&origin, None, Some(key(depth))).unwrap_err();
+        assert!(matches!(err.typ, RbacErrorType::PolicyTreeTooDeep));
+        assert_eq!(PeaqRBAC::policy_tree_root_of(origin), Some(root));
+        assert!(PeaqRBAC::policy_tree_node_of(
+            origin,
+            commitment::NodeId {
+                bit: depth as u8,
+                prefix: H256::zero(),
+            }
+        )
+        .is_none());
+
+        // Removing a leaf shortens the path
+        assert_ok!(PeaqRBAC::update_policy_tree(&origin, Some(key(0)), None));
+        let mut leaves: Vec<H256> = (1..depth).map(key).collect();
+        leaves.push(H256::zero());
+        let tree = commitment::PolicyTree::from_leaves(leaves);
+        assert_eq!(tree.height(), depth - 1);
+        assert_eq!(PeaqRBAC::policy_commitment_of(origin), tree.root());
+    });
+}
+
+#[test]
+fn policy_tree_depth_trait_call_test() {
+    new_test_ext().execute_with(|| {
+        UniqueNames::set(true);
+        let origin = account_key("Iredia");
+        let origin2 = account_key("Iredia2");
+        let role_id = *b"38676474666576474646673646376637";
+        let user_id = *b"20676474666576474646673646376637";
+        let name = b"FLEET_ADMIN";
+        let depth = <Test as crate::Config>::MaxPolicyTreeDepth::get();
+        let flip = |mut key: H256, bit: u32| {
+            key.0[bit as usize / 8] ^= 0x80 >> (bit % 8);
+            key
+        };
+        // Grows the owner's tree to its maximum depth along the path of `leaf`, so that
+        // adding the leaf makes it too deep
+        let fill = |owner, leaf: H256| {
+            let base = flip(leaf, depth);
+            for bit in 0..depth {
+                assert_ok!(PeaqRBAC::update_policy_tree(
+                    owner,
+                    None,
+                    Some(flip(base, bit))
+                ));
+            }
+            assert_ok!(PeaqRBAC::update_policy_tree(owner, None, Some(base)));
+        };
+
+        // Other pallets call the traits without the storage layer of a dispatchable
+        let role = Entity {
+            id: role_id,
+            name: name.to_vec(),
+            enabled: true,
+        };
+        fill(&origin, commitment::PolicyLeaf::Role(role).hash());
+        let root = PeaqRBAC::policy_tree_root_of(origin);
+        let err = PeaqRBAC::create_role(&origin, role_id, name).unwrap_err();
+        assert!(matches!(err.typ, RbacErrorType::PolicyTreeTooDeep));
+        assert!(PeaqRBAC::role_of(origin).is_empty());
+        assert!(!KeysLookUpStore::<Test>::contains_key(
+            crate::rbac::generate_key(&origin, &role_id, Tag::Role)
+        ));
+        assert!(NameIndexStore::<Test>::iter().next().is_none());
+        assert_eq!(PeaqRBAC::policy_tree_root_of(origin), root);
+
+        assert_ok!(PeaqRBAC::create_role(&origin2, role_id, name));
+        let r2u = Role2User {
+            role: role_id,
+            user: user_id,
+        };
+        fill(&origin2, commitment::PolicyLeaf::Role2User(r2u).hash());
+        let root = PeaqRBAC::policy_tree_root_of(origin2);
+        let err = PeaqRBAC::create_role_to_user(&origin2, role_id, user_id).unwrap_err();
+        assert!(matches!(err.typ, RbacErrorType::PolicyTreeTooDeep));
+        assert!(PeaqRBAC::role_to_user_of(crate::rbac::generate_key(
+            &origin2,
+            &user_id,
+            Tag::Role2User
+        ))
+        .is_empty());
+        assert!(PeaqRBAC::users_of(origin2).is_empty());
+        assert_eq!(PeaqRBAC::policy_tree_root_of(origin2), root);
+    });
+}
+
+#[test]
+fn audit_log_test() {
+    new_test_ext().execute_with(|| {
+        let origin = account_key("Iredia");
+        let role_id = *b"32676474666576474646673646376637";
+        let permission_id = *b"56464667364637663721676474666576";
+        let user_id = *b"20676474666576474646673646376637";
+        let name = b"Admin";
+
+        System::set_block_number(2);
+        Timestamp::set_timestamp(1234);
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, name.to_vec()),
+            PolicyOp::AddPermission(permission_id, name.to_vec()),
+            PolicyOp::AssignPermissionToRole(permission_id, role_id),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+            PolicyOp::DisablePermission(permission_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.clone().try_into().unwrap()
+        ));
+
+        // The log keeps the last MaxAuditLogEntries changes
+        let capacity = <Test as crate::Config>::MaxAuditLogEntries::get() as usize;
+        assert_eq!(PeaqRBAC::audit_log_count_of(origin), ops.len() as u64);
+        let log = PeaqRBAC::audit_log(&origin, 0, 10);
+        assert_eq!(log.len(), capacity);
+        for (record, op) in log.iter().zip(ops[ops.len() - capacity..].iter()) {
+            assert_eq!(record.op, *op);
+            assert_eq!(record.who, origin);
+            assert_eq!(record.block_number, 2);
+            assert_eq!(record.timestamp, 1234);
+        }
+        assert_eq!(log[0].seq, 1);
+
+        // Test for pagination
+        let page = PeaqRBAC::audit_log(&origin, 0, 2);
+        assert_eq!(page, log[..2].to_vec());
+        let page = PeaqRBAC::audit_log(&origin, 3, 10);
+        assert_eq!(page, log[2..].to_vec());
+        assert!(PeaqRBAC::audit_log(&origin, 5, 10).is_empty());
+
+        // Failed changes are not recorded
+        assert_noop!(
+            PeaqRBAC::add_role(RuntimeOrigin::signed(origin), role_id, bounded_name(name)),
+            Error::<Test>::EntityAlreadyExist
+        );
+        assert!(PeaqRBAC::audit_log(&account_key("Iredia2"), 0, 10).is_empty());
+
+        // Changes made on behalf of the owner record the actor
+        let actor = account_key("Iredia2");
+        assert_ok!(PeaqRBAC::with_actor(&actor, || {
+            PeaqRBAC::disable_existing_role(&origin, role_id)
+        }));
+        assert!(PeaqRBAC::actor().is_none());
+        let record = PeaqRBAC::audit_log(&origin, 5, 1).pop().unwrap();
+        assert_eq!(record.op, PolicyOp::DisableRole(role_id));
+        assert_eq!(record.who, actor);
+
+        // All changes of the block are announced, also those the log no longer keeps
+        let changes = PeaqRBAC::policy_changes(&origin);
+        assert_eq!(changes.len(), ops.len() + 1);
+        for (change, op) in changes.iter().zip(ops.iter()) {
+            assert_eq!(change.op, *op);
+            assert_eq!(change.who, origin);
+        }
+        assert_eq!(changes[ops.len()].who, actor);
+        assert!(PeaqRBAC::policy_changes(&actor).is_empty());
+    });
+}
+
+#[test]
+fn policy_snapshot_test() {
+    new_test_ext().execute_with(|| {
+        let origin = account_key("Iredia");
+        let role_id = *b"33676474666576474646673646376637";
+        let role_id2 = *b"34676474666576474646673646376637";
+        let permission_id = *b"57464667364637663721676474666576";
+        let user_id = *b"22676474666576474646673646376637";
+        let name = b"Admin";
+
+        System::set_block_number(1);
+        Balances::make_free_balance_be(&origin, 1000);
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, name.to_vec()),
+            PolicyOp::AddPermission(permission_id, name.to_vec()),
+            PolicyOp::AssignPermissionToRole(permission_id, role_id),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+        let snapshotted = PeaqRBAC::export_policy(&origin);
+        assert_eq!(snapshotted.item_count(), 4);
+
+        // Test for label length
+        assert!(BoundedVec::<u8, MaxSnapshotLabelLength>::try_from(vec![b'a'; 65]).is_err());
+
+        assert_ok!(PeaqRBAC::snapshot_policy(
+            RuntimeOrigin::signed(origin),
+            bounded_label(b"v1")
+        ));
+        System::assert_last_event(
+            Event::<Test>::PolicySnapshotted(origin, 0, b"v1".to_vec(), 4).into(),
+        );
+        assert_eq!(Balances::reserved_balance(origin), 14);
+        let snapshots = PeaqRBAC::snapshots(&origin);
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].label, b"v1".to_vec());
+        assert_eq!(snapshots[0].items, 4);
+
+        // Change the policy
+        assert_ok!(PeaqRBAC::unassign_permission_to_role(
+            RuntimeOrigin::signed(origin),
+            permission_id,
+            role_id
+        ));
+        assert_ok!(PeaqRBAC::disable_role(
+            RuntimeOrigin::signed(origin),
+            role_id
+        ));
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            role_id2,
+            bounded_name(name)
+        ));
+        assert_ok!(PeaqRBAC::assign_role_to_user(
+            RuntimeOrigin::signed(origin),
+            role_id2,
+            user_id
+        ));
+
+        // Test for unknown snapshot
+        assert_noop!(
+            PeaqRBAC::rollback_policy(RuntimeOrigin::signed(origin), 7),
+            Error::<Test>::SnapshotDoesNotExist
+        );
+
+        assert_ok!(PeaqRBAC::rollback_policy(RuntimeOrigin::signed(origin), 0));
+        System::assert_last_event(Event::<Test>::RollbackStarted(origin, 0, 4).into());
+        assert_noop!(
+            PeaqRBAC::rollback_policy(RuntimeOrigin::signed(origin), 0),
+            Error::<Test>::RollbackPending
+        );
+
+        // Nothing is applied without enough weight
+        PeaqRBAC::on_idle(1, Weight::zero());
+        assert_eq!(PeaqRBAC::pending_rollback_of(origin).unwrap().applied, 0);
+
+        // The rollback is applied in chunks of RollbackChunkSize operations
+        PeaqRBAC::on_idle(1, Weight::MAX);
+        assert_eq!(PeaqRBAC::pending_rollback_of(origin).unwrap().applied, 2);
+
+        // The snapshot can't be removed while being rolled back to
+        assert_noop!(
+            PeaqRBAC::remove_snapshot(RuntimeOrigin::signed(origin), 0),
+            Error::<Test>::RollbackPending
+        );
+
+        PeaqRBAC::on_idle(1, Weight::MAX);
+        assert!(PeaqRBAC::pending_rollback_of(origin).is_none());
+        System::assert_last_event(Event::<Test>::PolicyRolledBack(origin, 0, 4).into());
+
+        // Entities can't be removed, so the new role is disabled
+        let mut expected = snapshotted.clone();
+        expected.roles.push(Entity {
+            id: role_id2,
+            name: name.to_vec(),
+            enabled: false,
+        });
+        assert_eq!(PeaqRBAC::export_policy(&origin), expected);
+        assert_eq!(
+            PeaqRBAC::policy_commitment_of(origin),
+            commitment::policy_root(&expected)
+        );
+
+        // A rollback to the current policy is done at once
+        assert_ok!(PeaqRBAC::snapshot_policy(
+            RuntimeOrigin::signed(origin),
+            bounded_label(b"v2")
+        ));
+        assert_ok!(PeaqRBAC::rollback_policy(RuntimeOrigin::signed(origin), 1));
+        System::assert_last_event(Event::<Test>::PolicyRolledBack(origin, 1, 0).into());
+
+        // Test for snapshot quota
+        assert_noop!(
+            PeaqRBAC::snapshot_policy(RuntimeOrigin::signed(origin), bounded_label(b"v3")),
+            Error::<Test>::TooManySnapshots
+        );
+
+        // Removing snapshots releases their deposits
+        assert_ok!(PeaqRBAC::remove_snapshot(RuntimeOrigin::signed(origin), 0));
+        assert_ok!(PeaqRBAC::remove_snapshot(RuntimeOrigin::signed(origin), 1));
+        assert_eq!(Balances::reserved_balance(origin), 0);
+        assert!(PeaqRBAC::snapshots(&origin).is_empty());
+        assert_noop!(
+            PeaqRBAC::remove_snapshot(RuntimeOrigin::signed(origin), 0),
+            Error::<Test>::SnapshotDoesNotExist
+        );
+    });
+}
+
+#[test]
+fn rollback_abort_and_cancel_test() {
+    new_test_ext().execute_with(|| {
+        let origin = account_key("Iredia");
+        let role_id = *b"33676474666576474646673646376637";
+        let user_id = *b"22676474666576474646673646376637";
+        let name = b"Admin";
+
+        System::set_block_number(1);
+        Balances::make_free_balance_be(&origin, 1000);
+        assert_ok!(PeaqRBAC::snapshot_policy(
+            RuntimeOrigin::signed(origin),
+            bounded_label(b"empty")
+        ));
+        let ops = vec![
+            PolicyOp::AddRole(role_id, name.to_vec()),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+
+        // Test for cancelling without a pending rollback
+        assert_noop!(
+            PeaqRBAC::cancel_rollback(RuntimeOrigin::signed(origin)),
+            Error::<Test>::NoRollbackPending
+        );
+
+        assert_ok!(PeaqRBAC::rollback_policy(RuntimeOrigin::signed(origin), 0));
+        assert_ok!(PeaqRBAC::cancel_rollback(RuntimeOrigin::signed(origin)));
+        System::assert_last_event(Event::<Test>::RollbackCancelled(origin, 0, 0).into());
+        assert!(PeaqRBAC::pending_rollback_of(origin).is_none());
+        PeaqRBAC::on_idle(1, Weight::MAX);
+        assert!(PeaqRBAC::has_role(&origin, user_id, role_id));
+
+        // Changes made in the meantime are rolled back as well
+        assert_ok!(PeaqRBAC::rollback_policy(RuntimeOrigin::signed(origin), 0));
+        System::assert_last_event(Event::<Test>::RollbackStarted(origin, 0, 2).into());
+        let mut role_id2 = role_id;
+        role_id2[0] = b'a';
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            role_id2,
+            bounded_name(name)
+        ));
+        PeaqRBAC::on_idle(1, Weight::MAX);
+        PeaqRBAC::on_idle(1, Weight::MAX);
+        System::assert_last_event(Event::<Test>::PolicyRolledBack(origin, 0, 3).into());
+        assert!(PeaqRBAC::get_role(&origin, role_id2).is_err());
+
+        // A rollback which can't be continued is aborted
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            *b"43676474666576474646673646376637",
+            bounded_name(name)
+        ));
+        assert_ok!(PeaqRBAC::rollback_policy(RuntimeOrigin::signed(origin), 0));
+        let ops: Vec<PolicyOp<[u8; 32]>> = (0..16u8)
+            .map(|i| {
+                let mut role_id = role_id;
+                role_id[1] = b'a' + i;
+                PolicyOp::AddRole(role_id, name.to_vec())
+            })
+            .collect();
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+        PeaqRBAC::on_idle(1, Weight::MAX);
+        assert!(PeaqRBAC::pending_rollback_of(origin).is_none());
+        System::assert_last_event(
+            Event::<Test>::RollbackAborted(origin, 0, 0, Error::<Test>::PolicyTooLarge.into())
+                .into(),
+        );
+    });
+}
+
+#[test]
+fn policy_too_large_test() {
+    new_test_ext().execute_with(|| {
+        let origin = account_key("Iredia");
+        let user_id = *b"22676474666576474646673646376637";
+        Balances::make_free_balance_be(&origin, 1000);
+
+        // 9 roles assigned to the user, 18 items beyond MaxSnapshotItems
+        for roles in [0u8..8, 8..9] {
+            let ops: Vec<PolicyOp<[u8; 32]>> = roles
+                .flat_map(|i| {
+                    let mut role_id = *b"33676474666576474646673646376637";
+                    role_id[0] = b'a' + i;
+                    [
+                        PolicyOp::AddRole(role_id, b"Admin".to_vec()),
+                        PolicyOp::AssignRoleToUser(role_id, user_id),
+                    ]
+                })
+                .collect();
+            assert_ok!(PeaqRBAC::apply_policy_ops(
+                RuntimeOrigin::signed(origin),
+                ops.try_into().unwrap()
+            ));
+        }
+
+        // The export gives up once the items exceed the limit
+        assert!(PeaqRBAC::export_policy_within(&origin, 17).is_none());
+        assert_eq!(
+            PeaqRBAC::export_policy_within(&origin, 18),
+            Some(PeaqRBAC::export_policy(&origin))
+        );
+        assert_eq!(PeaqRBAC::export_policy(&origin).item_count(), 18);
+
+        assert_noop!(
+            PeaqRBAC::snapshot_policy(RuntimeOrigin::signed(origin), bounded_label(b"v1")),
+            Error::<Test>::PolicyTooLarge
+        );
+    });
+}
+
+#[test]
+fn policy_draft_test() {
+    new_test_ext().execute_with(|| {
+        let origin = account_key("Iredia");
+        let role_id = *b"35676474666576474646673646376637";
+        let permission_id = *b"58464667364637663721676474666576";
+        let user_id = *b"23676474666576474646673646376637";
+        let name = b"Admin";
+
+        System::set_block_number(1);
+
+        assert_ok!(PeaqRBAC::add_permission(
+            RuntimeOrigin::signed(origin),
+            permission_id,
+            bounded_name(name)
+        ));
+
+        assert_ok!(PeaqRBAC::stage_policy_ops(
+            RuntimeOrigin::signed(origin),
+            vec![
+                PolicyOp::AddRole(role_id, name.to_vec()),
+                PolicyOp::AssignPermissionToRole(permission_id, role_id),
+            ]
+            .try_into()
+            .unwrap()
+        ));
+        System::assert_last_event(Event::<Test>::DraftStaged(origin, 2, 2).into());
+
+        // Staged operations are checked against the draft, not the live policy. Only the
+        // weight of the staged draft is charged.
+        let info = PeaqRBAC::stage_policy_ops(
+            RuntimeOrigin::signed(origin),
+            vec![PolicyOp::AssignRoleToUser(role_id, user_id)]
+                .try_into()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            info.actual_weight,
+            Some(
+                <Test as crate::Config>::WeightInfo::stage_policy_ops(3)
+                    .saturating_add(PeaqRBAC::policy_change_weight(3))
+            )
+        );
+        assert_noop!(
+            PeaqRBAC::stage_policy_ops(
+                RuntimeOrigin::signed(origin),
+                vec![PolicyOp::AddRole(role_id, name.to_vec())]
+                    .try_into()
+                    .unwrap()
+            ),
+            Error::<Test>::EntityAlreadyExist
+        );
+        assert_noop!(
+            PeaqRBAC::stage_policy_ops(
+                RuntimeOrigin::signed(origin),
+                vec![PolicyOp::DisableRole(role_id); 6].try_into().unwrap()
+            ),
+            Error::<Test>::DraftTooLarge
+        );
+        assert_noop!(
+            PeaqRBAC::stage_policy_ops(
+                RuntimeOrigin::signed(origin),
+                vec![PolicyOp::EnablePermission(permission_id)]
+                    .try_into()
+                    .unwrap()
+            ),
+            Error::<Test>::PolicyOpNotAllowed
+        );
+
+        // The live policy is unchanged until the draft is published
+        assert!(PeaqRBAC::role_of(origin).is_empty());
+        let diff = PeaqRBAC::diff_draft(&origin).unwrap();
+        assert_eq!(
+            diff.added.roles,
+            vec![Entity {
+                id: role_id,
+                name: name.to_vec(),
+                enabled: true,
+            }]
+        );
+        assert_eq!(
+            diff.added.permission2roles,
+            vec![Permission2Role {
+                permission: permission_id,
+                role: role_id,
+            }]
+        );
+        assert_eq!(
+            diff.added.role2users,
+            vec![Role2User {
+                role: role_id,
+                user: user_id,
+            }]
+        );
+        assert_eq!(diff.removed.item_count(), 0);
+
+        assert_ok!(PeaqRBAC::publish_draft(RuntimeOrigin::signed(origin)));
+        System::assert_last_event(Event::<Test>::DraftPublished(origin, 3).into());
+        assert_eq!(PeaqRBAC::role_of(origin).len(), 1);
+        assert!(PeaqRBAC::draft_of(origin).is_empty());
+        assert!(PeaqRBAC::diff_draft(&origin).unwrap().is_empty());
+        assert_noop!(
+            PeaqRBAC::publish_draft(RuntimeOrigin::signed(origin)),
+            Error::<Test>::DraftDoesNotExist
+        );
+
+        // A draft which no longer applies to the live policy is kept
+        assert_ok!(PeaqRBAC::stage_policy_ops(
+            RuntimeOrigin::signed(origin),
+            vec![PolicyOp::DisablePermission(permission_id)]
+                .try_into()
+                .unwrap()
+        ));
+        assert_ok!(PeaqRBAC::disable_permission(
+            RuntimeOrigin::signed(origin),
+            permission_id
+        ));
+        assert_noop!(
+            PeaqRBAC::publish_draft(RuntimeOrigin::signed(origin)),
+            Error::<Test>::EntityDisabled
+        );
+        assert_eq!(PeaqRBAC::draft_of(origin).len(), 1);
+
+        assert_ok!(PeaqRBAC::discard_draft(RuntimeOrigin::signed(origin)));
+        System::assert_last_event(Event::<Test>::DraftDiscarded(origin).into());
+        assert_noop!(
+            PeaqRBAC::discard_draft(RuntimeOrigin::signed(origin)),
+            Error::<Test>::DraftDoesNotExist
+        );
+    });
+}
+
+#[test]
+fn batch_queries_test() {
+    new_test_ext().execute_with(|| {
+        let origin = account_key("Iredia");
+        let role_id = *b"36676474666576474646673646376637";
+        let permission_id = *b"59464667364637663721676474666576";
+        let permission_id2 = *b"60464667364637663721676474666576";
+        let user_id = *b"24676474666576474646673646376637";
+        let user_id2 = *b"25676474666576474646673646376637";
+        let name = b"Admin";
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, name.to_vec()),
+            PolicyOp::AddPermission(permission_id, name.to_vec()),
+            PolicyOp::AddPermission(permission_id2, name.to_vec()),
+            PolicyOp::AssignPermissionToRole(permission_id, role_id),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+
+        // Results are keyed by input and a failing item does not affect the others
+        let roles = PeaqRBAC::users_roles(&origin, vec![user_id, user_id2]);
+        assert_eq!(roles.len(), 2);
+        assert_eq!(roles[0].0, user_id);
+        assert_eq!(
+            roles[0].1.as_ref().unwrap(),
+            &vec![Role2User {
+                role: role_id,
+                user: user_id,
+            }]
+        );
+        assert_eq!(roles[1].0, user_id2);
+        assert!(matches!(
+            roles[1].1.as_ref().unwrap_err().typ,
+            RbacErrorType::AssignmentDoesNotExist
+        ));
+
+        let permissions = PeaqRBAC::users_permissions(&origin, vec![user_id2, user_id]);
+        assert_eq!(permissions[0].0, user_id2);
+        assert!(permissions[0].1.as_ref().unwrap().is_empty());
+        assert_eq!(permissions[1].0, user_id);
+        assert_eq!(
+            permissions[1]
+                .1
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>(),
+            vec![permission_id]
+        );
+
+        assert_eq!(
+            PeaqRBAC::check_permissions(
+                &origin,
+                vec![
+                    (user_id, permission_id),
+                    (user_id, permission_id2),
+                    (user_id2, permission_id)
+                ]
+            ),
+            vec![
+                (user_id, permission_id, true),
+                (user_id, permission_id2, false),
+                (user_id2, permission_id, false)
+            ]
+        );
+        assert!(PeaqRBAC::users_roles(&origin, vec![]).is_empty());
+    });
+}
+
+#[test]
+fn name_validation_test() {
+    new_test_ext().execute_with(|| {
+        let acct = "Iredia";
+        let origin = account_key(acct);
+        let role_id = *b"37676474666576474646673646376637";
+        let permission_id = *b"61676474666576474646673646376637";
+        let group_id = *b"17663776474646673646665421676476";
+        let max_name = vec![b'A'; <Test as crate::Config>::MaxNameLength::get() as usize];
+        let long_name = vec![b'A'; max_name.len() + 1];
+
+        // All kinds of entities are checked on creation and update
+        assert!(BoundedVec::<u8, MaxNameLength>::try_from(long_name.clone()).is_err());
+        let ops = vec![PolicyOp::AddGroup(group_id, long_name.clone())];
+        assert_noop!(
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::EntityNameTooLong
+        );
+        assert_noop!(
+            PeaqRBAC::add_group(RuntimeOrigin::signed(origin), group_id, bounded_name(&[])),
+            Error::<Test>::EntityNameEmpty
+        );
+        assert_ok!(PeaqRBAC::add_group(
+            RuntimeOrigin::signed(origin),
+            group_id,
+            bounded_name(&max_name)
+        ));
+        let ops = vec![PolicyOp::UpdateGroup(group_id, long_name.clone())];
+        assert_noop!(
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::EntityNameTooLong
+        );
+        assert_ok!(PeaqRBAC::add_permission(
+            RuntimeOrigin::signed(origin),
+            permission_id,
+            bounded_name(b"READ")
+        ));
+        assert_noop!(
+            PeaqRBAC::update_permission(
+                RuntimeOrigin::signed(origin),
+                permission_id,
+                bounded_name(&[])
+            ),
+            Error::<Test>::EntityNameEmpty
+        );
+
+        // Also when called by other pallets through the traits
+        let err = PeaqRBAC::create_role(&origin, role_id, &long_name).unwrap_err();
+        assert!(matches!(err.typ, RbacErrorType::NameExceedMaxChar));
+        assert!(PeaqRBAC::get_role(&origin, role_id).is_err());
+
+        // Kept names aren't checked again, they may predate stricter rules
+        let key = crate::rbac::generate_key(&origin, &group_id, Tag::Group);
+        KeysLookUpStore::<Test>::mutate(key, |group| group.name = long_name.clone());
+        let ops = vec![PolicyOp::UpdateGroup(group_id, long_name.clone())];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+
+        // Batches are rejected as a whole
+        assert_noop!(
+            PeaqRBAC::apply_policy_ops(
+                RuntimeOrigin::signed(origin),
+                vec![
+                    PolicyOp::AddRole(role_id, b"ADMIN".to_vec()),
+                    PolicyOp::UpdateRole(role_id, vec![]),
+                ]
+                .try_into()
+                .unwrap()
+            ),
+            Error::<Test>::EntityNameEmpty
+        );
+
+        assert!(Utf8Names::check("FLEET ADMIN ✓".as_bytes()).is_ok());
+        assert!(matches!(
+            Utf8Names::check(&[0x46, 0xff]),
+            Err(RbacErrorType::NameInvalidUtf8)
+        ));
+        assert!(matches!(
+            Utf8Names::check(b"FLEET\nADMIN"),
+            Err(RbacErrorType::NameInvalidChar)
+        ));
+        assert!(IdentifierNames::check(b"FLEET_ADMIN-2.0").is_ok());
+        assert!(matches!(
+            IdentifierNames::check(b"FLEET ADMIN"),
+            Err(RbacErrorType::NameInvalidChar)
+        ));
+    });
+}
+
+#[test]
+fn unique_names_test() {
+    new_test_ext().execute_with(|| {
+        UniqueNames::set(true);
+        let origin = account_key("Iredia");
+        let origin2 = account_key("Iredia2");
+        let role_id = *b"38676474666576474646673646376637";
+        let role_id2 = *b"39676474666576474646673646376637";
+        let group_id = *b"18663776474646673646665421676476";
+        let name = b"FLEET_ADMIN";
+
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            role_id,
+            bounded_name(name)
+        ));
+        assert_noop!(
+            PeaqRBAC::add_role(RuntimeOrigin::signed(origin), role_id2, bounded_name(name)),
+            Error::<Test>::EntityNameAlreadyExist
+        );
+        // Names are unique per owner and kind only
+        assert_ok!(PeaqRBAC::add_group(
+            RuntimeOrigin::signed(origin),
+            group_id,
+            bounded_name(name)
+        ));
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin2),
+            role_id2,
+            bounded_name(name)
+        ));
+
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin, name).unwrap().id,
+            role_id
+        );
+        assert_eq!(
+            PeaqRBAC::get_group_by_name(&origin, name).unwrap().id,
+            group_id
+        );
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin2, name).unwrap().id,
+            role_id2
+        );
+        let err = PeaqRBAC::get_permission_by_name(&origin, name).unwrap_err();
+        assert!(matches!(err.typ, RbacErrorType::EntityDoesNotExist));
+
+        // Renaming releases the old name
+        assert_ok!(PeaqRBAC::update_role(
+            RuntimeOrigin::signed(origin),
+            role_id,
+            bounded_name(name)
+        ));
+        assert_ok!(PeaqRBAC::update_role(
+            RuntimeOrigin::signed(origin),
+            role_id,
+            bounded_name(b"FLEET_OPS")
+        ));
+        assert!(PeaqRBAC::get_role_by_name(&origin, name).is_err());
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            role_id2,
+            bounded_name(name)
+        ));
+        assert_noop!(
+            PeaqRBAC::update_role(RuntimeOrigin::signed(origin), role_id, bounded_name(name)),
+            Error::<Test>::EntityNameAlreadyExist
+        );
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin, b"FLEET_OPS")
+                .unwrap()
+                .id,
+            role_id
+        );
+
+        // Without the index names are looked up among the owner's entities
+        UniqueNames::set(false);
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin, name).unwrap().id,
+            role_id2
+        );
+    });
+}
+
+#[test]
+fn backfill_policy_users_migration_test() {
+    new_test_ext().execute_with(|| {
+        let origin = account_key("Iredia");
+        let origin2 = account_key("Iredia2");
+        let role_id = *b"34676474666576474646673646376637";
+        let group_id = *b"17663776474646673646665421676476";
+        let user_id = *b"19676474666576474646673646376637";
+        let user_id2 = *b"18676474666576474646673646376637";
+        let user_id3 = *b"20676474666576474646673646376637";
+        let name = b"Admin";
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, name.to_vec()),
+            PolicyOp::AddGroup(group_id, name.to_vec()),
+            PolicyOp::AssignRoleToUser(role_id, user_id),
+            PolicyOp::AssignUserToGroup(user_id2, group_id),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+        // Another owner reusing the role's id
+        let ops = vec![
+            PolicyOp::AddRole(role_id, name.to_vec()),
+            PolicyOp::AssignRoleToUser(role_id, user_id3),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin2),
+            ops.try_into().unwrap()
+        ));
+        let exported = PeaqRBAC::export_policy(&origin);
+
+        // State of a chain assigning users before the user index was introduced
+        PolicyUsersStore::<Test>::remove(origin);
+        PolicyUsersStore::<Test>::remove(origin2);
+        StorageVersion::new(0).put::<PeaqRBAC>();
+        assert!(PeaqRBAC::export_policy(&origin).role2users.is_empty());
+
+        migrations::v1::BackfillPolicyUsers::<Test>::on_runtime_upgrade();
+
+        assert_eq!(PeaqRBAC::users_of(origin), vec![user_id2, user_id]);
+        assert_eq!(PeaqRBAC::users_of(origin2), vec![user_id3]);
+        assert_eq!(PeaqRBAC::export_policy(&origin), exported);
+        assert_eq!(PeaqRBAC::on_chain_storage_version(), 1);
+
+        // Runs only once
+        PolicyUsersStore::<Test>::remove(origin2);
+        migrations::v1::BackfillPolicyUsers::<Test>::on_runtime_upgrade();
+        assert!(PeaqRBAC::users_of(origin2).is_empty());
+    });
+}
+
+#[test]
+fn name_lookup_prefers_enabled_entities_test() {
+    new_test_ext().execute_with(|| {
+        let origin = account_key("Iredia");
+        let role_id = *b"38676474666576474646673646376637";
+        let role_id2 = *b"39676474666576474646673646376637";
+        let name = b"FLEET_ADMIN";
+
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            role_id,
+            bounded_name(name)
+        ));
+        assert_ok!(PeaqRBAC::disable_role(
+            RuntimeOrigin::signed(origin),
+            role_id
+        ));
+        let err = PeaqRBAC::get_role_by_name(&origin, name).unwrap_err();
+        assert!(matches!(err.typ, RbacErrorType::EntityDisabled));
+
+        // Without the index an enabled entity wins over an earlier disabled one
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            role_id2,
+            bounded_name(name)
+        ));
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin, name).unwrap().id,
+            role_id2
+        );
+
+        // Once both are disabled the first one is reported
+        assert_ok!(PeaqRBAC::disable_role(
+            RuntimeOrigin::signed(origin),
+            role_id2
+        ));
+        let err = PeaqRBAC::get_role_by_name(&origin, name).unwrap_err();
+        assert!(matches!(err.typ, RbacErrorType::EntityDisabled));
+        assert_eq!(err.param, role_id.to_vec());
+    });
+}
+
+#[test]
+fn unique_names_batch_test() {
+    new_test_ext().execute_with(|| {
+        UniqueNames::set(true);
+        let origin = account_key("Iredia");
+        let role_id = *b"38676474666576474646673646376637";
+        let role_id2 = *b"39676474666576474646673646376637";
+        let role_id3 = *b"40676474666576474646673646376637";
+        let user_id = *b"22676474666576474646673646376637";
+        let admin = b"FLEET_ADMIN";
+        let ops_name = b"FLEET_OPS";
+
+        System::set_block_number(1);
+        Balances::make_free_balance_be(&origin, 1000);
+
+        let ops = vec![
+            PolicyOp::AddRole(role_id, admin.to_vec()),
+            PolicyOp::AddRole(role_id2, ops_name.to_vec()),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+
+        // Names are checked against the end state of a batch, so they can be swapped
+        let ops = vec![
+            PolicyOp::UpdateRole(role_id, ops_name.to_vec()),
+            PolicyOp::UpdateRole(role_id2, admin.to_vec()),
+        ];
+        assert_ok!(PeaqRBAC::apply_policy_ops(
+            RuntimeOrigin::signed(origin),
+            ops.try_into().unwrap()
+        ));
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin, ops_name).unwrap().id,
+            role_id
+        );
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin, admin).unwrap().id,
+            role_id2
+        );
+
+        // Names held at the end of a batch still can't be claimed twice
+        let ops = vec![
+            PolicyOp::UpdateRole(role_id, b"FLEET".to_vec()),
+            PolicyOp::UpdateRole(role_id2, b"FLEET".to_vec()),
+        ];
+        assert_noop!(
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::EntityNameAlreadyExist
+        );
+        let ops = vec![PolicyOp::AddRole(role_id3, admin.to_vec())];
+        assert_noop!(
+            PeaqRBAC::apply_policy_ops(RuntimeOrigin::signed(origin), ops.try_into().unwrap()),
+            Error::<Test>::EntityNameAlreadyExist
+        );
+
+        // Disabling releases the name
+        assert_ok!(PeaqRBAC::snapshot_policy(
+            RuntimeOrigin::signed(origin),
+            bounded_label(b"v1")
+        ));
+        assert_ok!(PeaqRBAC::disable_role(
+            RuntimeOrigin::signed(origin),
+            role_id2
+        ));
+        assert!(PeaqRBAC::get_role_by_name(&origin, admin).is_err());
+        assert_ok!(PeaqRBAC::add_role(
+            RuntimeOrigin::signed(origin),
+            role_id3,
+            bounded_name(admin)
+        ));
+        assert_ok!(PeaqRBAC::assign_role_to_user(
+            RuntimeOrigin::signed(origin),
+            role_id3,
+            user_id
+        ));
+
+        // Rollbacks re-index the names of the entities they enable, even if the entity
+        // holding it is only disabled by a later chunk
+        assert_ok!(PeaqRBAC::rollback_policy(RuntimeOrigin::signed(origin), 0));
+        PeaqRBAC::on_idle(1, Weight::MAX);
+        assert_eq!(PeaqRBAC::pending_rollback_of(origin).unwrap().applied, 2);
+        PeaqRBAC::on_idle(1, Weight::MAX);
+        System::assert_last_event(Event::<Test>::PolicyRolledBack(origin, 0, 3).into());
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin, admin).unwrap().id,
+            role_id2
+        );
+
+        // The index can be rebuilt when names become unique on a live chain
+        let _ = NameIndexStore::<Test>::clear(u32::MAX, None);
+        StorageVersion::new(2).put::<PeaqRBAC>();
+        assert!(PeaqRBAC::get_role_by_name(&origin, ops_name).is_err());
+        migrations::v3::BuildNameIndex::<Test>::on_runtime_upgrade();
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin, ops_name).unwrap().id,
+            role_id
+        );
+        assert_eq!(
+            PeaqRBAC::get_role_by_name(&origin, admin).unwrap().id,
+            role_id2
+        );
+        assert_eq!(PeaqRBAC::on_chain_storage_version(), 3);
+
+        // It runs only once
+        let _ = NameIndexStore::<Test>::clear(u32::MAX, None);
+        migrations::v3::BuildNameIndex::<Test>::on_runtime_upgrade();
+        assert!(PeaqRBAC::get_role_by_name(&origin, ops_name).is_err());
+        UniqueNames::set(false);
+    });
+}
+
+#[test]
+fn build_policy_trees_migration_test() {
+    let origin = account_key("Iredia");
+    let config = GenesisConfig::<Test> {
+        policies: vec![(origin, genesis_policy())],
+    };
+
+    new_test_ext_with_genesis(config).execute_with(|| {
+        let policy = PeaqRBAC::export_policy(&origin);
+        let root = PeaqRBAC::policy_commitment_of(origin);
+        assert_eq!(root, commitment::policy_root(&policy));
+
+        // State of a chain committing to sorted leaves only
+        let _ = PolicyTreeStore::<Test>::clear_prefix(origin, u32::MAX, None);
+        PolicyTreeRootStore::<Test>::remove(origin);
+        PolicyCommitmentStore::<Test>::remove(origin);
+        put_storage_value(
+            b"PeaqRBAC",
+            b"PolicyLeavesStore",
+            &origin.encode(),
+            vec![H256::zero()],
+        );
+        StorageVersion::new(1).put::<PeaqRBAC>();
+
+        migrations::v2::BuildPolicyTrees::<Test>::on_runtime_upgrade();
+
+        assert_eq!(PeaqRBAC::policy_commitment_of(origin), root);
+        assert_eq!(
+            PeaqRBAC::policy_tree_root_of(origin).map(|r| r.hash()),
+            Some(root)
+        );
+        assert!(!have_storage_value(
+            b"PeaqRBAC",
+            b"PolicyLeavesStore",
+            &origin.encode()
+        ));
+        assert_eq!(PeaqRBAC::on_chain_storage_version(), 2);
+
+        // Later changes update the built tree
+        assert_ok!(PeaqRBAC::disable_role(
+            RuntimeOrigin::signed(origin),
+            policy.roles[0].id,
+        ));
+        assert_eq!(
+            PeaqRBAC::policy_commitment_of(origin),
+            commitment::policy_root(&PeaqRBAC::export_policy(&origin))
+        );
+    });
+}
@@ -32,5 +32,19 @@ pub trait WeightInfo {
     fn fetch_user_groups() -> Weight;
     fn fetch_user_permissions() -> Weight;
     fn fetch_group_permissions() -> Weight;
+    fn clone_role(p: u32) -> Weight;
+    fn clone_group(r: u32) -> Weight;
+    fn set_permission_calls() -> Weight;
+    fn dispatch_with_permission(l: u32) -> Weight;
+    fn publish_policy_root() -> Weight;
+    fn verify_access_proof() -> Weight;
+    fn snapshot_policy(n: u32) -> Weight;
+    fn rollback_policy(n: u32) -> Weight;
+    fn remove_snapshot() -> Weight;
+    fn cancel_rollback() -> Weight;
+    fn stage_policy_ops(n: u32) -> Weight;
+    fn publish_draft(n: u32) -> Weight;
+    fn discard_draft() -> Weight;
+    fn update_policy_tree(d: u32) -> Weight;
 }
 
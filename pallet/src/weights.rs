@@ -27,6 +27,7 @@
 
 use frame_support::{traits::Get, weights::Weight};
 use core::marker::PhantomData;
+use crate::WeightInfo as _;
 
 /// Weight functions for `peaq_pallet_rbac`.
 pub struct WeightInfo<T>(PhantomData<T>);
@@ -405,4 +406,262 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
 			.saturating_add(Weight::from_parts(0, 3749))
 			.saturating_add(T::DbWeight::get().reads(1))
 	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_role`, `add_role` and one
+	/// `assign_permission_to_role` per permission. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac KeysLookUpStore (r:3 w:1)
+	/// Proof Skipped: PeaqRbac KeysLookUpStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac Permission2RoleStore (r:2 w:1)
+	/// Proof Skipped: PeaqRbac Permission2RoleStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac RoleStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac RoleStore (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `p` is `[1, 128]`.
+	fn clone_role(p: u32, ) -> Weight {
+		Self::fetch_role()
+			.saturating_add(Self::add_role())
+			.saturating_add(Self::assign_permission_to_role().saturating_mul(p.into()))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_group`, `add_group` and one
+	/// `assign_role_to_group` per role. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac KeysLookUpStore (r:3 w:1)
+	/// Proof Skipped: PeaqRbac KeysLookUpStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac Role2GroupStore (r:2 w:1)
+	/// Proof Skipped: PeaqRbac Role2GroupStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac GroupStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac GroupStore (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `r` is `[1, 128]`.
+	fn clone_group(r: u32, ) -> Weight {
+		Self::fetch_group()
+			.saturating_add(Self::add_group())
+			.saturating_add(Self::assign_role_to_group().saturating_mul(r.into()))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_permission` and the write of
+	/// the call filter. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac KeysLookUpStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac KeysLookUpStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PermissionCallFilterStore (r:0 w:1)
+	/// Proof Skipped: PeaqRbac PermissionCallFilterStore (max_values: None, max_size: None, mode: Measured)
+	fn set_permission_calls() -> Weight {
+		Self::fetch_permission()
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_user_roles` and
+	/// `fetch_user_groups`, the accesses of the call filter and the actor, and a `fetch_role`
+	/// and `fetch_role_permissions` per role or group examined. Regenerate with the benchmark
+	/// CLI.
+	/// Storage: PeaqRbac KeysLookUpStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac KeysLookUpStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac Role2UserStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac Role2UserStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac User2GroupStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac User2GroupStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PermissionCallFilterStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac PermissionCallFilterStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac ActorStore (r:1 w:2)
+	/// Proof Skipped: PeaqRbac ActorStore (max_values: Some(1), max_size: None, mode: Measured)
+	/// Per role or group examined:
+	/// Storage: PeaqRbac KeysLookUpStore (r:1 w:0)
+	/// Storage: PeaqRbac Permission2RoleStore or Role2GroupStore (r:1 w:0)
+	/// The range of component `l` is `[1, MaxDispatchLookups]`.
+	fn dispatch_with_permission(l: u32, ) -> Weight {
+		Self::fetch_user_roles()
+			.saturating_add(Self::fetch_user_groups())
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2))
+			.saturating_add(
+				Self::fetch_role()
+					.saturating_add(Self::fetch_role_permissions())
+					.saturating_mul(l.into())
+			)
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_roles`, a call accessing one
+	/// item of the owner, and the write of the root. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac PublishedPolicyRootStore (r:0 w:1)
+	/// Proof Skipped: PeaqRbac PublishedPolicyRootStore (max_values: None, max_size: None, mode: Measured)
+	fn publish_policy_root() -> Weight {
+		Self::fetch_roles()
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_user_permissions`, whose
+	/// lookups the proof stands in for, and the read of the published root. Regenerate with
+	/// the benchmark CLI.
+	/// Storage: PeaqRbac PublishedPolicyRootStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac PublishedPolicyRootStore (max_values: None, max_size: None, mode: Measured)
+	fn verify_access_proof() -> Weight {
+		Self::fetch_user_permissions()
+			.saturating_add(T::DbWeight::get().reads(1))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_roles`, `fetch_groups`,
+	/// `fetch_permissions` and one `fetch_role_permissions` per item read, and the accesses
+	/// of the snapshot and its deposit. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac SnapshotIdsStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac SnapshotIdsStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac RoleStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac RoleStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac GroupStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac GroupStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PolicyUsersStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac PolicyUsersStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PermissionStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac PermissionStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac Permission2RoleStore (r:128 w:0)
+	/// Proof Skipped: PeaqRbac Permission2RoleStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: PeaqRbac NextSnapshotIdStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac NextSnapshotIdStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PolicySnapshotStore (r:0 w:1)
+	/// Proof Skipped: PeaqRbac PolicySnapshotStore (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[1, 128]`.
+	fn snapshot_policy(n: u32, ) -> Weight {
+		Self::fetch_roles()
+			.saturating_add(Self::fetch_groups())
+			.saturating_add(Self::fetch_permissions())
+			.saturating_add(T::DbWeight::get().reads_writes(4, 4))
+			.saturating_add(Self::fetch_role_permissions().saturating_mul(n.into()))
+	}
+	/// Not benchmarked yet, composed like `snapshot_policy` of the reads of the live policy,
+	/// and the accesses of the snapshot and the pending rollback. Regenerate with the
+	/// benchmark CLI.
+	/// Storage: PeaqRbac PendingRollbackStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac PendingRollbackStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PolicySnapshotStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac PolicySnapshotStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac RoleStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac RoleStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac GroupStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac GroupStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PolicyUsersStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac PolicyUsersStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PermissionStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac PermissionStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac Permission2RoleStore (r:128 w:0)
+	/// Proof Skipped: PeaqRbac Permission2RoleStore (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[1, 128]`.
+	fn rollback_policy(n: u32, ) -> Weight {
+		Self::fetch_roles()
+			.saturating_add(Self::fetch_groups())
+			.saturating_add(Self::fetch_permissions())
+			.saturating_add(T::DbWeight::get().reads_writes(3, 1))
+			.saturating_add(Self::fetch_role_permissions().saturating_mul(n.into()))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_roles`, a call accessing one
+	/// item of the owner, and the further accesses. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac PolicySnapshotStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac PolicySnapshotStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: PeaqRbac SnapshotIdsStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac SnapshotIdsStore (max_values: None, max_size: None, mode: Measured)
+	fn remove_snapshot() -> Weight {
+		Self::fetch_roles()
+			.saturating_add(T::DbWeight::get().reads_writes(2, 3))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_roles`, a call accessing one
+	/// item of the owner, and the writes. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac PendingRollbackStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac PendingRollbackStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac RollbackNameClaimsStore (r:0 w:1)
+	/// Proof Skipped: PeaqRbac RollbackNameClaimsStore (max_values: None, max_size: None, mode: Measured)
+	fn cancel_rollback() -> Weight {
+		Self::fetch_roles()
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_roles`, a call accessing one
+	/// item of the owner, the write of the draft and the heaviest benchmarked extrinsic of a
+	/// single operation per operation checked. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac PolicyDraftStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac PolicyDraftStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac KeysLookUpStore (r:128 w:128)
+	/// Proof Skipped: PeaqRbac KeysLookUpStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac RoleStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac RoleStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac AuditLogCountStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac AuditLogCountStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: PeaqRbac GroupStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac GroupStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PolicyUsersStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac PolicyUsersStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PermissionStore (r:1 w:0)
+	/// Proof Skipped: PeaqRbac PermissionStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac Permission2RoleStore (r:128 w:0)
+	/// Proof Skipped: PeaqRbac Permission2RoleStore (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[1, 128]`.
+	fn stage_policy_ops(n: u32, ) -> Weight {
+		Self::fetch_roles()
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(Self::policy_op().saturating_mul(n.into()))
+	}
+	/// Not benchmarked yet, composed like `stage_policy_ops` with the draft removed instead
+	/// of written. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac PolicyDraftStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac PolicyDraftStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac KeysLookUpStore (r:128 w:128)
+	/// Proof Skipped: PeaqRbac KeysLookUpStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac RoleStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac RoleStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac AuditLogCountStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac AuditLogCountStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: PeaqRbac AuditLogStore (r:0 w:128)
+	/// Proof Skipped: PeaqRbac AuditLogStore (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `n` is `[1, 128]`.
+	fn publish_draft(n: u32, ) -> Weight {
+		Self::fetch_roles()
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(Self::policy_op().saturating_mul(n.into()))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_roles`, a call accessing one
+	/// item of the owner, and the removal of the draft. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac PolicyDraftStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac PolicyDraftStore (max_values: None, max_size: None, mode: Measured)
+	fn discard_draft() -> Weight {
+		Self::fetch_roles()
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Not benchmarked yet, composed of the benchmarked `fetch_role` for the read of the root
+	/// and of storage accesses for the nodes, each read with the proof size of the item read
+	/// by `fetch_role`. Regenerate with the benchmark CLI.
+	/// Storage: PeaqRbac PolicyTreeRootStore (r:1 w:1)
+	/// Proof Skipped: PeaqRbac PolicyTreeRootStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PolicyCommitmentStore (r:0 w:1)
+	/// Proof Skipped: PeaqRbac PolicyCommitmentStore (max_values: None, max_size: None, mode: Measured)
+	/// Storage: PeaqRbac PolicyTreeStore (r:2d w:d+2)
+	/// Proof Skipped: PeaqRbac PolicyTreeStore (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `d` is `[1, MaxPolicyTreeDepth]`.
+	fn update_policy_tree(d: u32, ) -> Weight {
+		let node_reads = 2_u64.saturating_mul(d.into());
+		Self::fetch_role()
+			.saturating_add(T::DbWeight::get().reads(node_reads))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_add(d.into())))
+			.saturating_add(Weight::from_parts(0, Self::fetch_role().proof_size()).saturating_mul(node_reads))
+	}
+}
+
+impl<T: frame_system::Config> WeightInfo<T> {
+	/// Heaviest benchmarked extrinsic behind a single policy operation
+	fn policy_op() -> Weight {
+		[
+			Self::add_role(),
+			Self::update_role(),
+			Self::disable_role(),
+			Self::add_permission(),
+			Self::update_permission(),
+			Self::disable_permission(),
+			Self::add_group(),
+			Self::update_group(),
+			Self::disable_group(),
+			Self::assign_role_to_user(),
+			Self::unassign_role_to_user(),
+			Self::assign_role_to_group(),
+			Self::unassign_role_to_group(),
+			Self::assign_user_to_group(),
+			Self::unassign_user_to_group(),
+			Self::assign_permission_to_role(),
+			Self::unassign_permission_to_role(),
+		]
+		.into_iter()
+		.fold(Weight::zero(), |heaviest, weight| heaviest.max(weight))
+	}
 }
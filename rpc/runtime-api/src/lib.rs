@@ -6,13 +6,23 @@
 
 use codec::Codec;
 use peaq_pallet_rbac::{
+    commitment::AccessProof,
     error::RbacError,
     rbac::Result as RbacResult,
-    structs::{Entity, Permission2Role, Role2Group, Role2User, User2Group},
+    structs::{
+        AuditRecord, Entity, GenesisPolicy, Permission2Role, PolicyChange, PolicyDiff, Role2Group,
+        Role2User, SnapshotInfo, User2Group,
+    },
 };
+use sp_core::H256;
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+    /// Queries of the RBAC pallet. Version 1 holds the `fetch_*` queries of single
+    /// entities and assignments, version 2 added all others but the lookups by name, which
+    /// were added in version 3, and version 4 added the changes made in a block, see
+    /// `peaq_pallet_rbac_rpc::EXTENDED_API_VERSION`. Runtimes provide them by implementing
+    /// the API with the `#[api_version(_)]` of the queries they implement.
     pub trait PeaqRBACRuntimeApi<AccountId, EntityId>
     where
         AccountId: Codec,
@@ -41,5 +51,50 @@ sp_api::decl_runtime_apis! {
         fn fetch_user_permissions(owner: AccountId, user_id: EntityId) -> RbacResult<Vec<Entity<EntityId>>, RbacError>;
 
         fn fetch_group_permissions(owner: AccountId, group_id: EntityId) -> RbacResult<Vec<Entity<EntityId>>, RbacError>;
+
+        #[api_version(2)]
+        fn has_permission(owner: AccountId, user_id: EntityId, permission_id: EntityId) -> bool;
+
+        #[api_version(2)]
+        fn has_role(owner: AccountId, user_id: EntityId, role_id: EntityId) -> bool;
+
+        #[api_version(2)]
+        fn fetch_policy_commitment(owner: AccountId) -> H256;
+
+        #[api_version(2)]
+        fn verify_access_proof(owner: AccountId, user_id: EntityId, permission_id: EntityId, proof: AccessProof<EntityId>) -> bool;
+
+        #[api_version(2)]
+        fn fetch_audit_log(owner: AccountId, from: u64, limit: u32) -> Vec<AuditRecord<AccountId, EntityId>>;
+
+        #[api_version(2)]
+        fn fetch_snapshots(owner: AccountId) -> Vec<SnapshotInfo>;
+
+        #[api_version(2)]
+        fn diff_draft(owner: AccountId) -> RbacResult<PolicyDiff<EntityId>, RbacError>;
+
+        #[api_version(2)]
+        fn export_policy(owner: AccountId) -> GenesisPolicy<EntityId>;
+
+        #[api_version(2)]
+        fn fetch_users_roles(owner: AccountId, user_ids: Vec<EntityId>) -> Vec<(EntityId, RbacResult<Vec<Role2User<EntityId>>, RbacError>)>;
+
+        #[api_version(2)]
+        fn fetch_users_permissions(owner: AccountId, user_ids: Vec<EntityId>) -> Vec<(EntityId, RbacResult<Vec<Entity<EntityId>>, RbacError>)>;
+
+        #[api_version(2)]
+        fn check_permissions(owner: AccountId, queries: Vec<(EntityId, EntityId)>) -> Vec<(EntityId, EntityId, bool)>;
+
+        #[api_version(3)]
+        fn fetch_role_by_name(owner: AccountId, name: Vec<u8>) -> RbacResult<Entity<EntityId>, RbacError>;
+
+        #[api_version(3)]
+        fn fetch_permission_by_name(owner: AccountId, name: Vec<u8>) -> RbacResult<Entity<EntityId>, RbacError>;
+
+        #[api_version(3)]
+        fn fetch_group_by_name(owner: AccountId, name: Vec<u8>) -> RbacResult<Entity<EntityId>, RbacError>;
+
+        #[api_version(4)]
+        fn fetch_policy_changes(owner: AccountId) -> Vec<PolicyChange<AccountId, EntityId>>;
     }
 }
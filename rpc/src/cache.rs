@@ -0,0 +1,96 @@
+//! In-process cache of runtime API answers.
+//!
+//! Answers are keyed by block hash, method and parameters. The state of a block never
+//! changes, so entries can't become stale and are only evicted to bound the memory used:
+//! the least recently used ones once the cache is full, and those older than the TTL.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+
+/// Limits of a [`RpcCache`]
+#[derive(Clone, Copy, Debug)]
+pub struct CacheConfig {
+    /// Maximum number of cached answers
+    pub max_entries: NonZeroUsize,
+    /// Time after which an answer is evicted, `None` keeps answers until evicted by size
+    pub ttl: Option<Duration>,
+}
+
+/// Counters of a [`RpcCache`]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Number of answers currently cached
+    pub entries: u64,
+}
+
+/// Cache of SCALE encoded runtime API answers, shared by the RPC handlers and e.g. the
+/// node's metrics
+pub struct RpcCache {
+    entries: Mutex<LruCache<Vec<u8>, (Instant, Vec<u8>)>>,
+    ttl: Option<Duration>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl RpcCache {
+    pub fn new(config: CacheConfig) -> Self {
+        RpcCache {
+            entries: Mutex::new(LruCache::new(config.max_entries)),
+            ttl: config.ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Current counters
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.lock().len() as u64,
+        }
+    }
+
+    /// Cached answer of the key, counting the lookup as hit or miss
+    pub(crate) fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let mut entries = self.lock();
+        let expired = match entries.get(key) {
+            Some((inserted, value)) => match self.ttl {
+                Some(ttl) if inserted.elapsed() > ttl => true,
+                _ => {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return Some(value.clone());
+                }
+            },
+            None => false,
+        };
+        if expired {
+            entries.pop(key);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    pub(crate) fn insert(&self, key: Vec<u8>, value: Vec<u8>) {
+        self.lock().put(key, (Instant::now(), value));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<Vec<u8>, (Instant, Vec<u8>)>> {
+        // The cache holds no invariants a panicking holder could break
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
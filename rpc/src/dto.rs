@@ -0,0 +1,384 @@
+//! RPC-side representations of ids, entities and assignments.
+//!
+//! Responses serialise them like the on-chain types by default, or human-readable with
+//! names as UTF-8 strings (as `{"hex": "0x..."}` if not valid UTF-8) and ids as 0x-hex of
+//! their SCALE encoding, see [`crate::PeaqRBAC::with_readable_json`]. Requests accept ids
+//! in their JSON form as well as 0x-hex or text strings.
+
+use codec::{Decode, Encode};
+use jsonrpsee::{
+    core::{Error as JsonRpseeError, RpcResult},
+    types::error::{CallError, ErrorObject},
+};
+use serde::{Deserialize, Serialize};
+use sp_core::bytes::{from_hex, to_hex};
+use sp_std::vec::Vec;
+
+use peaq_pallet_rbac::structs::{
+    AuditRecord, Entity, GenesisPolicy, Permission2Role, PolicyChange, PolicyOp, Role2Group,
+    Role2User, User2Group,
+};
+
+use crate::Error;
+
+/// Entity id passed to a query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum IdParam<EntityId> {
+    /// JSON form of the id, e.g. an array of bytes
+    Id(EntityId),
+    /// 0x-hex of the SCALE encoded id, or text whose bytes are the encoded id
+    Text(String),
+}
+
+impl<EntityId: Decode> IdParam<EntityId> {
+    /// The id passed, decoding it from a string if needed
+    pub fn resolve(self) -> RpcResult<EntityId> {
+        let text = match self {
+            IdParam::Id(id) => return Ok(id),
+            IdParam::Text(text) => text,
+        };
+
+        let bytes = match text.strip_prefix("0x") {
+            Some(_) => from_hex(&text).map_err(|_| invalid_id(&text))?,
+            None => text.as_bytes().to_vec(),
+        };
+        let mut input = &bytes[..];
+        match EntityId::decode(&mut input) {
+            Ok(id) if input.is_empty() => Ok(id),
+            _ => Err(invalid_id(&text)),
+        }
+    }
+}
+
+/// Resolves all ids, failing on the first invalid one
+pub fn resolve_all<EntityId: Decode>(ids: Vec<IdParam<EntityId>>) -> RpcResult<Vec<EntityId>> {
+    ids.into_iter().map(IdParam::resolve).collect()
+}
+
+fn invalid_id(text: &str) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::InvalidId.into(),
+        "Invalid entity id.",
+        Some(text.to_string()),
+    )))
+}
+
+/// Entity name passed to a query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum NameParam {
+    /// Bytes of the name, as stored on chain
+    Raw(Vec<u8>),
+    /// UTF-8 name
+    Text(String),
+}
+
+impl NameParam {
+    /// The name passed, as stored on chain
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            NameParam::Raw(bytes) => bytes,
+            NameParam::Text(text) => text.into_bytes(),
+        }
+    }
+}
+
+/// Entity id in a response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum IdDto<EntityId> {
+    Raw(EntityId),
+    /// 0x-hex of the SCALE encoded id
+    Hex(String),
+}
+
+/// Entity name in a response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum NameDto {
+    Raw(Vec<u8>),
+    /// UTF-8 name
+    Text(String),
+    /// 0x-hex of a name which is not valid UTF-8, tagged to tell it from text
+    Hex {
+        hex: String,
+    },
+}
+
+/// Conversion of on-chain types into their RPC representation
+pub trait IntoDto {
+    type Dto: Serialize;
+
+    fn into_dto(self, readable: bool) -> Self::Dto;
+}
+
+impl<T: IntoDto> IntoDto for Vec<T> {
+    type Dto = Vec<T::Dto>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        self.into_iter()
+            .map(|item| item.into_dto(readable))
+            .collect()
+    }
+}
+
+pub(crate) fn id_dto<EntityId: Encode>(id: EntityId, readable: bool) -> IdDto<EntityId> {
+    if readable {
+        IdDto::Hex(to_hex(&id.encode(), false))
+    } else {
+        IdDto::Raw(id)
+    }
+}
+
+fn name_dto(name: Vec<u8>, readable: bool) -> NameDto {
+    if !readable {
+        return NameDto::Raw(name);
+    }
+    match String::from_utf8(name) {
+        Ok(text) => NameDto::Text(text),
+        Err(err) => NameDto::Hex {
+            hex: to_hex(err.as_bytes(), false),
+        },
+    }
+}
+
+/// Role, permission or group in a response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EntityDto<EntityId> {
+    pub id: IdDto<EntityId>,
+    pub name: NameDto,
+    pub enabled: bool,
+}
+
+impl<EntityId: Encode + Serialize> IntoDto for Entity<EntityId> {
+    type Dto = EntityDto<EntityId>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        EntityDto {
+            id: id_dto(self.id, readable),
+            name: name_dto(self.name, readable),
+            enabled: self.enabled,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Role2UserDto<EntityId> {
+    pub role: IdDto<EntityId>,
+    pub user: IdDto<EntityId>,
+}
+
+impl<EntityId: Encode + Serialize> IntoDto for Role2User<EntityId> {
+    type Dto = Role2UserDto<EntityId>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        Role2UserDto {
+            role: id_dto(self.role, readable),
+            user: id_dto(self.user, readable),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Role2GroupDto<EntityId> {
+    pub role: IdDto<EntityId>,
+    pub group: IdDto<EntityId>,
+}
+
+impl<EntityId: Encode + Serialize> IntoDto for Role2Group<EntityId> {
+    type Dto = Role2GroupDto<EntityId>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        Role2GroupDto {
+            role: id_dto(self.role, readable),
+            group: id_dto(self.group, readable),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct User2GroupDto<EntityId> {
+    pub user: IdDto<EntityId>,
+    pub group: IdDto<EntityId>,
+}
+
+impl<EntityId: Encode + Serialize> IntoDto for User2Group<EntityId> {
+    type Dto = User2GroupDto<EntityId>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        User2GroupDto {
+            user: id_dto(self.user, readable),
+            group: id_dto(self.group, readable),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Permission2RoleDto<EntityId> {
+    pub permission: IdDto<EntityId>,
+    pub role: IdDto<EntityId>,
+}
+
+impl<EntityId: Encode + Serialize> IntoDto for Permission2Role<EntityId> {
+    type Dto = Permission2RoleDto<EntityId>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        Permission2RoleDto {
+            permission: id_dto(self.permission, readable),
+            role: id_dto(self.role, readable),
+        }
+    }
+}
+
+/// Entities and assignments in a response, shaped like `GenesisPolicy`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GenesisPolicyDto<EntityId> {
+    pub roles: Vec<EntityDto<EntityId>>,
+    pub permissions: Vec<EntityDto<EntityId>>,
+    pub groups: Vec<EntityDto<EntityId>>,
+    pub role2users: Vec<Role2UserDto<EntityId>>,
+    pub role2groups: Vec<Role2GroupDto<EntityId>>,
+    pub user2groups: Vec<User2GroupDto<EntityId>>,
+    pub permission2roles: Vec<Permission2RoleDto<EntityId>>,
+}
+
+impl<EntityId: Encode + Serialize> IntoDto for GenesisPolicy<EntityId> {
+    type Dto = GenesisPolicyDto<EntityId>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        GenesisPolicyDto {
+            roles: self.roles.into_dto(readable),
+            permissions: self.permissions.into_dto(readable),
+            groups: self.groups.into_dto(readable),
+            role2users: self.role2users.into_dto(readable),
+            role2groups: self.role2groups.into_dto(readable),
+            user2groups: self.user2groups.into_dto(readable),
+            permission2roles: self.permission2roles.into_dto(readable),
+        }
+    }
+}
+
+/// Policy operation in a response, shaped like `PolicyOp`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PolicyOpDto<EntityId> {
+    AddRole(IdDto<EntityId>, NameDto),
+    UpdateRole(IdDto<EntityId>, NameDto),
+    DisableRole(IdDto<EntityId>),
+    AddPermission(IdDto<EntityId>, NameDto),
+    UpdatePermission(IdDto<EntityId>, NameDto),
+    DisablePermission(IdDto<EntityId>),
+    AddGroup(IdDto<EntityId>, NameDto),
+    UpdateGroup(IdDto<EntityId>, NameDto),
+    DisableGroup(IdDto<EntityId>),
+    AssignRoleToUser(IdDto<EntityId>, IdDto<EntityId>),
+    UnassignRoleToUser(IdDto<EntityId>, IdDto<EntityId>),
+    AssignRoleToGroup(IdDto<EntityId>, IdDto<EntityId>),
+    UnassignRoleToGroup(IdDto<EntityId>, IdDto<EntityId>),
+    AssignUserToGroup(IdDto<EntityId>, IdDto<EntityId>),
+    UnassignUserToGroup(IdDto<EntityId>, IdDto<EntityId>),
+    AssignPermissionToRole(IdDto<EntityId>, IdDto<EntityId>),
+    UnassignPermissionToRole(IdDto<EntityId>, IdDto<EntityId>),
+    EnableRole(IdDto<EntityId>),
+    EnablePermission(IdDto<EntityId>),
+    EnableGroup(IdDto<EntityId>),
+}
+
+impl<EntityId: Encode + Serialize> IntoDto for PolicyOp<EntityId> {
+    type Dto = PolicyOpDto<EntityId>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        let id = |id| id_dto(id, readable);
+        let name = |name| name_dto(name, readable);
+        match self {
+            PolicyOp::AddRole(role, n) => PolicyOpDto::AddRole(id(role), name(n)),
+            PolicyOp::UpdateRole(role, n) => PolicyOpDto::UpdateRole(id(role), name(n)),
+            PolicyOp::DisableRole(role) => PolicyOpDto::DisableRole(id(role)),
+            PolicyOp::AddPermission(permission, n) => {
+                PolicyOpDto::AddPermission(id(permission), name(n))
+            }
+            PolicyOp::UpdatePermission(permission, n) => {
+                PolicyOpDto::UpdatePermission(id(permission), name(n))
+            }
+            PolicyOp::DisablePermission(permission) => {
+                PolicyOpDto::DisablePermission(id(permission))
+            }
+            PolicyOp::AddGroup(group, n) => PolicyOpDto::AddGroup(id(group), name(n)),
+            PolicyOp::UpdateGroup(group, n) => PolicyOpDto::UpdateGroup(id(group), name(n)),
+            PolicyOp::DisableGroup(group) => PolicyOpDto::DisableGroup(id(group)),
+            PolicyOp::AssignRoleToUser(role, user) => {
+                PolicyOpDto::AssignRoleToUser(id(role), id(user))
+            }
+            PolicyOp::UnassignRoleToUser(role, user) => {
+                PolicyOpDto::UnassignRoleToUser(id(role), id(user))
+            }
+            PolicyOp::AssignRoleToGroup(role, group) => {
+                PolicyOpDto::AssignRoleToGroup(id(role), id(group))
+            }
+            PolicyOp::UnassignRoleToGroup(role, group) => {
+                PolicyOpDto::UnassignRoleToGroup(id(role), id(group))
+            }
+            PolicyOp::AssignUserToGroup(user, group) => {
+                PolicyOpDto::AssignUserToGroup(id(user), id(group))
+            }
+            PolicyOp::UnassignUserToGroup(user, group) => {
+                PolicyOpDto::UnassignUserToGroup(id(user), id(group))
+            }
+            PolicyOp::AssignPermissionToRole(permission, role) => {
+                PolicyOpDto::AssignPermissionToRole(id(permission), id(role))
+            }
+            PolicyOp::UnassignPermissionToRole(permission, role) => {
+                PolicyOpDto::UnassignPermissionToRole(id(permission), id(role))
+            }
+            PolicyOp::EnableRole(role) => PolicyOpDto::EnableRole(id(role)),
+            PolicyOp::EnablePermission(permission) => PolicyOpDto::EnablePermission(id(permission)),
+            PolicyOp::EnableGroup(group) => PolicyOpDto::EnableGroup(id(group)),
+        }
+    }
+}
+
+/// Audit log record in a response, shaped like `AuditRecord`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditRecordDto<AccountId, EntityId> {
+    pub seq: u64,
+    pub op: PolicyOpDto<EntityId>,
+    pub who: AccountId,
+    pub block_number: u64,
+    pub timestamp: u64,
+}
+
+impl<AccountId: Serialize, EntityId: Encode + Serialize> IntoDto
+    for AuditRecord<AccountId, EntityId>
+{
+    type Dto = AuditRecordDto<AccountId, EntityId>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        AuditRecordDto {
+            seq: self.seq,
+            op: self.op.into_dto(readable),
+            who: self.who,
+            block_number: self.block_number,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// Policy change in a response, shaped like `PolicyChange`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PolicyChangeDto<AccountId, EntityId> {
+    pub op: PolicyOpDto<EntityId>,
+    pub who: AccountId,
+}
+
+impl<AccountId: Serialize, EntityId: Encode + Serialize> IntoDto
+    for PolicyChange<AccountId, EntityId>
+{
+    type Dto = PolicyChangeDto<AccountId, EntityId>;
+
+    fn into_dto(self, readable: bool) -> Self::Dto {
+        PolicyChangeDto {
+            op: self.op.into_dto(readable),
+            who: self.who,
+        }
+    }
+}
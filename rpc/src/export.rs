@@ -0,0 +1,232 @@
+//! Self-contained documents of an owner's policy and of its changes, as served by
+//! `peaqrbac_exportPolicy`, `peaqrbac_diffPolicy` and `peaqrbac_diffDraft`. Ids and assignments follow the
+//! server's JSON mode, see [`crate::dto`].
+
+use codec::Encode;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    vec::Vec,
+};
+
+use peaq_pallet_rbac::structs::{Entity, GenesisPolicy, PolicyDiff};
+
+use crate::dto::{
+    id_dto, IdDto, IntoDto, Permission2RoleDto, Role2GroupDto, Role2UserDto, User2GroupDto,
+};
+
+/// Version of the [`PolicyExport`] schema, increased on incompatible changes
+pub const POLICY_EXPORT_VERSION: u32 = 1;
+
+/// Role, permission or group of an exported policy
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedEntity<EntityId> {
+    pub id: IdDto<EntityId>,
+    /// Name as text, `None` if it is not valid UTF-8
+    pub name: Option<String>,
+    /// Name as stored on chain
+    pub raw_name: Bytes,
+    pub enabled: bool,
+}
+
+impl<EntityId: Encode> ExportedEntity<EntityId> {
+    fn new(entity: Entity<EntityId>, readable: bool) -> Self {
+        ExportedEntity {
+            id: id_dto(entity.id, readable),
+            name: String::from_utf8(entity.name.clone()).ok(),
+            raw_name: Bytes(entity.name),
+            enabled: entity.enabled,
+        }
+    }
+}
+
+/// All entities and assignments of an owner at a block
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyExport<BlockHash, AccountId, EntityId> {
+    /// Schema version, see [`POLICY_EXPORT_VERSION`]
+    pub version: u32,
+    pub owner: AccountId,
+    /// Block the policy was exported at
+    pub at: BlockHash,
+    pub roles: Vec<ExportedEntity<EntityId>>,
+    pub permissions: Vec<ExportedEntity<EntityId>>,
+    pub groups: Vec<ExportedEntity<EntityId>>,
+    /// Users referenced by any assignment, sorted by id
+    pub users: Vec<IdDto<EntityId>>,
+    pub role2users: Vec<Role2UserDto<EntityId>>,
+    pub role2groups: Vec<Role2GroupDto<EntityId>>,
+    pub user2groups: Vec<User2GroupDto<EntityId>>,
+    pub permission2roles: Vec<Permission2RoleDto<EntityId>>,
+}
+
+impl<BlockHash, AccountId, EntityId> PolicyExport<BlockHash, AccountId, EntityId>
+where
+    EntityId: Ord + Clone + Encode + Serialize,
+{
+    /// Document of the policy exported from the runtime
+    pub fn new(
+        owner: AccountId,
+        at: BlockHash,
+        policy: GenesisPolicy<EntityId>,
+        readable: bool,
+    ) -> Self {
+        let entities = |entities: Vec<Entity<EntityId>>| -> Vec<ExportedEntity<EntityId>> {
+            entities
+                .into_iter()
+                .map(|entity| ExportedEntity::new(entity, readable))
+                .collect()
+        };
+        let users: BTreeSet<EntityId> = policy
+            .role2users
+            .iter()
+            .map(|r2u| r2u.user.clone())
+            .chain(policy.user2groups.iter().map(|u2g| u2g.user.clone()))
+            .collect();
+
+        PolicyExport {
+            version: POLICY_EXPORT_VERSION,
+            owner,
+            at,
+            roles: entities(policy.roles),
+            permissions: entities(policy.permissions),
+            groups: entities(policy.groups),
+            users: users
+                .into_iter()
+                .map(|user| id_dto(user, readable))
+                .collect(),
+            role2users: policy.role2users.into_dto(readable),
+            role2groups: policy.role2groups.into_dto(readable),
+            user2groups: policy.user2groups.into_dto(readable),
+            permission2roles: policy.permission2roles.into_dto(readable),
+        }
+    }
+}
+
+/// Entity whose name or enabled flag changed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModifiedEntity<EntityId> {
+    pub from: ExportedEntity<EntityId>,
+    pub to: ExportedEntity<EntityId>,
+}
+
+/// Changes of one kind of entity
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityChanges<EntityId> {
+    pub added: Vec<ExportedEntity<EntityId>>,
+    pub removed: Vec<ExportedEntity<EntityId>>,
+    pub modified: Vec<ModifiedEntity<EntityId>>,
+}
+
+impl<EntityId: Ord + Clone + Encode> EntityChanges<EntityId> {
+    /// Pairs up entities found in both lists of a diff by their id
+    fn new(added: Vec<Entity<EntityId>>, removed: Vec<Entity<EntityId>>, readable: bool) -> Self {
+        let mut removed: BTreeMap<EntityId, Entity<EntityId>> =
+            removed.into_iter().map(|e| (e.id.clone(), e)).collect();
+        let mut changes = EntityChanges {
+            added: Vec::new(),
+            removed: Vec::new(),
+            modified: Vec::new(),
+        };
+
+        for entity in added.into_iter() {
+            match removed.remove(&entity.id) {
+                Some(old) => changes.modified.push(ModifiedEntity {
+                    from: ExportedEntity::new(old, readable),
+                    to: ExportedEntity::new(entity, readable),
+                }),
+                None => changes.added.push(ExportedEntity::new(entity, readable)),
+            }
+        }
+        changes.removed = removed
+            .into_values()
+            .map(|entity| ExportedEntity::new(entity, readable))
+            .collect();
+        changes
+    }
+}
+
+/// Changes of one assignment relation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationChanges<T> {
+    pub added: Vec<T>,
+    pub removed: Vec<T>,
+}
+
+/// Changes of an owner's policy between two blocks
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyChanges<BlockHash, AccountId, EntityId> {
+    /// Schema version, see [`POLICY_EXPORT_VERSION`]
+    pub version: u32,
+    pub owner: AccountId,
+    pub from: BlockHash,
+    pub to: BlockHash,
+    pub roles: EntityChanges<EntityId>,
+    pub permissions: EntityChanges<EntityId>,
+    pub groups: EntityChanges<EntityId>,
+    pub role2users: RelationChanges<Role2UserDto<EntityId>>,
+    pub role2groups: RelationChanges<Role2GroupDto<EntityId>>,
+    pub user2groups: RelationChanges<User2GroupDto<EntityId>>,
+    pub permission2roles: RelationChanges<Permission2RoleDto<EntityId>>,
+}
+
+impl<BlockHash, AccountId, EntityId> PolicyChanges<BlockHash, AccountId, EntityId>
+where
+    EntityId: Ord + Clone + Encode + Serialize,
+{
+    /// Document of the changes between the policies exported at both blocks
+    pub fn new(
+        owner: AccountId,
+        from: BlockHash,
+        to: BlockHash,
+        from_policy: &GenesisPolicy<EntityId>,
+        to_policy: &GenesisPolicy<EntityId>,
+        readable: bool,
+    ) -> Self {
+        Self::from_diff(owner, from, to, from_policy.diff(to_policy), readable)
+    }
+
+    /// Document of the changes of a diff computed by the runtime, such as the one of an
+    /// owner's policy draft
+    pub fn from_diff(
+        owner: AccountId,
+        from: BlockHash,
+        to: BlockHash,
+        diff: PolicyDiff<EntityId>,
+        readable: bool,
+    ) -> Self {
+        let PolicyDiff { added, removed } = diff;
+
+        PolicyChanges {
+            version: POLICY_EXPORT_VERSION,
+            owner,
+            from,
+            to,
+            roles: EntityChanges::new(added.roles, removed.roles, readable),
+            permissions: EntityChanges::new(added.permissions, removed.permissions, readable),
+            groups: EntityChanges::new(added.groups, removed.groups, readable),
+            role2users: RelationChanges {
+                added: added.role2users.into_dto(readable),
+                removed: removed.role2users.into_dto(readable),
+            },
+            role2groups: RelationChanges {
+                added: added.role2groups.into_dto(readable),
+                removed: removed.role2groups.into_dto(readable),
+            },
+            user2groups: RelationChanges {
+                added: added.user2groups.into_dto(readable),
+                removed: removed.user2groups.into_dto(readable),
+            },
+            permission2roles: RelationChanges {
+                added: added.permission2roles.into_dto(readable),
+                removed: removed.permission2roles.into_dto(readable),
+            },
+        }
+    }
+}
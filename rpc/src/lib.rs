@@ -1,26 +1,180 @@
 //! RPC of Peaq-pallet-RBAC.
-use codec::Codec;
+use codec::{Codec, DecodeAll, Encode};
+use futures::{
+    future,
+    stream::{self, BoxStream},
+    FutureExt, StreamExt,
+};
 use jsonrpsee::{
     core::{async_trait, Error as JsonRpseeError, RpcResult},
     proc_macros::rpc,
-    types::error::{CallError, ErrorObject},
+    types::{
+        error::{CallError, ErrorObject},
+        SubscriptionResult,
+    },
+    SubscriptionSink,
 };
-use sp_api::{ApiError, ProvideRuntimeApi};
-use sp_blockchain::HeaderBackend;
+use prometheus_endpoint::{PrometheusError, Registry};
+use sc_client_api::{BlockchainEvents, ProofProvider};
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiError, ApiExt, ProvideRuntimeApi};
+use sp_blockchain::{HeaderBackend, HeaderMetadata};
+use sp_core::{bytes::to_hex, traits::SpawnNamed, Bytes, H256};
 use sp_runtime::traits::Block as BlockT;
 use sp_std::vec::Vec;
-use std::{convert::From, sync::Arc};
+use std::{convert::From, mem, sync::Arc};
 
 use peaq_pallet_rbac::{
-    error::RbacError,
-    rbac::Result as RbacResult,
-    structs::{Entity, Permission2Role, Role2Group, Role2User, User2Group},
+    commitment::AccessProof,
+    error::{RbacError, RbacErrorType},
+    proof,
+    rbac::{Result as RbacResult, Tag},
+    structs::{Entity, SnapshotInfo},
 };
 pub use peaq_pallet_rbac_runtime_api::PeaqRBACRuntimeApi;
 
-pub type Result<T> = RpcResult<RbacResult<T, RbacError>>;
+pub mod cache;
+pub mod dto;
+pub mod export;
+pub mod metrics;
+use cache::RpcCache;
+use dto::{
+    id_dto, resolve_all, AuditRecordDto, EntityDto, IdDto, IdParam, IntoDto, NameParam,
+    Permission2RoleDto, PolicyChangeDto, Role2GroupDto, Role2UserDto, User2GroupDto,
+};
+use export::{PolicyChanges, PolicyExport};
+use metrics::{Answer, RpcMetrics};
+
+pub type Result<T> = RpcResult<RbacResponse<T>>;
+
+/// Answer of a query which can fail with an `RbacError`. Such errors are returned as
+/// JSON-RPC errors, see [`rbac_error_code`], unless the server keeps the legacy shape,
+/// see [`PeaqRBAC::with_legacy_errors`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum RbacResponse<T> {
+    /// Nested result, `{"Ok": ...}` or `{"Err": {"typ": ..., "param": [...]}}`
+    Legacy(RbacResult<T, RbacError>),
+    Value(T),
+}
+
+/// Version of the runtime API which added all queries but the original `fetch_*` ones and
+/// the lookups by name. Against older runtimes `hasPermission`, `hasRole` and the batch
+/// queries are answered with those, the other queries fail with
+/// [`Error::UnsupportedByRuntime`].
+pub const EXTENDED_API_VERSION: u32 = 2;
+
+/// Version of the runtime API which added the lookups by name. Against older runtimes
+/// they are answered from all entities of the owner.
+pub const NAME_LOOKUP_API_VERSION: u32 = 3;
+
+/// Version of the runtime API which added the changes made in a block, required by
+/// `peaqrbac_subscribePolicyChanges`.
+pub const POLICY_CHANGES_API_VERSION: u32 = 4;
+
+/// Offset of the JSON-RPC error codes of `RbacError`s
+pub const RBAC_ERROR_CODE_BASE: i32 = 1000;
+
+/// Stable JSON-RPC error code of an `RbacError` of the given type
+pub fn rbac_error_code(typ: &RbacErrorType) -> i32 {
+    RBAC_ERROR_CODE_BASE + typ.code() as i32
+}
+
+/// Entity a query is about, telling how to read the parameter of its `RbacError`s
+enum Queried<'a, EntityId> {
+    /// The query is about no single entity
+    Nothing,
+    /// The query is about the entity with this id
+    Id(&'a EntityId, EntityKind),
+    /// The query looks up an entity of this kind by its name
+    Name(EntityKind),
+}
+
+/// Kind of the entity an `RbacError` refers to
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EntityKind {
+    Role,
+    Permission,
+    Group,
+    User,
+}
+
+/// `data` of the JSON-RPC error of an `RbacError`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RbacErrorData {
+    #[serde(rename = "type")]
+    pub typ: RbacErrorType,
+    /// 0x-hex of the entity id the error refers to, `None` if the parameter is no id
+    pub entity_id: Option<String>,
+    /// Known if the error refers to the entity queried for, by id or by name
+    pub entity_kind: Option<EntityKind>,
+    /// 0x-hex of the SCALE encoded parameter of the error
+    pub param: String,
+}
+
+/// Name of the RBAC pallet in `construct_runtime!` of the peaq runtimes
+pub const DEFAULT_PALLET_PREFIX: &[u8] = b"PeaqRbac";
+
+/// Answer of a query together with the storage keys it was computed from and a read
+/// proof of these keys, verifiable with `peaq_pallet_rbac::proof::RbacProofVerifier`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenResult<BlockHash, T> {
+    /// Block the answer and the proof refer to
+    pub at: BlockHash,
+    /// Answer of the query
+    pub result: RbacResult<T, RbacError>,
+    /// Storage keys covered by the proof
+    pub keys: Vec<Bytes>,
+    /// Trie nodes of the read proof
+    pub proof: Vec<Bytes>,
+}
+
+/// Maximum number of items answered by one batch query
+pub const MAX_BATCH_SIZE: usize = 1024;
 
-/// Trait defines RBAC-RPC interface
+/// Answer of a batch query for a single user
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserResult<EntityId, T> {
+    pub user_id: EntityId,
+    pub result: RbacResult<T, RbacError>,
+}
+
+/// Answer of `peaqrbac_checkPermissions` for a single (user, permission) pair
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionCheck<EntityId> {
+    pub user_id: EntityId,
+    pub permission_id: EntityId,
+    pub granted: bool,
+}
+
+/// Changes of an owner's policy made in a block, as announced by its `PolicyChanged`
+/// events. Sent for every block with changes, in chain order, and after a reorg.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyChangeNotice<BlockHash, AccountId, EntityId> {
+    pub block: BlockHash,
+    /// Blocks notified before which are no longer part of the chain, newest first. Their
+    /// changes were reverted unless made again in the blocks notified since.
+    pub retracted: Vec<BlockHash>,
+    pub changes: Vec<PolicyChangeDto<AccountId, EntityId>>,
+}
+
+/// Effective permissions of a user as of a block
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPermissionsNotice<BlockHash, EntityId> {
+    pub block: BlockHash,
+    pub permissions: Vec<EntityDto<EntityId>>,
+}
+
+/// Trait defines RBAC-RPC interface. Queries are answered at the block `at`, which
+/// defaults to the best block or, see [`PeaqRBAC::with_finalized_reads`], to the last
+/// finalized block.
 #[rpc(client, server)]
 pub trait PeaqRBACApi<BlockHash, AccountId, EntityId> {
     /// RPC method for extrinsic call fetchRole
@@ -28,98 +182,299 @@ pub trait PeaqRBACApi<BlockHash, AccountId, EntityId> {
     fn fetch_role(
         &self,
         account: AccountId,
-        entity: EntityId,
-        at: BlockHash,
-    ) -> Result<Entity<EntityId>>;
+        entity: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> Result<EntityDto<EntityId>>;
 
     /// RPC method for extrinsic call fetchRoles
     #[method(name = "peaqrbac_fetchRoles")]
-    fn fetch_roles(&self, owner: AccountId, at: BlockHash) -> Result<Vec<Entity<EntityId>>>;
+    fn fetch_roles(
+        &self,
+        owner: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<EntityDto<EntityId>>>;
 
     /// RPC method for extrinsic call fetchUserRoles
     #[method(name = "peaqrbac_fetchUserRoles")]
     fn fetch_user_roles(
         &self,
         owner: AccountId,
-        user_id: EntityId,
-        at: BlockHash,
-    ) -> Result<Vec<Role2User<EntityId>>>;
+        user_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<Role2UserDto<EntityId>>>;
 
     /// RPC method for extrinsic call fetchPermission
     #[method(name = "peaqrbac_fetchPermission")]
     fn fetch_permission(
         &self,
         owner: AccountId,
-        permission_id: EntityId,
-        at: BlockHash,
-    ) -> Result<Entity<EntityId>>;
+        permission_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> Result<EntityDto<EntityId>>;
 
     /// RPC method for extrinsic call fetchPermissions
     #[method(name = "peaqrbac_fetchPermissions")]
-    fn fetch_permissions(&self, owner: AccountId, at: BlockHash) -> Result<Vec<Entity<EntityId>>>;
+    fn fetch_permissions(
+        &self,
+        owner: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<EntityDto<EntityId>>>;
 
     /// RPC method for extrinsic call fetchRolePermissions
     #[method(name = "peaqrbac_fetchRolePermissions")]
     fn fetch_role_permissions(
         &self,
         owner: AccountId,
-        role_id: EntityId,
-        at: BlockHash,
-    ) -> Result<Vec<Permission2Role<EntityId>>>;
+        role_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<Permission2RoleDto<EntityId>>>;
 
     /// RPC method for extrinsic call fetchGroup
     #[method(name = "peaqrbac_fetchGroup")]
     fn fetch_group(
         &self,
         owner: AccountId,
-        group_id: EntityId,
-        at: BlockHash,
-    ) -> Result<Entity<EntityId>>;
+        group_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> Result<EntityDto<EntityId>>;
 
     /// RPC method for extrinsic call fetchGroups
     #[method(name = "peaqrbac_fetchGroups")]
-    fn fetch_groups(&self, owner: AccountId, at: BlockHash) -> Result<Vec<Entity<EntityId>>>;
+    fn fetch_groups(
+        &self,
+        owner: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<EntityDto<EntityId>>>;
 
     /// RPC method for extrinsic call fetchGroupRoles
     #[method(name = "peaqrbac_fetchGroupRoles")]
     fn fetch_group_roles(
         &self,
         owner: AccountId,
-        group_id: EntityId,
-        at: BlockHash,
-    ) -> Result<Vec<Role2Group<EntityId>>>;
+        group_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<Role2GroupDto<EntityId>>>;
 
     /// RPC method for extrinsic call fetchUserGroups
     #[method(name = "peaqrbac_fetchUserGroups")]
     fn fetch_user_groups(
         &self,
         owner: AccountId,
-        user_id: EntityId,
-        at: BlockHash,
-    ) -> Result<Vec<User2Group<EntityId>>>;
+        user_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<User2GroupDto<EntityId>>>;
 
     /// RPC method for extrinsic call fetchUserPermissions
     #[method(name = "peaqrbac_fetchUserPermissions")]
     fn fetch_user_permissions(
         &self,
         owner: AccountId,
-        user_id: EntityId,
-        at: BlockHash,
-    ) -> Result<Vec<Entity<EntityId>>>;
+        user_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<EntityDto<EntityId>>>;
 
     /// RPC method for extrinsic call fetchGroupPermissions
     #[method(name = "peaqrbac_fetchGroupPermissions")]
     fn fetch_group_permissions(
         &self,
         owner: AccountId,
-        group_id: EntityId,
-        at: BlockHash,
-    ) -> Result<Vec<Entity<EntityId>>>;
+        group_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<EntityDto<EntityId>>>;
+
+    /// RPC method checking whether a user holds a permission
+    #[method(name = "peaqrbac_hasPermission")]
+    fn has_permission(
+        &self,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        permission_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<bool>;
+
+    /// RPC method checking whether a user holds a role
+    #[method(name = "peaqrbac_hasRole")]
+    fn has_role(
+        &self,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        role_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<bool>;
+
+    /// RPC method for fetchUserRoles of many users in a single runtime call
+    #[method(name = "peaqrbac_fetchUsersRoles")]
+    fn fetch_users_roles(
+        &self,
+        owner: AccountId,
+        user_ids: Vec<IdParam<EntityId>>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<UserResult<IdDto<EntityId>, Vec<Role2UserDto<EntityId>>>>>;
+
+    /// RPC method for fetchUserPermissions of many users in a single runtime call
+    #[method(name = "peaqrbac_fetchUsersPermissions")]
+    fn fetch_users_permissions(
+        &self,
+        owner: AccountId,
+        user_ids: Vec<IdParam<EntityId>>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<UserResult<IdDto<EntityId>, Vec<EntityDto<EntityId>>>>>;
+
+    /// RPC method checking many (user, permission) pairs in a single runtime call
+    #[method(name = "peaqrbac_checkPermissions")]
+    fn check_permissions(
+        &self,
+        owner: AccountId,
+        queries: Vec<(IdParam<EntityId>, IdParam<EntityId>)>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<PermissionCheck<IdDto<EntityId>>>>;
+
+    /// RPC method fetching the commitment to an owner's whole policy
+    #[method(name = "peaqrbac_fetchPolicyCommitment")]
+    fn fetch_policy_commitment(&self, owner: AccountId, at: Option<BlockHash>) -> RpcResult<H256>;
+
+    /// RPC method checking an access proof against an owner's published policy root
+    #[method(name = "peaqrbac_verifyAccessProof")]
+    fn verify_access_proof(
+        &self,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        permission_id: IdParam<EntityId>,
+        proof: AccessProof<EntityId>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<bool>;
+
+    /// RPC method fetching a page of an owner's audit log, starting at sequence number
+    /// `from` or at the oldest record still kept
+    #[method(name = "peaqrbac_fetchAuditLog")]
+    fn fetch_audit_log(
+        &self,
+        owner: AccountId,
+        from: u64,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<AuditRecordDto<AccountId, EntityId>>>;
+
+    /// RPC method listing the policy snapshots stored by an owner
+    #[method(name = "peaqrbac_fetchSnapshots")]
+    fn fetch_snapshots(
+        &self,
+        owner: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<SnapshotInfo>>;
+
+    /// RPC method showing the changes publishing an owner's policy draft would make, in
+    /// the document of `peaqrbac_diffPolicy` with both blocks being the one the draft is
+    /// compared at
+    #[method(name = "peaqrbac_diffDraft")]
+    fn diff_draft(
+        &self,
+        owner: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<PolicyChanges<BlockHash, AccountId, EntityId>>;
+
+    /// RPC method exporting all entities and assignments of an owner in one document. On
+    /// chains which assigned users before the pallet's user index was introduced, user
+    /// assignments are only exported from blocks after its storage migration to v1.
+    #[method(name = "peaqrbac_exportPolicy")]
+    fn export_policy(
+        &self,
+        owner: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<PolicyExport<BlockHash, AccountId, EntityId>>;
+
+    /// RPC method listing the changes of an owner's policy between two blocks
+    #[method(name = "peaqrbac_diffPolicy")]
+    fn diff_policy(
+        &self,
+        owner: AccountId,
+        from_block: BlockHash,
+        to_block: Option<BlockHash>,
+    ) -> RpcResult<PolicyChanges<BlockHash, AccountId, EntityId>>;
+
+    /// RPC method for extrinsic call fetchUserRoles, including a read proof of the
+    /// user's role assignments and of the assigned roles
+    #[method(name = "peaqrbac_proveUserRoles")]
+    fn prove_user_roles(
+        &self,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<ProvenResult<BlockHash, Vec<Role2UserDto<EntityId>>>>;
+
+    /// RPC method for extrinsic call fetchRolePermissions, including a read proof of the
+    /// role, its permission assignments and of the assigned permissions
+    #[method(name = "peaqrbac_proveRolePermissions")]
+    fn prove_role_permissions(
+        &self,
+        owner: AccountId,
+        role_id: IdParam<EntityId>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<ProvenResult<BlockHash, Vec<Permission2RoleDto<EntityId>>>>;
+
+    /// RPC method looking up an owner's role by its name
+    #[method(name = "peaqrbac_fetchRoleByName")]
+    fn fetch_role_by_name(
+        &self,
+        owner: AccountId,
+        name: NameParam,
+        at: Option<BlockHash>,
+    ) -> Result<EntityDto<EntityId>>;
+
+    /// RPC method looking up an owner's permission by its name
+    #[method(name = "peaqrbac_fetchPermissionByName")]
+    fn fetch_permission_by_name(
+        &self,
+        owner: AccountId,
+        name: NameParam,
+        at: Option<BlockHash>,
+    ) -> Result<EntityDto<EntityId>>;
+
+    /// RPC method looking up an owner's group by its name
+    #[method(name = "peaqrbac_fetchGroupByName")]
+    fn fetch_group_by_name(
+        &self,
+        owner: AccountId,
+        name: NameParam,
+        at: Option<BlockHash>,
+    ) -> Result<EntityDto<EntityId>>;
+
+    /// Subscription streaming the changes of an owner's policy, on each new best block or,
+    /// if `finalized` is set, on each finalized block. `finalized` defaults to the
+    /// server's finalized reads setting. Closed with an error if the changes of a block
+    /// can't be read, e.g. as its state was pruned.
+    #[subscription(
+        name = "peaqrbac_subscribePolicyChanges" => "peaqrbac_policyChanges",
+        unsubscribe = "peaqrbac_unsubscribePolicyChanges",
+        item = PolicyChangeNotice<BlockHash, AccountId, EntityId>,
+    )]
+    fn subscribe_policy_changes(&self, owner: AccountId, finalized: Option<bool>);
+
+    /// Subscription pushing the effective permissions of a user, at first and whenever
+    /// they change
+    #[subscription(
+        name = "peaqrbac_subscribeUserPermissions" => "peaqrbac_userPermissions",
+        unsubscribe = "peaqrbac_unsubscribeUserPermissions",
+        item = UserPermissionsNotice<BlockHash, EntityId>,
+    )]
+    fn subscribe_user_permissions(
+        &self,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        finalized: Option<bool>,
+    );
 }
 
 /// A struct that implements the [`PeaqRBACApi`].
 pub struct PeaqRBAC<Client, Block> {
     client: Arc<Client>,
+    pallet_prefix: Vec<u8>,
+    executor: Option<Arc<dyn SpawnNamed>>,
+    finalized_reads: bool,
+    legacy_errors: bool,
+    readable_json: bool,
+    cache: Option<Arc<RpcCache>>,
+    metrics: Option<RpcMetrics>,
     _marker: std::marker::PhantomData<Block>,
 }
 
@@ -128,19 +483,392 @@ impl<Client, Block> PeaqRBAC<Client, Block> {
     pub fn new(client: Arc<Client>) -> Self {
         PeaqRBAC {
             client,
+            pallet_prefix: DEFAULT_PALLET_PREFIX.to_vec(),
+            executor: None,
+            finalized_reads: false,
+            legacy_errors: false,
+            readable_json: false,
+            cache: None,
+            metrics: None,
             _marker: Default::default(),
         }
     }
+
+    /// Create new `PeaqRBAC` recording metrics of its method calls in the registry, if
+    /// any is given.
+    pub fn new_with_metrics(
+        client: Arc<Client>,
+        registry: Option<&Registry>,
+    ) -> std::result::Result<Self, PrometheusError> {
+        let mut rbac = Self::new(client);
+        rbac.metrics = registry.map(RpcMetrics::register).transpose()?;
+        Ok(rbac)
+    }
+
+    /// Sets the name of the RBAC pallet in `construct_runtime!`, used to derive the
+    /// storage keys of proofs, if it differs from [`DEFAULT_PALLET_PREFIX`].
+    pub fn with_pallet_prefix(mut self, pallet_prefix: &[u8]) -> Self {
+        self.pallet_prefix = pallet_prefix.to_vec();
+        self
+    }
+
+    /// Answers queries without an explicit block at the last finalized instead of the best
+    /// block, so access decisions can't be reversed by a reorg. Also the default of
+    /// subscriptions.
+    pub fn with_finalized_reads(mut self, finalized_reads: bool) -> Self {
+        self.finalized_reads = finalized_reads;
+        self
+    }
+
+    /// Returns `RbacError`s nested in a successful response, as before they became
+    /// JSON-RPC errors, for clients relying on that shape.
+    pub fn with_legacy_errors(mut self, legacy_errors: bool) -> Self {
+        self.legacy_errors = legacy_errors;
+        self
+    }
+
+    /// Serialises names and ids in responses human-readable, see [`dto`].
+    pub fn with_readable_json(mut self, readable_json: bool) -> Self {
+        self.readable_json = readable_json;
+        self
+    }
+
+    /// Caches the answers of queries in the given cache, which may be shared with other
+    /// handlers and read for its statistics, see [`cache`].
+    pub fn with_cache(mut self, cache: Arc<RpcCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Answers the call of `method`, recording its metrics if configured
+    fn measure<T: Answer>(
+        &self,
+        method: &str,
+        call: impl FnOnce() -> RpcResult<T>,
+    ) -> RpcResult<T> {
+        match &self.metrics {
+            Some(metrics) => metrics.measure(method, call),
+            None => call(),
+        }
+    }
+
+    /// Answer of a query, turning an `RbacError` into a JSON-RPC error unless legacy
+    /// errors are configured. `queried` is the entity the query is about.
+    fn respond<T, EntityId: Codec + PartialEq>(
+        &self,
+        result: RbacResult<T, RbacError>,
+        queried: Queried<EntityId>,
+    ) -> Result<T> {
+        if self.legacy_errors {
+            return Ok(RbacResponse::Legacy(result));
+        }
+        result
+            .map(RbacResponse::Value)
+            .map_err(|err| map_rbac_err(err, queried))
+    }
+
+    /// Sets the executor running subscriptions, which are rejected without one.
+    pub fn with_executor(mut self, executor: Arc<dyn SpawnNamed>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Pipes the stream into the subscription in a task of its own, closing it with the
+    /// first error
+    fn spawn_subscription<T: Serialize + Send + 'static>(
+        &self,
+        mut sink: SubscriptionSink,
+        stream: BoxStream<'static, std::result::Result<T, String>>,
+    ) -> SubscriptionResult {
+        let executor = match &self.executor {
+            Some(executor) => executor,
+            None => {
+                let _ = sink.reject(ErrorObject::owned(
+                    Error::SubscriptionError.into(),
+                    "Subscriptions are not supported.",
+                    None::<()>,
+                ));
+                return Ok(());
+            }
+        };
+
+        let fut = async move {
+            let closed = sink.pipe_from_try_stream(stream).await;
+            sink.close(closed);
+        };
+        executor.spawn("peaqrbac-rpc-subscription", Some("rpc"), fut.boxed());
+        Ok(())
+    }
+}
+
+impl<Client, Block> PeaqRBAC<Client, Block>
+where
+    Block: BlockT,
+    Client: HeaderBackend<Block> + BlockchainEvents<Block>,
+{
+    /// Block a query is answered at, if none is given the best or last finalized block
+    fn block_hash(&self, at: Option<<Block as BlockT>::Hash>) -> <Block as BlockT>::Hash {
+        at.unwrap_or_else(|| {
+            let info = self.client.info();
+            if self.finalized_reads {
+                info.finalized_hash
+            } else {
+                info.best_hash
+            }
+        })
+    }
+
+    /// Hash of the block subscriptions start at, followed by the hashes of new best or
+    /// finalized blocks
+    fn block_stream(&self, finalized: bool) -> BoxStream<'static, <Block as BlockT>::Hash> {
+        let info = self.client.info();
+        if finalized {
+            let blocks = self
+                .client
+                .finality_notification_stream()
+                .map(|notification| notification.hash);
+            stream::once(future::ready(info.finalized_hash))
+                .chain(blocks)
+                .boxed()
+        } else {
+            let blocks = self
+                .client
+                .import_notification_stream()
+                .filter(|notification| future::ready(notification.is_new_best))
+                .map(|notification| notification.hash);
+            stream::once(future::ready(info.best_hash))
+                .chain(blocks)
+                .boxed()
+        }
+    }
+}
+
+impl<Client, Block: BlockT> PeaqRBAC<Client, Block> {
+    /// Answer of the runtime API call, from the cache if configured and the same
+    /// `method` was called with `params` at the block before
+    fn cached<P: Encode, R: Codec>(
+        &self,
+        at: <Block as BlockT>::Hash,
+        method: &'static str,
+        params: &P,
+        call: impl FnOnce() -> std::result::Result<R, ApiError>,
+    ) -> RpcResult<R> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return call().map_err(map_api_err),
+        };
+
+        let key = (at, method, params).encode();
+        let cached = cache
+            .get(&key)
+            .and_then(|value| R::decode(&mut &value[..]).ok());
+        if let Some(metrics) = &self.metrics {
+            metrics.cache_lookup(method, cached.is_some());
+        }
+        if let Some(answer) = cached {
+            return Ok(answer);
+        }
+        let answer = call().map_err(map_api_err)?;
+        cache.insert(key, answer.encode());
+        Ok(answer)
+    }
+}
+
+impl<Client, Block> PeaqRBAC<Client, Block>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block>,
+{
+    /// Whether the runtime at the block implements the version of the runtime API
+    fn has_api_version<AccountId, EntityId>(
+        &self,
+        at: <Block as BlockT>::Hash,
+        version: u32,
+    ) -> std::result::Result<bool, ApiError>
+    where
+        AccountId: Codec,
+        EntityId: Codec,
+        Client::Api: PeaqRBACRuntimeApi<Block, AccountId, EntityId>,
+    {
+        self.client
+            .runtime_api()
+            .has_api_with::<dyn PeaqRBACRuntimeApi<Block, AccountId, EntityId>, _>(at, |v| {
+                v >= version
+            })
+    }
+
+    /// Rejects queries the runtime at the block doesn't implement
+    fn ensure_api_version<AccountId, EntityId>(
+        &self,
+        at: <Block as BlockT>::Hash,
+        version: u32,
+    ) -> RpcResult<()>
+    where
+        AccountId: Codec,
+        EntityId: Codec,
+        Client::Api: PeaqRBACRuntimeApi<Block, AccountId, EntityId>,
+    {
+        match self.has_api_version::<AccountId, EntityId>(at, version) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(unsupported_err(version)),
+            Err(err) => Err(map_api_err(err)),
+        }
+    }
+
+    /// `has_permission` of older runtimes, walking the roles of the user and of its groups
+    /// like the runtime does. Their `fetch_user_permissions` can't be used, as it neither
+    /// skips disabled roles and groups nor tolerates disabled permissions.
+    fn has_permission_v1<AccountId, EntityId>(
+        &self,
+        at: <Block as BlockT>::Hash,
+        owner: AccountId,
+        user_id: EntityId,
+        permission_id: &EntityId,
+    ) -> std::result::Result<bool, ApiError>
+    where
+        AccountId: Codec + Clone,
+        EntityId: Codec + Clone + PartialEq,
+        Client::Api: PeaqRBACRuntimeApi<Block, AccountId, EntityId>,
+    {
+        let api = self.client.runtime_api();
+        if api
+            .fetch_permission(at, owner.clone(), permission_id.clone())?
+            .is_err()
+        {
+            return Ok(false);
+        }
+        let grants = |role_id: EntityId| -> std::result::Result<bool, ApiError> {
+            if api.fetch_role(at, owner.clone(), role_id.clone())?.is_err() {
+                return Ok(false);
+            }
+            Ok(api
+                .fetch_role_permissions(at, owner.clone(), role_id)?
+                .map_or(false, |permissions| {
+                    permissions
+                        .iter()
+                        .any(|p2r| &p2r.permission == permission_id)
+                }))
+        };
+
+        for r2u in api
+            .fetch_user_roles(at, owner.clone(), user_id.clone())?
+            .unwrap_or_default()
+        {
+            if grants(r2u.role)? {
+                return Ok(true);
+            }
+        }
+        for u2g in api
+            .fetch_user_groups(at, owner.clone(), user_id)?
+            .unwrap_or_default()
+        {
+            if api
+                .fetch_group(at, owner.clone(), u2g.group.clone())?
+                .is_err()
+            {
+                continue;
+            }
+            for r2g in api
+                .fetch_group_roles(at, owner.clone(), u2g.group)?
+                .unwrap_or_default()
+            {
+                if grants(r2g.role)? {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// `has_role` of older runtimes, from the roles of the user and of its groups
+    fn has_role_v1<AccountId, EntityId>(
+        &self,
+        at: <Block as BlockT>::Hash,
+        owner: AccountId,
+        user_id: EntityId,
+        role_id: &EntityId,
+    ) -> std::result::Result<bool, ApiError>
+    where
+        AccountId: Codec + Clone,
+        EntityId: Codec + Clone + PartialEq,
+        Client::Api: PeaqRBACRuntimeApi<Block, AccountId, EntityId>,
+    {
+        let api = self.client.runtime_api();
+        if api.fetch_role(at, owner.clone(), role_id.clone())?.is_err() {
+            return Ok(false);
+        }
+        if api
+            .fetch_user_roles(at, owner.clone(), user_id.clone())?
+            .map_or(false, |roles| roles.iter().any(|r2u| &r2u.role == role_id))
+        {
+            return Ok(true);
+        }
+
+        for u2g in api
+            .fetch_user_groups(at, owner.clone(), user_id)?
+            .unwrap_or_default()
+        {
+            if api
+                .fetch_group(at, owner.clone(), u2g.group.clone())?
+                .is_err()
+            {
+                continue;
+            }
+            if api
+                .fetch_group_roles(at, owner.clone(), u2g.group)?
+                .map_or(false, |roles| roles.iter().any(|r2g| &r2g.role == role_id))
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl<Client, Block> PeaqRBAC<Client, Block>
+where
+    Block: BlockT,
+    Client: ProofProvider<Block>,
+{
+    /// Bundles the answer with a read proof of the given storage keys
+    fn prove<T>(
+        &self,
+        result: RbacResult<T, RbacError>,
+        keys: Vec<Vec<u8>>,
+        at: <Block as BlockT>::Hash,
+    ) -> RpcResult<ProvenResult<<Block as BlockT>::Hash, T>> {
+        let read_proof = self
+            .client
+            .read_proof(at, &mut keys.iter().map(|key| key.as_slice()))
+            .map_err(map_proof_err)?;
+
+        Ok(ProvenResult {
+            at,
+            result,
+            keys: keys.into_iter().map(Bytes).collect(),
+            proof: read_proof.into_iter_nodes().map(Bytes).collect(),
+        })
+    }
 }
 
 pub enum Error {
     RuntimeError,
+    ProofError,
+    SubscriptionError,
+    BatchTooLarge,
+    InvalidId,
+    UnsupportedByRuntime,
 }
 
 impl From<Error> for i32 {
     fn from(e: Error) -> i32 {
         match e {
             Error::RuntimeError => 1,
+            Error::ProofError => 2,
+            Error::SubscriptionError => 3,
+            Error::BatchTooLarge => 4,
+            Error::InvalidId => 5,
+            Error::UnsupportedByRuntime => 6,
         }
     }
 }
@@ -155,137 +883,915 @@ fn map_api_err(api_err: ApiError) -> JsonRpseeError {
     )))
 }
 
+/// Error mapping of domain errors. The parameter is decoded as entity id unless the
+/// error type carries something else: a name for name conflicts and failed lookups by
+/// name, the owner for a too deep policy tree.
+fn map_rbac_err<EntityId: Codec + PartialEq>(
+    err: RbacError,
+    queried: Queried<EntityId>,
+) -> JsonRpseeError {
+    let carries_id = match err.typ {
+        RbacErrorType::NameAlreadyExist | RbacErrorType::PolicyTreeTooDeep => false,
+        RbacErrorType::EntityDoesNotExist => !matches!(queried, Queried::Name(_)),
+        _ => true,
+    };
+    let id = carries_id
+        .then(|| EntityId::decode_all(&mut &err.param[..]).ok())
+        .flatten();
+    let entity_kind = match (&id, queried) {
+        (Some(id), Queried::Id(queried, kind)) if id == queried => Some(kind),
+        // Any entity id an error of a lookup by name carries is the one found
+        (Some(_), Queried::Name(kind)) => Some(kind),
+        _ => None,
+    };
+    let entity_id = id.map(|id| to_hex(&id.encode(), false));
+
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        rbac_error_code(&err.typ),
+        format!("{:?}", err.typ),
+        Some(RbacErrorData {
+            entity_id,
+            entity_kind,
+            param: to_hex(&err.param, false),
+            typ: err.typ,
+        }),
+    )))
+}
+
+/// Error mapping of failed read proof generation
+#[inline]
+fn map_proof_err(err: sp_blockchain::Error) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::ProofError.into(),
+        "Unable to generate read proof.",
+        Some(format!("{err:?}")),
+    )))
+}
+
+/// Error of queries the runtime doesn't implement
+fn unsupported_err(version: u32) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::UnsupportedByRuntime.into(),
+        "Unsupported by runtime.",
+        Some(format!("runtime API version {version} required")),
+    )))
+}
+
+/// Lookup by name of older runtimes, the first of the owner's entities holding the name
+fn find_by_name<EntityId: Codec>(
+    entities: RbacResult<Vec<Entity<EntityId>>, RbacError>,
+    name: &[u8],
+) -> RbacResult<Entity<EntityId>, RbacError> {
+    match entities?.into_iter().find(|entity| entity.name == name) {
+        Some(entity) if !entity.enabled => Err(RbacError {
+            typ: RbacErrorType::EntityDisabled,
+            param: entity.id.encode(),
+        }),
+        Some(entity) => Ok(entity),
+        None => Err(RbacError {
+            typ: RbacErrorType::EntityDoesNotExist,
+            param: name.encode(),
+        }),
+    }
+}
+
+/// Rejects batch queries with more than [`MAX_BATCH_SIZE`] items
+fn ensure_batch_size(len: usize) -> RpcResult<()> {
+    if len > MAX_BATCH_SIZE {
+        return Err(JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+            Error::BatchTooLarge.into(),
+            "Too many items in batch.",
+            Some(format!("{len} > {MAX_BATCH_SIZE}")),
+        ))));
+    }
+    Ok(())
+}
+
+/// Notices of the owner's policy changes made in the blocks enacted on the way from block
+/// `from` to block `to`, the first one also listing the blocks retracted on the way
+fn policy_change_notices<Client, Block, AccountId, EntityId>(
+    client: &Client,
+    owner: &AccountId,
+    from: <Block as BlockT>::Hash,
+    to: <Block as BlockT>::Hash,
+    readable: bool,
+) -> std::result::Result<
+    Vec<PolicyChangeNotice<<Block as BlockT>::Hash, AccountId, EntityId>>,
+    String,
+>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block> + HeaderMetadata<Block, Error = sp_blockchain::Error>,
+    Client::Api: PeaqRBACRuntimeApi<Block, AccountId, EntityId>,
+    AccountId: Codec + Clone + Serialize,
+    EntityId: Codec + Serialize,
+{
+    let route = sp_blockchain::tree_route(client, from, to)
+        .map_err(|e| format!("No route from block {from:?} to {to:?}: {e}"))?;
+    let mut retracted: Vec<_> = route.retracted().iter().map(|block| block.hash).collect();
+
+    let mut notices = Vec::new();
+    for block in route.enacted() {
+        let changes = client
+            .runtime_api()
+            .fetch_policy_changes(block.hash, owner.clone())
+            .map_err(|e| format!("Changes of block {:?} unavailable: {e}", block.hash))?;
+        if !changes.is_empty() || !retracted.is_empty() {
+            notices.push(PolicyChangeNotice {
+                block: block.hash,
+                retracted: mem::take(&mut retracted),
+                changes: changes.into_dto(readable),
+            });
+        }
+    }
+    // A reorg to an ancestor enacts no block
+    if !retracted.is_empty() {
+        notices.push(PolicyChangeNotice {
+            block: to,
+            retracted,
+            changes: Vec::new(),
+        });
+    }
+    Ok(notices)
+}
+
 #[async_trait]
 impl<Client, Block, AccountId, EntityId>
     PeaqRBACApiServer<<Block as BlockT>::Hash, AccountId, EntityId> for PeaqRBAC<Client, Block>
 where
     Block: BlockT,
-    Client: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    Client: Send
+        + Sync
+        + 'static
+        + ProvideRuntimeApi<Block>
+        + HeaderBackend<Block>
+        + HeaderMetadata<Block, Error = sp_blockchain::Error>
+        + ProofProvider<Block>
+        + BlockchainEvents<Block>,
     Client::Api: PeaqRBACRuntimeApi<Block, AccountId, EntityId>,
-    AccountId: Codec,
-    EntityId: Codec,
+    AccountId: Codec + Clone + Serialize + Send + Sync + 'static,
+    EntityId: Codec + Clone + Ord + Serialize + Send + Sync + 'static,
 {
     fn fetch_role(
         &self,
         account: AccountId,
-        entity: EntityId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Entity<EntityId>> {
-        let api = self.client.runtime_api();
-        api.fetch_role(at, account, entity).map_err(map_api_err)
+        entity: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<EntityDto<EntityId>> {
+        self.measure("peaqrbac_fetchRole", || {
+            let at = self.block_hash(at);
+            let entity = entity.resolve()?;
+            let result = self.cached(at, "fetch_role", &(&account, &entity), || {
+                self.client
+                    .runtime_api()
+                    .fetch_role(at, account.clone(), entity.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::Id(&entity, EntityKind::Role),
+            )
+        })
     }
 
     fn fetch_roles(
         &self,
         owner: AccountId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Vec<Entity<EntityId>>> {
-        let api = self.client.runtime_api();
-        api.fetch_roles(at, owner).map_err(map_api_err)
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<EntityDto<EntityId>>> {
+        self.measure("peaqrbac_fetchRoles", || {
+            let at = self.block_hash(at);
+            let result = self.cached(at, "fetch_roles", &owner, || {
+                self.client.runtime_api().fetch_roles(at, owner.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::<EntityId>::Nothing,
+            )
+        })
     }
 
     fn fetch_user_roles(
         &self,
         owner: AccountId,
-        user_id: EntityId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Vec<Role2User<EntityId>>> {
-        let api = self.client.runtime_api();
-        api.fetch_user_roles(at, owner, user_id)
-            .map_err(map_api_err)
+        user_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<Role2UserDto<EntityId>>> {
+        self.measure("peaqrbac_fetchUserRoles", || {
+            let at = self.block_hash(at);
+            let user_id = user_id.resolve()?;
+            let result = self.cached(at, "fetch_user_roles", &(&owner, &user_id), || {
+                self.client
+                    .runtime_api()
+                    .fetch_user_roles(at, owner.clone(), user_id.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::Id(&user_id, EntityKind::User),
+            )
+        })
     }
 
     fn fetch_permission(
         &self,
         owner: AccountId,
-        permission_id: EntityId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Entity<EntityId>> {
-        let api = self.client.runtime_api();
-        api.fetch_permission(at, owner, permission_id)
-            .map_err(map_api_err)
+        permission_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<EntityDto<EntityId>> {
+        self.measure("peaqrbac_fetchPermission", || {
+            let at = self.block_hash(at);
+            let permission_id = permission_id.resolve()?;
+            let result = self.cached(at, "fetch_permission", &(&owner, &permission_id), || {
+                self.client
+                    .runtime_api()
+                    .fetch_permission(at, owner.clone(), permission_id.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::Id(&permission_id, EntityKind::Permission),
+            )
+        })
     }
 
     fn fetch_permissions(
         &self,
         owner: AccountId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Vec<Entity<EntityId>>> {
-        let api = self.client.runtime_api();
-        api.fetch_permissions(at, owner).map_err(map_api_err)
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<EntityDto<EntityId>>> {
+        self.measure("peaqrbac_fetchPermissions", || {
+            let at = self.block_hash(at);
+            let result = self.cached(at, "fetch_permissions", &owner, || {
+                self.client
+                    .runtime_api()
+                    .fetch_permissions(at, owner.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::<EntityId>::Nothing,
+            )
+        })
     }
 
     fn fetch_role_permissions(
         &self,
         owner: AccountId,
-        role_id: EntityId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Vec<Permission2Role<EntityId>>> {
-        let api = self.client.runtime_api();
-        api.fetch_role_permissions(at, owner, role_id)
-            .map_err(map_api_err)
+        role_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<Permission2RoleDto<EntityId>>> {
+        self.measure("peaqrbac_fetchRolePermissions", || {
+            let at = self.block_hash(at);
+            let role_id = role_id.resolve()?;
+            let result = self.cached(at, "fetch_role_permissions", &(&owner, &role_id), || {
+                self.client
+                    .runtime_api()
+                    .fetch_role_permissions(at, owner.clone(), role_id.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::Id(&role_id, EntityKind::Role),
+            )
+        })
     }
 
     fn fetch_group(
         &self,
         owner: AccountId,
-        group_id: EntityId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Entity<EntityId>> {
-        let api = self.client.runtime_api();
-        api.fetch_group(at, owner, group_id).map_err(map_api_err)
+        group_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<EntityDto<EntityId>> {
+        self.measure("peaqrbac_fetchGroup", || {
+            let at = self.block_hash(at);
+            let group_id = group_id.resolve()?;
+            let result = self.cached(at, "fetch_group", &(&owner, &group_id), || {
+                self.client
+                    .runtime_api()
+                    .fetch_group(at, owner.clone(), group_id.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::Id(&group_id, EntityKind::Group),
+            )
+        })
     }
 
     fn fetch_groups(
         &self,
         owner: AccountId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Vec<Entity<EntityId>>> {
-        let api = self.client.runtime_api();
-        api.fetch_groups(at, owner).map_err(map_api_err)
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<EntityDto<EntityId>>> {
+        self.measure("peaqrbac_fetchGroups", || {
+            let at = self.block_hash(at);
+            let result = self.cached(at, "fetch_groups", &owner, || {
+                self.client.runtime_api().fetch_groups(at, owner.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::<EntityId>::Nothing,
+            )
+        })
     }
 
     fn fetch_group_roles(
         &self,
         owner: AccountId,
-        group_id: EntityId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Vec<Role2Group<EntityId>>> {
-        let api = self.client.runtime_api();
-        api.fetch_group_roles(at, owner, group_id)
-            .map_err(map_api_err)
+        group_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<Role2GroupDto<EntityId>>> {
+        self.measure("peaqrbac_fetchGroupRoles", || {
+            let at = self.block_hash(at);
+            let group_id = group_id.resolve()?;
+            let result = self.cached(at, "fetch_group_roles", &(&owner, &group_id), || {
+                self.client
+                    .runtime_api()
+                    .fetch_group_roles(at, owner.clone(), group_id.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::Id(&group_id, EntityKind::Group),
+            )
+        })
     }
 
     fn fetch_user_groups(
         &self,
         owner: AccountId,
-        user_id: EntityId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Vec<User2Group<EntityId>>> {
-        let api = self.client.runtime_api();
-        api.fetch_user_groups(at, owner, user_id)
-            .map_err(map_api_err)
+        user_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<User2GroupDto<EntityId>>> {
+        self.measure("peaqrbac_fetchUserGroups", || {
+            let at = self.block_hash(at);
+            let user_id = user_id.resolve()?;
+            let result = self.cached(at, "fetch_user_groups", &(&owner, &user_id), || {
+                self.client
+                    .runtime_api()
+                    .fetch_user_groups(at, owner.clone(), user_id.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::Id(&user_id, EntityKind::User),
+            )
+        })
     }
 
     fn fetch_user_permissions(
         &self,
         owner: AccountId,
-        user_id: EntityId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Vec<Entity<EntityId>>> {
-        let api = self.client.runtime_api();
-        api.fetch_user_permissions(at, owner, user_id)
-            .map_err(map_api_err)
+        user_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<EntityDto<EntityId>>> {
+        self.measure("peaqrbac_fetchUserPermissions", || {
+            let at = self.block_hash(at);
+            let user_id = user_id.resolve()?;
+            let result = self.cached(at, "fetch_user_permissions", &(&owner, &user_id), || {
+                self.client
+                    .runtime_api()
+                    .fetch_user_permissions(at, owner.clone(), user_id.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::Id(&user_id, EntityKind::User),
+            )
+        })
     }
 
     fn fetch_group_permissions(
         &self,
         owner: AccountId,
-        group_id: EntityId,
-        at: <Block as BlockT>::Hash,
-    ) -> Result<Vec<Entity<EntityId>>> {
-        let api = self.client.runtime_api();
-        api.fetch_group_permissions(at, owner, group_id)
-            .map_err(map_api_err)
+        group_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<EntityDto<EntityId>>> {
+        self.measure("peaqrbac_fetchGroupPermissions", || {
+            let at = self.block_hash(at);
+            let group_id = group_id.resolve()?;
+            let result =
+                self.cached(at, "fetch_group_permissions", &(&owner, &group_id), || {
+                    self.client.runtime_api().fetch_group_permissions(
+                        at,
+                        owner.clone(),
+                        group_id.clone(),
+                    )
+                })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::Id(&group_id, EntityKind::Group),
+            )
+        })
+    }
+
+    fn has_permission(
+        &self,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        permission_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<bool> {
+        self.measure("peaqrbac_hasPermission", || {
+            let at = self.block_hash(at);
+            let user_id = user_id.resolve()?;
+            let permission_id = permission_id.resolve()?;
+            self.cached(
+                at,
+                "has_permission",
+                &(&owner, &user_id, &permission_id),
+                || {
+                    if !self.has_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)? {
+                        return self.has_permission_v1(
+                            at,
+                            owner.clone(),
+                            user_id.clone(),
+                            &permission_id,
+                        );
+                    }
+                    self.client.runtime_api().has_permission(
+                        at,
+                        owner.clone(),
+                        user_id.clone(),
+                        permission_id.clone(),
+                    )
+                },
+            )
+        })
+    }
+
+    fn has_role(
+        &self,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        role_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<bool> {
+        self.measure("peaqrbac_hasRole", || {
+            let at = self.block_hash(at);
+            let user_id = user_id.resolve()?;
+            let role_id = role_id.resolve()?;
+            self.cached(at, "has_role", &(&owner, &user_id, &role_id), || {
+                if !self.has_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)? {
+                    return self.has_role_v1(at, owner.clone(), user_id.clone(), &role_id);
+                }
+                self.client.runtime_api().has_role(
+                    at,
+                    owner.clone(),
+                    user_id.clone(),
+                    role_id.clone(),
+                )
+            })
+        })
+    }
+
+    fn fetch_users_roles(
+        &self,
+        owner: AccountId,
+        user_ids: Vec<IdParam<EntityId>>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<UserResult<IdDto<EntityId>, Vec<Role2UserDto<EntityId>>>>> {
+        self.measure("peaqrbac_fetchUsersRoles", || {
+            let at = self.block_hash(at);
+            ensure_batch_size(user_ids.len())?;
+            let user_ids = resolve_all(user_ids)?;
+            let results = self.cached(at, "fetch_users_roles", &(&owner, &user_ids), || {
+                let api = self.client.runtime_api();
+                if !self.has_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)? {
+                    return user_ids
+                        .iter()
+                        .map(|user_id| {
+                            let roles = api.fetch_user_roles(at, owner.clone(), user_id.clone())?;
+                            Ok::<_, ApiError>((user_id.clone(), roles))
+                        })
+                        .collect();
+                }
+                api.fetch_users_roles(at, owner.clone(), user_ids.clone())
+            })?;
+            Ok(results
+                .into_iter()
+                .map(|(user_id, result)| UserResult {
+                    user_id: id_dto(user_id, self.readable_json),
+                    result: result.map(|v| v.into_dto(self.readable_json)),
+                })
+                .collect())
+        })
+    }
+
+    fn fetch_users_permissions(
+        &self,
+        owner: AccountId,
+        user_ids: Vec<IdParam<EntityId>>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<UserResult<IdDto<EntityId>, Vec<EntityDto<EntityId>>>>> {
+        self.measure("peaqrbac_fetchUsersPermissions", || {
+            let at = self.block_hash(at);
+            ensure_batch_size(user_ids.len())?;
+            let user_ids = resolve_all(user_ids)?;
+            let results =
+                self.cached(at, "fetch_users_permissions", &(&owner, &user_ids), || {
+                    let api = self.client.runtime_api();
+                    if !self.has_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)? {
+                        return user_ids
+                            .iter()
+                            .map(|user_id| {
+                                let permissions =
+                                    api.fetch_user_permissions(at, owner.clone(), user_id.clone())?;
+                                Ok::<_, ApiError>((user_id.clone(), permissions))
+                            })
+                            .collect();
+                    }
+                    api.fetch_users_permissions(at, owner.clone(), user_ids.clone())
+                })?;
+            Ok(results
+                .into_iter()
+                .map(|(user_id, result)| UserResult {
+                    user_id: id_dto(user_id, self.readable_json),
+                    result: result.map(|v| v.into_dto(self.readable_json)),
+                })
+                .collect())
+        })
+    }
+
+    fn check_permissions(
+        &self,
+        owner: AccountId,
+        queries: Vec<(IdParam<EntityId>, IdParam<EntityId>)>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<PermissionCheck<IdDto<EntityId>>>> {
+        self.measure("peaqrbac_checkPermissions", || {
+            let at = self.block_hash(at);
+            ensure_batch_size(queries.len())?;
+            let queries = queries
+                .into_iter()
+                .map(|(user_id, permission_id)| Ok((user_id.resolve()?, permission_id.resolve()?)))
+                .collect::<RpcResult<Vec<_>>>()?;
+            let results = self.cached(at, "check_permissions", &(&owner, &queries), || {
+                if !self.has_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)? {
+                    return queries
+                        .iter()
+                        .map(|(user_id, permission_id)| {
+                            let granted = self.has_permission_v1(
+                                at,
+                                owner.clone(),
+                                user_id.clone(),
+                                permission_id,
+                            )?;
+                            Ok::<_, ApiError>((user_id.clone(), permission_id.clone(), granted))
+                        })
+                        .collect();
+                }
+                self.client
+                    .runtime_api()
+                    .check_permissions(at, owner.clone(), queries.clone())
+            })?;
+            Ok(results
+                .into_iter()
+                .map(|(user_id, permission_id, granted)| PermissionCheck {
+                    user_id: id_dto(user_id, self.readable_json),
+                    permission_id: id_dto(permission_id, self.readable_json),
+                    granted,
+                })
+                .collect())
+        })
+    }
+
+    fn fetch_policy_commitment(
+        &self,
+        owner: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<H256> {
+        self.measure("peaqrbac_fetchPolicyCommitment", || {
+            let at = self.block_hash(at);
+            self.ensure_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)?;
+            let api = self.client.runtime_api();
+            api.fetch_policy_commitment(at, owner).map_err(map_api_err)
+        })
+    }
+
+    fn verify_access_proof(
+        &self,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        permission_id: IdParam<EntityId>,
+        proof: AccessProof<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<bool> {
+        self.measure("peaqrbac_verifyAccessProof", || {
+            let at = self.block_hash(at);
+            self.ensure_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)?;
+            let user_id = user_id.resolve()?;
+            let permission_id = permission_id.resolve()?;
+            let api = self.client.runtime_api();
+            api.verify_access_proof(at, owner, user_id, permission_id, proof)
+                .map_err(map_api_err)
+        })
+    }
+
+    fn fetch_audit_log(
+        &self,
+        owner: AccountId,
+        from: u64,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<AuditRecordDto<AccountId, EntityId>>> {
+        self.measure("peaqrbac_fetchAuditLog", || {
+            let at = self.block_hash(at);
+            self.ensure_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)?;
+            let api = self.client.runtime_api();
+            let records = api
+                .fetch_audit_log(at, owner, from, limit)
+                .map_err(map_api_err)?;
+            Ok(records.into_dto(self.readable_json))
+        })
+    }
+
+    fn fetch_snapshots(
+        &self,
+        owner: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<SnapshotInfo>> {
+        self.measure("peaqrbac_fetchSnapshots", || {
+            let at = self.block_hash(at);
+            self.ensure_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)?;
+            let api = self.client.runtime_api();
+            api.fetch_snapshots(at, owner).map_err(map_api_err)
+        })
+    }
+
+    fn diff_draft(
+        &self,
+        owner: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<PolicyChanges<<Block as BlockT>::Hash, AccountId, EntityId>> {
+        self.measure("peaqrbac_diffDraft", || {
+            let at = self.block_hash(at);
+            self.ensure_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)?;
+            let api = self.client.runtime_api();
+            let result = api.diff_draft(at, owner.clone()).map_err(map_api_err)?;
+            self.respond(
+                result
+                    .map(|diff| PolicyChanges::from_diff(owner, at, at, diff, self.readable_json)),
+                Queried::<EntityId>::Nothing,
+            )
+        })
+    }
+
+    fn export_policy(
+        &self,
+        owner: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<PolicyExport<<Block as BlockT>::Hash, AccountId, EntityId>> {
+        self.measure("peaqrbac_exportPolicy", || {
+            let at = self.block_hash(at);
+            self.ensure_api_version::<AccountId, EntityId>(at, EXTENDED_API_VERSION)?;
+            let api = self.client.runtime_api();
+            let policy = api.export_policy(at, owner.clone()).map_err(map_api_err)?;
+            Ok(PolicyExport::new(owner, at, policy, self.readable_json))
+        })
+    }
+
+    fn diff_policy(
+        &self,
+        owner: AccountId,
+        from_block: <Block as BlockT>::Hash,
+        to_block: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<PolicyChanges<<Block as BlockT>::Hash, AccountId, EntityId>> {
+        self.measure("peaqrbac_diffPolicy", || {
+            let to_block = self.block_hash(to_block);
+            self.ensure_api_version::<AccountId, EntityId>(from_block, EXTENDED_API_VERSION)?;
+            self.ensure_api_version::<AccountId, EntityId>(to_block, EXTENDED_API_VERSION)?;
+            let api = self.client.runtime_api();
+            let from_policy = api
+                .export_policy(from_block, owner.clone())
+                .map_err(map_api_err)?;
+            let to_policy = api
+                .export_policy(to_block, owner.clone())
+                .map_err(map_api_err)?;
+            Ok(PolicyChanges::new(
+                owner,
+                from_block,
+                to_block,
+                &from_policy,
+                &to_policy,
+                self.readable_json,
+            ))
+        })
+    }
+
+    fn prove_user_roles(
+        &self,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<ProvenResult<<Block as BlockT>::Hash, Vec<Role2UserDto<EntityId>>>> {
+        self.measure("peaqrbac_proveUserRoles", || {
+            let at = self.block_hash(at);
+            let user_id = user_id.resolve()?;
+            let api = self.client.runtime_api();
+            let result = api
+                .fetch_user_roles(at, owner.clone(), user_id.clone())
+                .map_err(map_api_err)?;
+
+            let prefix = &self.pallet_prefix;
+            let mut keys = vec![proof::user_roles_storage_key(prefix, &owner, &user_id)];
+            if let Ok(roles) = &result {
+                keys.extend(
+                    roles
+                        .iter()
+                        .map(|r2u| proof::entity_storage_key(prefix, &owner, &r2u.role, Tag::Role)),
+                );
+            }
+
+            self.prove(result.map(|v| v.into_dto(self.readable_json)), keys, at)
+        })
+    }
+
+    fn prove_role_permissions(
+        &self,
+        owner: AccountId,
+        role_id: IdParam<EntityId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<ProvenResult<<Block as BlockT>::Hash, Vec<Permission2RoleDto<EntityId>>>> {
+        self.measure("peaqrbac_proveRolePermissions", || {
+            let at = self.block_hash(at);
+            let role_id = role_id.resolve()?;
+            let api = self.client.runtime_api();
+            let result = api
+                .fetch_role_permissions(at, owner.clone(), role_id.clone())
+                .map_err(map_api_err)?;
+
+            let prefix = &self.pallet_prefix;
+            let mut keys = vec![
+                proof::entity_storage_key(prefix, &owner, &role_id, Tag::Role),
+                proof::role_permissions_storage_key(prefix, &owner, &role_id),
+            ];
+            if let Ok(permissions) = &result {
+                keys.extend(permissions.iter().map(|p2r| {
+                    proof::entity_storage_key(prefix, &owner, &p2r.permission, Tag::Permission)
+                }));
+            }
+
+            self.prove(result.map(|v| v.into_dto(self.readable_json)), keys, at)
+        })
+    }
+
+    fn fetch_role_by_name(
+        &self,
+        owner: AccountId,
+        name: NameParam,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<EntityDto<EntityId>> {
+        self.measure("peaqrbac_fetchRoleByName", || {
+            let at = self.block_hash(at);
+            let name = name.into_bytes();
+            let result = self.cached(at, "fetch_role_by_name", &(&owner, &name), || {
+                let api = self.client.runtime_api();
+                if !self.has_api_version::<AccountId, EntityId>(at, NAME_LOOKUP_API_VERSION)? {
+                    let roles = api.fetch_roles(at, owner.clone())?;
+                    return Ok(find_by_name(roles, &name));
+                }
+                api.fetch_role_by_name(at, owner.clone(), name.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::<EntityId>::Name(EntityKind::Role),
+            )
+        })
+    }
+
+    fn fetch_permission_by_name(
+        &self,
+        owner: AccountId,
+        name: NameParam,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<EntityDto<EntityId>> {
+        self.measure("peaqrbac_fetchPermissionByName", || {
+            let at = self.block_hash(at);
+            let name = name.into_bytes();
+            let result = self.cached(at, "fetch_permission_by_name", &(&owner, &name), || {
+                let api = self.client.runtime_api();
+                if !self.has_api_version::<AccountId, EntityId>(at, NAME_LOOKUP_API_VERSION)? {
+                    let permissions = api.fetch_permissions(at, owner.clone())?;
+                    return Ok(find_by_name(permissions, &name));
+                }
+                api.fetch_permission_by_name(at, owner.clone(), name.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::<EntityId>::Name(EntityKind::Permission),
+            )
+        })
+    }
+
+    fn fetch_group_by_name(
+        &self,
+        owner: AccountId,
+        name: NameParam,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<EntityDto<EntityId>> {
+        self.measure("peaqrbac_fetchGroupByName", || {
+            let at = self.block_hash(at);
+            let name = name.into_bytes();
+            let result = self.cached(at, "fetch_group_by_name", &(&owner, &name), || {
+                let api = self.client.runtime_api();
+                if !self.has_api_version::<AccountId, EntityId>(at, NAME_LOOKUP_API_VERSION)? {
+                    let groups = api.fetch_groups(at, owner.clone())?;
+                    return Ok(find_by_name(groups, &name));
+                }
+                api.fetch_group_by_name(at, owner.clone(), name.clone())
+            })?;
+            self.respond(
+                result.map(|v| v.into_dto(self.readable_json)),
+                Queried::<EntityId>::Name(EntityKind::Group),
+            )
+        })
+    }
+
+    fn subscribe_policy_changes(
+        &self,
+        sink: SubscriptionSink,
+        owner: AccountId,
+        finalized: Option<bool>,
+    ) -> SubscriptionResult {
+        let at = self.block_hash(None);
+        if !matches!(
+            self.has_api_version::<AccountId, EntityId>(at, POLICY_CHANGES_API_VERSION),
+            Ok(true)
+        ) {
+            let _ = sink.reject(ErrorObject::owned(
+                Error::UnsupportedByRuntime.into(),
+                "Unsupported by runtime.",
+                None::<()>,
+            ));
+            return Ok(());
+        }
+        let client = self.client.clone();
+        let readable = self.readable_json;
+        // Changes are taken from the events of every block on the route from the block
+        // notified before, so blocks skipped by the notifications and reorgs are covered
+        let mut last = None;
+
+        let changes = move |hash: <Block as BlockT>::Hash| {
+            // The starting block only sets the position in the chain
+            let notices = match last.replace(hash) {
+                Some(from) => policy_change_notices(&*client, &owner, from, hash, readable),
+                None => Ok(Vec::new()),
+            };
+            let notices: Vec<_> = match notices {
+                Ok(notices) => notices.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(notices)
+        };
+        let stream = self
+            .block_stream(finalized.unwrap_or(self.finalized_reads))
+            .map(changes)
+            .flatten();
+
+        self.spawn_subscription(sink, stream.boxed())
+    }
+
+    fn subscribe_user_permissions(
+        &self,
+        sink: SubscriptionSink,
+        owner: AccountId,
+        user_id: IdParam<EntityId>,
+        finalized: Option<bool>,
+    ) -> SubscriptionResult {
+        let user_id = match user_id.resolve() {
+            Ok(user_id) => user_id,
+            Err(_) => {
+                let _ = sink.reject(ErrorObject::owned(
+                    Error::InvalidId.into(),
+                    "Invalid entity id.",
+                    None::<()>,
+                ));
+                return Ok(());
+            }
+        };
+        let client = self.client.clone();
+        let readable = self.readable_json;
+        let mut last: Option<Vec<Entity<EntityId>>> = None;
+
+        let changes = move |hash: <Block as BlockT>::Hash| {
+            // A user without any role has no permissions
+            let permissions = client
+                .runtime_api()
+                .fetch_user_permissions(hash, owner.clone(), user_id.clone())
+                .map(|result| result.unwrap_or_default());
+
+            let notice = match permissions {
+                Ok(permissions) if last.as_ref() != Some(&permissions) => {
+                    last = Some(permissions.clone());
+                    Some(Ok(UserPermissionsNotice {
+                        block: hash,
+                        permissions: permissions.into_dto(readable),
+                    }))
+                }
+                Ok(_) => None,
+                Err(e) => Some(Err(format!(
+                    "Permissions at block {hash:?} unavailable: {e}"
+                ))),
+            };
+            future::ready(notice)
+        };
+        let stream = self
+            .block_stream(finalized.unwrap_or(self.finalized_reads))
+            .filter_map(changes);
+
+        self.spawn_subscription(sink, stream.boxed())
     }
 }
@@ -0,0 +1,231 @@
+//! Prometheus metrics of the RPC methods.
+
+use std::{io, time::Instant};
+
+use jsonrpsee::{
+    core::{Error as JsonRpseeError, RpcResult},
+    types::error::CallError,
+};
+use prometheus_endpoint::{
+    exponential_buckets, register, CounterVec, HistogramOpts, HistogramVec, Opts, PrometheusError,
+    Registry, U64,
+};
+use serde::Serialize;
+use sp_core::H256;
+
+use peaq_pallet_rbac::{error::RbacError, structs::SnapshotInfo};
+
+use crate::{
+    dto::AuditRecordDto,
+    export::{PolicyChanges, PolicyExport},
+    PermissionCheck, ProvenResult, RbacResponse, UserResult, RBAC_ERROR_CODE_BASE,
+};
+
+/// Label of errors which are not `RbacError`s, e.g. failed runtime calls or invalid params
+const OTHER_ERROR: &str = "Other";
+
+/// One in this many answers of a method is serialised to record its size, the others are
+/// only serialised once by the server
+const RESPONSE_SIZE_SAMPLING: u64 = 16;
+
+/// Metrics of the method calls of a [`crate::PeaqRBAC`]
+#[derive(Clone)]
+pub struct RpcMetrics {
+    requests: CounterVec<U64>,
+    errors: CounterVec<U64>,
+    latency: HistogramVec,
+    response_size: HistogramVec,
+    cache_hits: CounterVec<U64>,
+    cache_misses: CounterVec<U64>,
+}
+
+impl RpcMetrics {
+    pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(RpcMetrics {
+            requests: register(
+                CounterVec::new(
+                    Opts::new(
+                        "peaqrbac_rpc_requests_total",
+                        "Number of calls of RBAC RPC methods",
+                    ),
+                    &["method"],
+                )?,
+                registry,
+            )?,
+            errors: register(
+                CounterVec::new(
+                    Opts::new(
+                        "peaqrbac_rpc_errors_total",
+                        "Number of RBAC RPC calls answered with an error and of failed items \
+                         of batch calls, by RbacErrorType",
+                    ),
+                    &["method", "error"],
+                )?,
+                registry,
+            )?,
+            latency: register(
+                HistogramVec::new(
+                    HistogramOpts::new(
+                        "peaqrbac_rpc_latency_seconds",
+                        "Time taken to answer RBAC RPC calls",
+                    )
+                    .buckets(exponential_buckets(0.0001, 4.0, 9)?),
+                    &["method"],
+                )?,
+                registry,
+            )?,
+            response_size: register(
+                HistogramVec::new(
+                    HistogramOpts::new(
+                        "peaqrbac_rpc_response_size_bytes",
+                        format!(
+                            "Size of the JSON answers of a sample of RBAC RPC calls, one in {}",
+                            RESPONSE_SIZE_SAMPLING
+                        ),
+                    )
+                    .buckets(exponential_buckets(64.0, 4.0, 9)?),
+                    &["method"],
+                )?,
+                registry,
+            )?,
+            cache_hits: register(
+                CounterVec::new(
+                    Opts::new(
+                        "peaqrbac_rpc_cache_hits_total",
+                        "Number of runtime API calls answered from the RBAC RPC cache",
+                    ),
+                    &["query"],
+                )?,
+                registry,
+            )?,
+            cache_misses: register(
+                CounterVec::new(
+                    Opts::new(
+                        "peaqrbac_rpc_cache_misses_total",
+                        "Number of runtime API calls not found in the RBAC RPC cache",
+                    ),
+                    &["query"],
+                )?,
+                registry,
+            )?,
+        })
+    }
+
+    /// Records a lookup of the runtime API `query` in the cache
+    pub(crate) fn cache_lookup(&self, query: &str, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.with_label_values(&[query]).inc();
+    }
+
+    /// Answers the call of `method`, recording its metrics
+    pub(crate) fn measure<T: Answer>(
+        &self,
+        method: &str,
+        call: impl FnOnce() -> RpcResult<T>,
+    ) -> RpcResult<T> {
+        let started = Instant::now();
+        let response = call();
+        self.latency
+            .with_label_values(&[method])
+            .observe(started.elapsed().as_secs_f64());
+        let requests = self.requests.with_label_values(&[method]);
+        requests.inc();
+
+        match &response {
+            Ok(value) => {
+                for err in value.rbac_errors() {
+                    self.errors
+                        .with_label_values(&[method, &format!("{:?}", err.typ)])
+                        .inc();
+                }
+                let mut size = ByteCounter(0);
+                if requests.get() % RESPONSE_SIZE_SAMPLING == 1
+                    && serde_json::to_writer(&mut size, value).is_ok()
+                {
+                    self.response_size
+                        .with_label_values(&[method])
+                        .observe(size.0 as f64);
+                }
+            }
+            Err(err) => self
+                .errors
+                .with_label_values(&[method, error_label(err)])
+                .inc(),
+        }
+        response
+    }
+}
+
+/// Answer of an RPC method, which may carry `RbacError`s instead of failing with one
+pub(crate) trait Answer: Serialize {
+    fn rbac_errors(&self) -> Vec<&RbacError> {
+        Vec::new()
+    }
+}
+
+impl<T: Serialize> Answer for RbacResponse<T> {
+    /// Error answered in the legacy shape, see [`crate::PeaqRBAC::with_legacy_errors`]
+    fn rbac_errors(&self) -> Vec<&RbacError> {
+        match self {
+            RbacResponse::Legacy(Err(err)) => vec![err],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl<BlockHash: Serialize, T: Serialize> Answer for ProvenResult<BlockHash, T> {
+    fn rbac_errors(&self) -> Vec<&RbacError> {
+        self.result.as_ref().err().into_iter().collect()
+    }
+}
+
+/// Errors of the items of a batch answer
+impl<T: Answer> Answer for Vec<T> {
+    fn rbac_errors(&self) -> Vec<&RbacError> {
+        self.iter().flat_map(Answer::rbac_errors).collect()
+    }
+}
+
+impl<EntityId: Serialize, T: Serialize> Answer for UserResult<EntityId, T> {
+    fn rbac_errors(&self) -> Vec<&RbacError> {
+        self.result.as_ref().err().into_iter().collect()
+    }
+}
+
+impl<EntityId: Serialize> Answer for PermissionCheck<EntityId> {}
+impl<A: Serialize, E: Serialize> Answer for AuditRecordDto<A, E> {}
+impl Answer for SnapshotInfo {}
+impl<B: Serialize, A: Serialize, E: Serialize> Answer for PolicyExport<B, A, E> {}
+impl<B: Serialize, A: Serialize, E: Serialize> Answer for PolicyChanges<B, A, E> {}
+impl Answer for H256 {}
+impl Answer for bool {}
+
+/// Writer counting the bytes of a serialised answer without keeping them
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `RbacErrorType` of the error, whose name is the message of RBAC JSON-RPC errors
+fn error_label(err: &JsonRpseeError) -> &str {
+    match err {
+        JsonRpseeError::Call(CallError::Custom(object))
+            if object.code() >= RBAC_ERROR_CODE_BASE =>
+        {
+            object.message()
+        }
+        _ => OTHER_ERROR,
+    }
+}